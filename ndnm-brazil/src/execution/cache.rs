@@ -0,0 +1,535 @@
+// ndnm-brazil/src/execution/cache.rs
+//
+// Cache de execução baseado em hash dos inputs (Fase 2 do PLAY_SYSTEM.md)
+//
+// Estrutura em disco:
+//     workspaces/runs/
+//     ├── run_2024-10-20_22-30-15_abc123/
+//     │   ├── sum-1/          (nome da pasta = node_id escapado, ver node_dir_name)
+//     │   │   ├── input.json      (input que o node recebeu, inclusive quando falhou)
+//     │   │   ├── output.json
+//     │   │   ├── input-hash.txt
+//     │   │   ├── output-hash.txt
+//     │   │   └── computed-at.txt (quando o node rodou de verdade; cópias do cache mantêm a data original)
+//     │   ├── metadata.json
+//     │   ├── snapshot.json   (grafo executado: histórico e replay)
+//     │   └── pinned          (opcional: run fixado, o Cache Cleaner não apaga)
+//     └── run_.../
+
+use super::types::{ExecutionState, GraphNode, NodeExecutionStatus, RunSnapshot};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Pasta padrão dos runs (relativa ao diretório onde o Brazil roda, igual a `workspaces/`)
+pub const DEFAULT_RUNS_DIR: &str = "workspaces/runs";
+
+const INPUT_FILE: &str = "input.json";
+const OUTPUT_FILE: &str = "output.json";
+const INPUT_HASH_FILE: &str = "input-hash.txt";
+const OUTPUT_HASH_FILE: &str = "output-hash.txt";
+const COMPUTED_AT_FILE: &str = "computed-at.txt";
+const METADATA_FILE: &str = "metadata.json";
+const SNAPSHOT_FILE: &str = "snapshot.json";
+const PINNED_FILE: &str = "pinned";
+
+/// Output encontrado no cache
+#[derive(Debug, Clone)]
+pub struct CachedOutput {
+    /// Run onde o output foi gerado/gravado
+    pub run_id: String,
+    pub output: Value,
+    pub output_hash: String,
+    /// Quando o node rodou de verdade (cache antigo sem computed-at.txt: data do input-hash.txt)
+    pub computed_at: Option<DateTime<Utc>>,
+}
+
+/// Info de um node dentro do metadata.json do run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunNodeMetadata {
+    pub node_id: String,
+    /// completed, cached, failed ou skipped (metadata antigo só tinha nodes que terminaram)
+    #[serde(default = "default_node_status")]
+    pub status: NodeExecutionStatus,
+    pub cached: bool,
+    pub duration_ms: u64,
+    /// Tentativas do /run (0 = cache; > 1 = teve retry)
+    #[serde(default)]
+    pub attempts: u32,
+    /// Erro do node (failed) ou motivo do pulo (skipped)
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl RunNodeMetadata {
+    /// Node terminou (executado ou do cache)
+    pub fn is_done(&self) -> bool {
+        matches!(self.status, NodeExecutionStatus::Completed | NodeExecutionStatus::Cached)
+    }
+}
+
+fn default_node_status() -> NodeExecutionStatus {
+    NodeExecutionStatus::Completed
+}
+
+/// Conteúdo do metadata.json de cada run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunMetadata {
+    pub run_id: String,
+    pub workspace_id: String,
+    /// Play disparado (None = EXECUTE_NODES, os nodes pedidos ficam em `selection`)
+    #[serde(default)]
+    pub play_node_id: Option<String>,
+    /// Nodes pedidos pelo EXECUTE_NODES (a listagem não precisa abrir o snapshot.json)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selection: Option<Vec<String>>,
+    pub status: ExecutionState,
+    pub started_at: String,
+    pub finished_at: String,
+    pub duration_ms: u64,
+    pub total_nodes: usize,
+    pub executed_nodes: usize,
+    pub cached_nodes: usize,
+    pub nodes: Vec<RunNodeMetadata>,
+    pub error: Option<String>,
+    /// Run que disparou este (Plays em cascata)
+    #[serde(default)]
+    pub parent_run_id: Option<String>,
+}
+
+#[cfg(test)]
+impl RunMetadata {
+    /// Metadata mínimo de um run completo (testes que gravam o cache na mão)
+    pub fn finished(run_id: &str, workspace_id: &str) -> Self {
+        let now = Utc::now().to_rfc3339();
+        Self {
+            run_id: run_id.to_string(),
+            workspace_id: workspace_id.to_string(),
+            play_node_id: Some("play-1".to_string()),
+            selection: None,
+            status: ExecutionState::Completed,
+            started_at: now.clone(),
+            finished_at: now,
+            duration_ms: 0,
+            total_nodes: 0,
+            executed_nodes: 0,
+            cached_nodes: 0,
+            nodes: Vec::new(),
+            error: None,
+            parent_run_id: None,
+        }
+    }
+}
+
+/// Cache de outputs por node, gravado em pastas de run
+#[derive(Debug, Clone)]
+pub struct RunCache {
+    root: PathBuf,
+}
+
+impl RunCache {
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Pasta raiz dos runs
+    #[cfg(test)]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Pasta de um run
+    pub fn run_dir(&self, run_id: &str) -> PathBuf {
+        self.root.join(run_id)
+    }
+
+    /// Pasta de um node dentro de um run
+    pub fn node_dir(&self, run_id: &str, node_id: &str) -> PathBuf {
+        self.run_dir(run_id).join(node_dir_name(node_id))
+    }
+
+    /// Run terminado deste workspace (run sem metadata.json ainda não dá pra saber: fica de fora)
+    fn run_in_workspace(&self, run_id: &str, workspace_id: &str) -> bool {
+        self.read_metadata(run_id).is_some_and(|m| m.workspace_id == workspace_id)
+    }
+
+    /// Hash dos inputs de um node: tipo + dados estáticos + hashes dos outputs conectados nele.
+    ///
+    /// `upstream` é a lista (to_input_index, from_output_index, output_hash do node de origem).
+    /// Se um node anterior mudou, o hash dele muda e invalida toda a cadeia abaixo.
+    pub fn input_hash(node: &GraphNode, upstream: &[(usize, usize, String)]) -> String {
+        let mut upstream = upstream.to_vec();
+        upstream.sort();
+
+        // serde_json ordena as chaves dos objetos → serialização determinística
+        let payload = json!({
+            "node_type": node.node_type,
+            "data": node.data,
+            "upstream": upstream,
+        });
+
+        hash_value(&payload)
+    }
+
+    /// Procura, do run mais recente pro mais antigo, um output desse node com o mesmo hash de input.
+    /// Só runs do mesmo workspace contam: os ids do editor (n1, n2, ...) se repetem entre workspaces.
+    /// Bloqueante (lê todas as pastas de run): chamar fora do runtime async.
+    pub fn lookup(&self, workspace_id: &str, node_id: &str, input_hash: &str) -> Option<CachedOutput> {
+        self.list_runs().into_iter().find_map(|run_id| {
+            let stored_hash = fs::read_to_string(self.node_dir(&run_id, node_id).join(INPUT_HASH_FILE)).ok()?;
+            if stored_hash.trim() != input_hash || !self.run_in_workspace(&run_id, workspace_id) {
+                return None;
+            }
+            self.read_cached(run_id, node_id)
+        })
+    }
+
+    /// Output gravado de um node num run (o hash de input já foi conferido)
+    fn read_cached(&self, run_id: String, node_id: &str) -> Option<CachedOutput> {
+        let node_dir = self.node_dir(&run_id, node_id);
+        let output_str = fs::read_to_string(node_dir.join(OUTPUT_FILE)).ok()?;
+        let output: Value = serde_json::from_str(&output_str).ok()?;
+        let output_hash = fs::read_to_string(node_dir.join(OUTPUT_HASH_FILE))
+            .map(|h| h.trim().to_string())
+            .unwrap_or_else(|_| hash_value(&output));
+        let computed_at = fs::read_to_string(node_dir.join(COMPUTED_AT_FILE))
+            .ok()
+            .and_then(|at| DateTime::parse_from_rfc3339(at.trim()).ok())
+            .map(|at| at.with_timezone(&Utc))
+            .or_else(|| {
+                let modified = fs::metadata(node_dir.join(INPUT_HASH_FILE)).ok()?.modified().ok()?;
+                Some(DateTime::<Utc>::from(modified))
+            });
+
+        Some(CachedOutput {
+            run_id,
+            output,
+            output_hash,
+            computed_at,
+        })
+    }
+
+    /// Grava output + hashes de um node que acabou de rodar. Retorna o hash do output.
+    pub fn store(&self, run_id: &str, node_id: &str, input_hash: &str, output: &Value) -> Result<String, String> {
+        self.write_node(run_id, node_id, input_hash, output, Utc::now())
+    }
+
+    /// Copia um output do cache pro run atual, mantendo a data em que o node rodou (TTL)
+    pub fn store_hit(&self, run_id: &str, node_id: &str, input_hash: &str, hit: &CachedOutput) -> Result<String, String> {
+        self.write_node(run_id, node_id, input_hash, &hit.output, hit.computed_at.unwrap_or_else(Utc::now))
+    }
+
+    fn write_node(
+        &self,
+        run_id: &str,
+        node_id: &str,
+        input_hash: &str,
+        output: &Value,
+        computed_at: DateTime<Utc>,
+    ) -> Result<String, String> {
+        let node_dir = self.node_dir(run_id, node_id);
+        fs::create_dir_all(&node_dir)
+            .map_err(|e| format!("Erro ao criar pasta de cache {:?}: {}", node_dir, e))?;
+
+        let output_hash = hash_value(output);
+        let output_str = serde_json::to_string_pretty(output)
+            .map_err(|e| format!("Erro ao serializar output do node {}: {}", node_id, e))?;
+
+        fs::write(node_dir.join(OUTPUT_FILE), output_str)
+            .and_then(|_| fs::write(node_dir.join(INPUT_HASH_FILE), input_hash))
+            .and_then(|_| fs::write(node_dir.join(OUTPUT_HASH_FILE), &output_hash))
+            .and_then(|_| fs::write(node_dir.join(COMPUTED_AT_FILE), computed_at.to_rfc3339()))
+            .map_err(|e| format!("Erro ao gravar cache do node {}: {}", node_id, e))?;
+
+        Ok(output_hash)
+    }
+
+    /// Grava o input que o node recebeu neste run (histórico; não entra no cache)
+    pub fn store_input(&self, run_id: &str, node_id: &str, input: &Value) -> Result<(), String> {
+        let node_dir = self.node_dir(run_id, node_id);
+        fs::create_dir_all(&node_dir)
+            .map_err(|e| format!("Erro ao criar pasta de cache {:?}: {}", node_dir, e))?;
+        let content = serde_json::to_string_pretty(input)
+            .map_err(|e| format!("Erro ao serializar input do node {}: {}", node_id, e))?;
+        fs::write(node_dir.join(INPUT_FILE), content)
+            .map_err(|e| format!("Erro ao gravar input do node {}: {}", node_id, e))
+    }
+
+    /// Input e output gravados de um node num run
+    pub fn read_node_io(&self, run_id: &str, node_id: &str) -> (Option<Value>, Option<Value>) {
+        let node_dir = self.node_dir(run_id, node_id);
+        let read = |file: &str| {
+            let content = fs::read_to_string(node_dir.join(file)).ok()?;
+            serde_json::from_str(&content).ok()
+        };
+        (read(INPUT_FILE), read(OUTPUT_FILE))
+    }
+
+    /// Grava o snapshot.json do run (o grafo que ele executou)
+    pub fn write_snapshot(&self, run_id: &str, snapshot: &RunSnapshot) -> Result<(), String> {
+        let run_dir = self.run_dir(run_id);
+        fs::create_dir_all(&run_dir)
+            .map_err(|e| format!("Erro ao criar pasta do run {:?}: {}", run_dir, e))?;
+        let content = serde_json::to_string_pretty(snapshot)
+            .map_err(|e| format!("Erro ao serializar snapshot: {}", e))?;
+        fs::write(run_dir.join(SNAPSHOT_FILE), content)
+            .map_err(|e| format!("Erro ao gravar snapshot do run {}: {}", run_id, e))
+    }
+
+    /// Snapshot de um run (None = run gravado antes do histórico existir)
+    pub fn read_snapshot(&self, run_id: &str) -> Option<RunSnapshot> {
+        let content = fs::read_to_string(self.run_dir(run_id).join(SNAPSHOT_FILE)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn has_snapshot(&self, run_id: &str) -> bool {
+        self.run_dir(run_id).join(SNAPSHOT_FILE).is_file()
+    }
+
+    /// Grava o metadata.json do run
+    pub fn write_metadata(&self, metadata: &RunMetadata) -> Result<(), String> {
+        let run_dir = self.run_dir(&metadata.run_id);
+        fs::create_dir_all(&run_dir)
+            .map_err(|e| format!("Erro ao criar pasta do run {:?}: {}", run_dir, e))?;
+
+        let content = serde_json::to_string_pretty(metadata)
+            .map_err(|e| format!("Erro ao serializar metadata: {}", e))?;
+        fs::write(run_dir.join(METADATA_FILE), content)
+            .map_err(|e| format!("Erro ao gravar metadata do run {}: {}", metadata.run_id, e))
+    }
+
+    /// Lista os run_ids existentes, do mais recente pro mais antigo.
+    /// O run_id começa com o timestamp, então ordem alfabética = ordem cronológica.
+    pub fn list_runs(&self) -> Vec<String> {
+        let mut runs: Vec<String> = match fs::read_dir(&self.root) {
+            Ok(entries) => entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_dir())
+                .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
+                .filter(|name| name.starts_with("run_"))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        runs.sort();
+        runs.reverse();
+        runs
+    }
+
+    /// Lê o metadata.json de um run (None = run em andamento ou pasta quebrada)
+    pub fn read_metadata(&self, run_id: &str) -> Option<RunMetadata> {
+        let content = fs::read_to_string(self.run_dir(run_id).join(METADATA_FILE)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// run_id aceito vindo de fora (HTTP/WS): formato gerado pelo engine, sem separador de caminho
+    pub fn is_valid_run_id(run_id: &str) -> bool {
+        run_id.starts_with("run_") && run_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+
+    /// Fixa (ou solta) um run: fixado nunca é apagado pelo Cache Cleaner
+    pub fn set_pinned(&self, run_id: &str, pinned: bool) -> Result<(), String> {
+        let run_dir = self.run_dir(run_id);
+        if !Self::is_valid_run_id(run_id) || !run_dir.is_dir() {
+            return Err(format!("Run não encontrado: {}", run_id));
+        }
+
+        let marker = run_dir.join(PINNED_FILE);
+        let result = if pinned {
+            fs::write(&marker, Utc::now().to_rfc3339())
+        } else if marker.exists() {
+            fs::remove_file(&marker)
+        } else {
+            Ok(())
+        };
+        result.map_err(|e| format!("Erro ao atualizar pin do run {}: {}", run_id, e))
+    }
+
+    pub fn is_pinned(&self, run_id: &str) -> bool {
+        self.run_dir(run_id).join(PINNED_FILE).exists()
+    }
+
+    /// Apaga a pasta de um run inteira. Retorna quantos bytes foram liberados.
+    pub fn delete_run(&self, run_id: &str) -> Result<u64, String> {
+        let run_dir = self.run_dir(run_id);
+        let bytes = dir_size(&run_dir);
+        fs::remove_dir_all(&run_dir).map_err(|e| format!("Erro ao apagar run {}: {}", run_id, e))?;
+        Ok(bytes)
+    }
+
+    /// Tamanho em disco de um run
+    pub fn run_size(&self, run_id: &str) -> u64 {
+        dir_size(&self.run_dir(run_id))
+    }
+
+    /// Invalida o cache de um node do workspace: apaga o input-hash.txt dele nos runs do workspace,
+    /// então o próximo Play executa o node de novo. Os outputs continuam lá (histórico).
+    /// Retorna quantos runs tinham cache do node.
+    pub fn invalidate_node(&self, workspace_id: &str, node_id: &str) -> usize {
+        self.list_runs()
+            .iter()
+            .filter(|run_id| {
+                let hash_file = self.node_dir(run_id, node_id).join(INPUT_HASH_FILE);
+                hash_file.exists() && self.run_in_workspace(run_id, workspace_id) && fs::remove_file(hash_file).is_ok()
+            })
+            .count()
+    }
+
+    /// Output mais recente gravado de um node do workspace (mesmo invalidado: o output continua lá)
+    pub fn latest_output(&self, workspace_id: &str, node_id: &str) -> Option<Value> {
+        self.list_runs().into_iter().find_map(|run_id| {
+            let content = fs::read_to_string(self.node_dir(&run_id, node_id).join(OUTPUT_FILE)).ok()?;
+            let output = serde_json::from_str(&content).ok()?;
+            self.run_in_workspace(&run_id, workspace_id).then_some(output)
+        })
+    }
+}
+
+impl Default for RunCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_RUNS_DIR)
+    }
+}
+
+/// Soma do tamanho dos arquivos de uma pasta (recursivo)
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else { return 0 };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| match e.metadata() {
+            Ok(m) if m.is_dir() => dir_size(&e.path()),
+            Ok(m) => m.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+/// Hash (blake3, hex) de um valor JSON
+pub fn hash_value(value: &Value) -> String {
+    let bytes = serde_json::to_vec(value).unwrap_or_default();
+    blake3::hash(&bytes).to_hex().to_string()
+}
+
+/// Node IDs viram nomes de pasta. Escapa com %XX (bytes UTF-8) tudo que não for minúscula, número,
+/// `-`, `_` ou `.` no meio: ids diferentes nunca caem na mesma pasta (`a/b`, `a:b` e `a_b` ficam
+/// separados), nem em disco que não diferencia maiúscula (Windows), e `..` não sai do run.
+fn node_dir_name(node_id: &str) -> String {
+    let last = node_id.len().saturating_sub(1);
+    let mut name = String::with_capacity(node_id.len());
+    for (i, byte) in node_id.bytes().enumerate() {
+        let safe = byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'-' || byte == b'_'
+            || (byte == b'.' && i != 0 && i != last);
+        if safe {
+            name.push(byte as char);
+        } else {
+            name.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::types::test_graph::node_on;
+
+    /// metadata.json de um run terminado (o lookup só considera runs com metadata)
+    fn finish_run(cache: &RunCache, run_id: &str, workspace_id: &str) {
+        cache.write_metadata(&RunMetadata::finished(run_id, workspace_id)).unwrap();
+    }
+
+    fn setup_cache(dir_name: &str) -> RunCache {
+        let root = std::env::temp_dir().join(dir_name);
+        if root.exists() {
+            fs::remove_dir_all(&root).unwrap();
+        }
+        RunCache::new(root)
+    }
+
+    fn node(data: Value) -> GraphNode {
+        node_on("sum-1", "add", 3000, data)
+    }
+
+    #[test]
+    fn test_input_hash_changes_with_data_and_upstream() {
+        let a = RunCache::input_hash(&node(json!({"variables": [1, 2]})), &[]);
+        let b = RunCache::input_hash(&node(json!({"variables": [1, 2]})), &[]);
+        let c = RunCache::input_hash(&node(json!({"variables": [1, 3]})), &[]);
+        let d = RunCache::input_hash(&node(json!({"variables": [1, 2]})), &[(0, 0, "abc".to_string())]);
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_ne!(a, d);
+    }
+
+    #[test]
+    fn test_store_and_lookup_by_workspace() {
+        let cache = setup_cache("ndnm_test_run_cache_lookup");
+        let output = json!({"response": 35});
+
+        cache.store("run_2024-01-01_00-00-00_aaaa", "sum-1", "hash-old", &json!({"response": 1})).unwrap();
+        finish_run(&cache, "run_2024-01-01_00-00-00_aaaa", "ws-a");
+        let output_hash = cache.store("run_2024-01-02_00-00-00_bbbb", "sum-1", "hash-new", &output).unwrap();
+        finish_run(&cache, "run_2024-01-02_00-00-00_bbbb", "ws-a");
+        // Outro workspace com o mesmo id de node (e o mesmo hash) num run mais novo
+        cache.store("run_2024-01-03_00-00-00_cccc", "sum-1", "hash-new", &json!({"response": 99})).unwrap();
+        finish_run(&cache, "run_2024-01-03_00-00-00_cccc", "ws-b");
+
+        let hit = cache.lookup("ws-a", "sum-1", "hash-new").unwrap();
+        assert_eq!(hit.run_id, "run_2024-01-02_00-00-00_bbbb");
+        assert_eq!(hit.output, output);
+        assert_eq!(hit.output_hash, output_hash);
+
+        // Runs mais antigos também valem (não só o mais recente)
+        assert_eq!(cache.lookup("ws-a", "sum-1", "hash-old").unwrap().run_id, "run_2024-01-01_00-00-00_aaaa");
+        assert_eq!(cache.lookup("ws-b", "sum-1", "hash-new").unwrap().output, json!({"response": 99}));
+        assert!(cache.lookup("ws-b", "sum-1", "hash-old").is_none());
+
+        // Run sem metadata (em andamento) e node que nunca rodou
+        cache.store("run_2024-01-04_00-00-00_dddd", "sum-1", "hash-running", &output).unwrap();
+        assert!(cache.lookup("ws-a", "sum-1", "hash-running").is_none());
+        assert!(cache.lookup("ws-a", "subtract-1", "hash-new").is_none());
+
+        fs::remove_dir_all(cache.root()).unwrap();
+    }
+
+    #[test]
+    fn test_invalidate_node_and_pin() {
+        let cache = setup_cache("ndnm_test_run_cache_invalidate");
+        for (run_id, workspace_id) in [
+            ("run_2024-01-01_00-00-00_aaaa", "ws-a"),
+            ("run_2024-01-02_00-00-00_bbbb", "ws-a"),
+            ("run_2024-01-03_00-00-00_cccc", "ws-b"),
+        ] {
+            cache.store(run_id, "sum-1", "hash", &json!({"response": 1})).unwrap();
+            finish_run(&cache, run_id, workspace_id);
+        }
+        assert!(cache.lookup("ws-a", "sum-1", "hash").is_some());
+
+        // Some de todos os runs do workspace (não cai pro run mais antigo); outro workspace não muda
+        assert_eq!(cache.invalidate_node("ws-a", "sum-1"), 2);
+        assert!(cache.lookup("ws-a", "sum-1", "hash").is_none());
+        assert!(cache.lookup("ws-b", "sum-1", "hash").is_some());
+        assert!(cache.node_dir("run_2024-01-02_00-00-00_bbbb", "sum-1").join(OUTPUT_FILE).exists());
+
+        cache.set_pinned("run_2024-01-01_00-00-00_aaaa", true).unwrap();
+        assert!(cache.is_pinned("run_2024-01-01_00-00-00_aaaa"));
+        cache.set_pinned("run_2024-01-01_00-00-00_aaaa", false).unwrap();
+        assert!(!cache.is_pinned("run_2024-01-01_00-00-00_aaaa"));
+        assert!(cache.set_pinned("run_../../etc", true).is_err());
+
+        fs::remove_dir_all(cache.root()).unwrap();
+    }
+
+    #[test]
+    fn test_node_dir_name() {
+        assert_eq!(node_dir_name("node-sum-1"), "node-sum-1");
+        assert_eq!(node_dir_name("../etc/passwd"), "%2E.%2Fetc%2Fpasswd");
+        assert_eq!(node_dir_name("v1.2."), "v1.2%2E");
+
+        let names: std::collections::HashSet<String> =
+            ["a/b", "a:b", "a_b", "a%2Fb", "A_b", "a_B"].iter().map(|id| node_dir_name(id).to_lowercase()).collect();
+        assert_eq!(names.len(), 6);
+    }
+}
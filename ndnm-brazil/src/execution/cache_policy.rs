@@ -0,0 +1,175 @@
+// ndnm-brazil/src/execution/cache_policy.rs
+//
+// Política de cache por node (seção `cache:` do config.yaml do node)
+// - pure: mesmo hash de input = output do cache (padrão, comportamento da Fase 2)
+// - volatile: sempre roda (node-list-directory, node-fs-browser: leem o disco na hora)
+// - ttl: cache vale por `ttl_seconds` desde que o node rodou de verdade
+// - content_addressed: os arquivos citados no input entram no hash (mtime+tamanho ou blake3),
+//   então trocar o arquivo sem mudar o caminho invalida o cache (node-load-checkpoint)
+
+use super::cache::{hash_value, CachedOutput};
+use chrono::{DateTime, Utc};
+use ndnm_core::{CacheMode, CachePolicyConfig, FileKey};
+use serde_json::{json, Value};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// Política efetiva de um node
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum CachePolicy {
+    #[default]
+    Pure,
+    Volatile,
+    Ttl(chrono::Duration),
+    ContentAddressed { file_fields: Vec<String>, file_key: FileKey },
+}
+
+impl CachePolicy {
+    pub fn from_config(config: Option<&CachePolicyConfig>) -> Self {
+        let Some(config) = config else { return Self::Pure };
+
+        match config.policy.unwrap_or(CacheMode::Pure) {
+            CacheMode::Pure => Self::Pure,
+            CacheMode::Volatile => Self::Volatile,
+            // ttl sem ttl_seconds não tem como expirar: mais seguro nunca usar o cache
+            CacheMode::Ttl => match config.ttl_seconds.and_then(|s| chrono::Duration::try_seconds(s as i64)) {
+                Some(ttl) => Self::Ttl(ttl),
+                None => Self::Volatile,
+            },
+            CacheMode::ContentAddressed => Self::ContentAddressed {
+                file_fields: config.file_fields.clone(),
+                file_key: config.file_key.unwrap_or(FileKey::MtimeSize),
+            },
+        }
+    }
+
+    /// O node pode vir do cache?
+    pub fn uses_cache(&self) -> bool {
+        *self != Self::Volatile
+    }
+
+    /// O output do cache ainda vale? (só o ttl olha a data)
+    pub fn accepts(&self, hit: &CachedOutput, now: DateTime<Utc>) -> bool {
+        match self {
+            Self::Volatile => false,
+            Self::Ttl(ttl) => hit.computed_at.is_some_and(|at| now - at < *ttl),
+            Self::Pure | Self::ContentAddressed { .. } => true,
+        }
+    }
+
+    /// Hash de input final: o hash do grafo + identidade dos arquivos do input (content_addressed).
+    /// Bloqueante (lê o disco): chamar fora do runtime async.
+    pub fn cache_key(&self, input: &Value, input_hash: String) -> String {
+        let Self::ContentAddressed { file_fields, file_key } = self else { return input_hash };
+
+        let paths: Vec<&str> = match input.as_object() {
+            Some(fields) if file_fields.is_empty() => fields
+                .values()
+                .filter_map(Value::as_str)
+                .filter(|path| Path::new(path).is_file())
+                .collect(),
+            Some(fields) => file_fields.iter().filter_map(|name| fields.get(name)?.as_str()).collect(),
+            None => Vec::new(),
+        };
+        if paths.is_empty() {
+            return input_hash;
+        }
+
+        let files: Vec<Value> = paths.iter().map(|path| file_identity(Path::new(path), *file_key)).collect();
+        hash_value(&json!({ "input_hash": input_hash, "files": files }))
+    }
+}
+
+/// Identidade de um arquivo pro hash (arquivo que não existe também entra: quando aparecer, muda)
+fn file_identity(path: &Path, key: FileKey) -> Value {
+    let Ok(metadata) = fs::metadata(path) else {
+        return json!({ "path": path, "missing": true });
+    };
+
+    match key {
+        FileKey::MtimeSize => {
+            let mtime = metadata
+                .modified()
+                .ok()
+                .and_then(|m| m.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| d.as_nanos().to_string());
+            json!({ "path": path, "mtime": mtime, "size": metadata.len() })
+        }
+        FileKey::ContentHash => json!({ "path": path, "blake3": content_hash(path) }),
+    }
+}
+
+/// blake3 do arquivo em blocos (checkpoints têm GBs, não dá pra ler tudo na memória)
+fn content_hash(path: &Path) -> Option<String> {
+    let mut file = fs::File::open(path).ok()?;
+    let mut hasher = blake3::Hasher::new();
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        let read = file.read(&mut buffer).ok()?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Some(hasher.finalize().to_hex().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hit(computed_at: DateTime<Utc>) -> CachedOutput {
+        CachedOutput {
+            run_id: "run_x".to_string(),
+            output: json!(1),
+            output_hash: String::new(),
+            computed_at: Some(computed_at),
+        }
+    }
+
+    #[test]
+    fn test_from_config_and_accepts() {
+        let now = Utc::now();
+        let ttl = CachePolicy::from_config(Some(&CachePolicyConfig {
+            policy: Some(CacheMode::Ttl),
+            ttl_seconds: Some(60),
+            ..Default::default()
+        }));
+        assert!(ttl.accepts(&hit(now - chrono::Duration::seconds(30)), now));
+        assert!(!ttl.accepts(&hit(now - chrono::Duration::seconds(90)), now));
+
+        let no_ttl = CachePolicyConfig { policy: Some(CacheMode::Ttl), ..Default::default() };
+        assert_eq!(CachePolicy::from_config(Some(&no_ttl)), CachePolicy::Volatile);
+        assert!(!CachePolicy::Volatile.uses_cache());
+        assert_eq!(CachePolicy::from_config(None), CachePolicy::Pure);
+    }
+
+    #[test]
+    fn test_content_addressed_key_follows_file() {
+        let dir = std::env::temp_dir().join("ndnm_test_cache_policy");
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("model.safetensors");
+        fs::write(&file, b"v1").unwrap();
+        let input = json!({ "file_path": file.to_string_lossy() });
+
+        for file_key in [FileKey::MtimeSize, FileKey::ContentHash] {
+            let policy = CachePolicy::ContentAddressed { file_fields: vec![], file_key };
+            fs::write(&file, b"v1").unwrap();
+            let first = policy.cache_key(&input, "hash".to_string());
+            assert_ne!(first, "hash");
+            assert_eq!(policy.cache_key(&input, "hash".to_string()), first);
+
+            // Mesmo caminho, conteúdo (e tamanho) novo: hash muda
+            fs::write(&file, b"version 2").unwrap();
+            assert_ne!(policy.cache_key(&input, "hash".to_string()), first);
+        }
+
+        // Campo listado que não está no input: fica o hash do grafo
+        let named = CachePolicy::ContentAddressed { file_fields: vec!["other".to_string()], file_key: FileKey::MtimeSize };
+        assert_eq!(named.cache_key(&input, "hash".to_string()), "hash");
+        assert_eq!(CachePolicy::Pure.cache_key(&input, "hash".to_string()), "hash");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
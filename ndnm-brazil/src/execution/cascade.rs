@@ -0,0 +1,224 @@
+// ndnm-brazil/src/execution/cascade.rs
+//
+// Plays em cascata (Fase 4 do PLAY_SYSTEM.md)
+// Quando a cadeia de um Play termina, os Plays ligados na saída dele rodam em seguida,
+// cada um num run próprio (cache isolado), todos pendurados no run do primeiro Play.
+//
+//     [X] → [Play A] → [Y] → [Play B]
+//
+// run pai  = cadeia do Play A (X)
+// run filho = cadeia do Play B (Y), recebendo o output do Play A
+
+use super::error::ExecutionError;
+use super::executor::{generate_run_id, ExecutionEngine, RunContext};
+use super::types::{ChildRun, ExecutionRequest, ExecutionResult, ExecutionState, GraphNode, WorkflowGraph};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Máximo de runs filhos numa cascata (proteção contra loops entre Plays)
+pub const MAX_CASCADE_RUNS: usize = 32;
+
+/// Plays disparados pela saída de um Play: segue as conexões pra frente,
+/// passando por nodes comuns e parando no primeiro Play de cada caminho.
+pub fn downstream_plays<'a>(graph: &'a WorkflowGraph, play_id: &str) -> Vec<&'a GraphNode> {
+    let node_map: HashMap<&str, &GraphNode> = graph.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+
+    let mut plays = Vec::new();
+    let mut seen: HashSet<&str> = HashSet::from([play_id]);
+    let mut queue: VecDeque<&str> = VecDeque::from([play_id]);
+
+    while let Some(current) = queue.pop_front() {
+        // Ordena as conexões pelo destino (determinístico)
+        let mut targets: Vec<&str> = graph
+            .connections
+            .iter()
+            .filter(|c| c.from_node_id == current)
+            .map(|c| c.to_node_id.as_str())
+            .collect();
+        targets.sort();
+
+        for target in targets {
+            if !seen.insert(target) {
+                continue;
+            }
+            let Some(node) = node_map.get(target) else { continue };
+            if node.is_play() {
+                plays.push(*node);
+            } else {
+                queue.push_back(target);
+            }
+        }
+    }
+
+    plays
+}
+
+/// Fim da cadeia ligada na saída de um Play: nodes comuns alcançados por ela que não ligam em nada.
+/// Play com payload de gatilho (webhook, file watcher) roda até eles no próprio run,
+/// então o payload chega nos nodes depois dele mesmo sem outro Play no caminho.
+pub fn downstream_tails(graph: &WorkflowGraph, play_id: &str) -> Vec<String> {
+    let node_map: HashMap<&str, &GraphNode> = graph.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+    let mut targets_of: HashMap<&str, Vec<&str>> = HashMap::new();
+    for conn in &graph.connections {
+        targets_of.entry(conn.from_node_id.as_str()).or_default().push(conn.to_node_id.as_str());
+    }
+
+    let mut tails = Vec::new();
+    let mut seen: HashSet<&str> = HashSet::from([play_id]);
+    let mut queue: VecDeque<&str> = VecDeque::from([play_id]);
+
+    while let Some(current) = queue.pop_front() {
+        for &target in targets_of.get(current).into_iter().flatten() {
+            if !seen.insert(target) || node_map.get(target).is_none_or(|node| node.is_play()) {
+                continue;
+            }
+            if !targets_of.contains_key(target) {
+                tails.push(target.to_string());
+            }
+            queue.push_back(target);
+        }
+    }
+
+    tails.sort();
+    tails
+}
+
+impl ExecutionEngine {
+    /// Executa o Play pedido e, se ele terminar com sucesso, os Plays em cascata.
+    /// O resultado é o do run pai, com os filhos em `child_runs`.
+    pub async fn execute_cascade(
+        &self,
+        request: ExecutionRequest,
+        context: RunContext,
+    ) -> Result<ExecutionResult, ExecutionError> {
+        // EXECUTE_NODES não tem Play: não dispara Plays em cascata
+        let Some(root_play) = request.play_node_id.clone().filter(|_| request.selection.is_none()) else {
+            return self.execute_run(request, context).await;
+        };
+
+        let events = context.events.clone();
+        let cancel = context.cancel.clone();
+        let children = context.children.clone();
+        // Replay de um run filho já chega com os outputs dos Plays anteriores
+        let mut play_outputs = context.seed.clone();
+
+        let mut root = self.execute_run(request.clone(), context).await?;
+        if root.status != ExecutionState::Completed {
+            return Ok(root);
+        }
+
+        // Outputs de todos os Plays que já terminaram na cascata (entram como seed nos filhos)
+        if let Some(output) = root.output.clone() {
+            play_outputs.insert(root_play.clone(), output);
+        }
+
+        let mut visited: HashSet<String> = HashSet::from([root_play.clone()]);
+        let mut queue: VecDeque<(String, String)> = downstream_plays(&request.graph, &root_play)
+            .into_iter()
+            .map(|play| (play.id.clone(), root_play.clone()))
+            .collect();
+
+        while let Some((play_id, triggered_by)) = queue.pop_front() {
+            if cancel.is_cancelled() {
+                root.status = ExecutionState::Cancelled;
+                break;
+            }
+            // Mesmo Play alcançado por dois caminhos roda uma vez só
+            if !visited.insert(play_id.clone()) {
+                continue;
+            }
+            if root.child_runs.len() >= MAX_CASCADE_RUNS {
+                println!("⚠️  Cascata parou: limite de {} runs filhos atingido", MAX_CASCADE_RUNS);
+                break;
+            }
+
+            println!("🔗 Cascata: {} disparou {}", triggered_by, play_id);
+
+            let child_context = RunContext {
+                run_id: generate_run_id(),
+                events: events.clone(),
+                cancel: cancel.child_token(),
+                seed: play_outputs.clone(),
+                parent_run_id: Some(root.run_id.clone()),
+                children: children.clone(),
+            };
+            let child_run_id = child_context.run_id.clone();
+            children.insert(&child_run_id, child_context.cancel.clone());
+
+            let mut child_request = request.clone();
+            child_request.play_node_id = Some(play_id.clone());
+            // O payload do gatilho é só do Play raiz (nos filhos ele chega pelo seed)
+            child_request.trigger_payload = None;
+
+            let outcome = self.execute_run(child_request, child_context).await;
+            children.remove(&child_run_id);
+
+            let child = match outcome {
+                Ok(result) => {
+                    let completed = result.status == ExecutionState::Completed;
+                    if completed {
+                        if let Some(output) = result.output.clone() {
+                            play_outputs.insert(play_id.clone(), output);
+                        }
+                        for next in downstream_plays(&request.graph, &play_id) {
+                            queue.push_back((next.id.clone(), play_id.clone()));
+                        }
+                    }
+                    ChildRun {
+                        run_id: result.run_id,
+                        play_node_id: play_id,
+                        triggered_by,
+                        status: result.status,
+                        executed_nodes: result.executed_nodes,
+                        cached_nodes: result.cached_nodes,
+                        duration_ms: result.duration_ms,
+                        error: result.error,
+                        output: result.output,
+                    }
+                }
+                // Filho que falhou não derruba a cascata inteira, só não dispara os Plays depois dele
+                Err(error) => ChildRun {
+                    run_id: child_run_id,
+                    play_node_id: play_id,
+                    triggered_by,
+                    status: ExecutionState::Failed,
+                    executed_nodes: 0,
+                    cached_nodes: 0,
+                    duration_ms: 0,
+                    error: Some(error.message),
+                    output: None,
+                },
+            };
+            root.child_runs.push(child);
+        }
+
+        Ok(root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::types::test_graph::{conn, node};
+
+    #[test]
+    fn test_downstream_plays_stop_at_first_play() {
+        // A → Y → B → Z → C   e   A → D (direto)
+        let graph = WorkflowGraph {
+            nodes: vec![
+                node("A", "playButton"),
+                node("Y", "add"),
+                node("B", "playButton"),
+                node("Z", "add"),
+                node("C", "comfyPlay"),
+                node("D", "playButton"),
+            ],
+            connections: vec![conn("A", "Y"), conn("Y", "B"), conn("B", "Z"), conn("Z", "C"), conn("A", "D")],
+        };
+
+        let ids: Vec<&str> = downstream_plays(&graph, "A").iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(ids, vec!["D", "B"]);
+
+        let from_b: Vec<&str> = downstream_plays(&graph, "B").iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(from_b, vec!["C"]);
+    }
+}
@@ -0,0 +1,225 @@
+// ndnm-brazil/src/execution/dataflow.rs
+//
+// Camada de dataflow do run
+// Guarda o output de cada node e monta o input dos próximos a partir das conexões
+
+use super::types::{Connection, GraphNode};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// Campo do input que recebe os valores vindos das conexões.
+/// É a convenção dos nodes com entradas dinâmicas (sum/subtract): `{"variables": [...]}`,
+/// onde a posição no array é o `to_input_index` da conexão.
+pub const WIRED_INPUTS_FIELD: &str = "variables";
+
+/// Campo opcional de output para nodes com múltiplas saídas distintas
+/// (`{"outputs": [a, b, ...]}` → saída 0 = a, saída 1 = b)
+pub const MULTI_OUTPUTS_FIELD: &str = "outputs";
+
+/// Armazena os outputs dos nodes já executados durante um run
+#[derive(Debug, Default, Clone)]
+pub struct ValueStore {
+    /// Mapa de node_id → output JSON retornado pelo /run do node
+    outputs: HashMap<String, Value>,
+}
+
+impl ValueStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registra o output de um node
+    pub fn insert(&mut self, node_id: &str, output: Value) {
+        self.outputs.insert(node_id.to_string(), output);
+    }
+
+    /// Output completo de um node (se já executou)
+    pub fn get(&self, node_id: &str) -> Option<&Value> {
+        self.outputs.get(node_id)
+    }
+
+    /// Monta o input final de um node: dados estáticos (`GraphNode::data`)
+    /// sobrescritos pelos valores que chegam pelas conexões.
+    ///
+    /// Exemplo (test_execution.json):
+    ///     sum-1 → {"response": 35}
+    ///     subtract-1.data = {"variables": [100, 15]}
+    ///     conexão sum-1[0] → subtract-1[0]
+    ///
+    /// Resultado: {"variables": [35, 15]}
+    pub fn resolve_input(&self, node: &GraphNode, connections: &[Connection]) -> Result<Value, String> {
+        let mut input: Map<String, Value> = node
+            .data
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+
+        if !connections.iter().any(|c| c.to_node_id == node.id) {
+            return Ok(Value::Object(input));
+        }
+
+        let wired = match input.remove(WIRED_INPUTS_FIELD) {
+            Some(Value::Array(values)) => values,
+            _ => Vec::new(),
+        };
+        let wired = self.wired_values(node, connections, wired)?;
+
+        input.insert(WIRED_INPUTS_FIELD.to_string(), Value::Array(wired));
+        Ok(Value::Object(input))
+    }
+
+    /// Output de um Play node: repassa o que chegou em cada entrada (entrada i → saída i).
+    /// É isso que os Plays em cascata recebem do Play anterior.
+    pub fn passthrough_output(&self, node: &GraphNode, connections: &[Connection]) -> Result<Value, String> {
+        let wired = self.wired_values(node, connections, Vec::new())?;
+        Ok(multi_output(wired))
+    }
+
+    /// Escreve os valores das conexões que chegam no node em `wired[to_input_index]`
+    fn wired_values(&self, node: &GraphNode, connections: &[Connection], mut wired: Vec<Value>) -> Result<Vec<Value>, String> {
+        let mut incoming: Vec<&Connection> = connections
+            .iter()
+            .filter(|c| c.to_node_id == node.id)
+            .collect();
+
+        // Ordena por entrada e depois por origem (pra ser determinístico)
+        incoming.sort_by(|a, b| {
+            a.to_input_index
+                .cmp(&b.to_input_index)
+                .then_with(|| a.from_node_id.cmp(&b.from_node_id))
+        });
+
+        for conn in incoming {
+            let upstream = self.get(&conn.from_node_id).ok_or_else(|| {
+                format!(
+                    "Output do node {} não disponível para o node {}",
+                    conn.from_node_id, node.id
+                )
+            })?;
+
+            let value = select_output(upstream, conn.from_output_index)?;
+
+            if wired.len() <= conn.to_input_index {
+                wired.resize(conn.to_input_index + 1, Value::Null);
+            }
+            wired[conn.to_input_index] = value;
+        }
+
+        Ok(wired)
+    }
+}
+
+/// Monta um output com várias saídas (`{"outputs": [saída 0, saída 1, ...]}`)
+pub fn multi_output(outputs: Vec<Value>) -> Value {
+    let mut output = Map::new();
+    output.insert(MULTI_OUTPUTS_FIELD.to_string(), Value::Array(outputs));
+    Value::Object(output)
+}
+
+/// Extrai o valor de uma saída específica do output de um node
+///
+/// - `{"outputs": [...]}` → elemento no index pedido
+/// - objeto com um único campo (`{"response": 35}`) → o valor do campo, em todas as saídas
+/// - qualquer outro formato → o output inteiro, em todas as saídas
+pub fn select_output(output: &Value, index: usize) -> Result<Value, String> {
+    match output {
+        Value::Object(map) => {
+            if let Some(Value::Array(outputs)) = map.get(MULTI_OUTPUTS_FIELD) {
+                return outputs
+                    .get(index)
+                    .cloned()
+                    .ok_or_else(|| format!("Saída {} não existe (node tem {} saídas)", index, outputs.len()));
+            }
+            if map.len() == 1 {
+                if let Some(value) = map.values().next() {
+                    return Ok(value.clone());
+                }
+            }
+            Ok(output.clone())
+        }
+        _ => Ok(output.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::types::test_graph::{conn_at, node_on};
+    use serde_json::json;
+
+    fn node(id: &str, data: Value) -> GraphNode {
+        node_on(id, "subtract", 3001, data)
+    }
+
+    #[test]
+    fn test_sum_feeds_subtract() {
+        let subtract = node("subtract-1", json!({"variables": [100, 15]}));
+        let connections = vec![conn_at("sum-1", 0, "subtract-1", 0)];
+
+        let mut store = ValueStore::new();
+        store.insert("sum-1", json!({"response": 35}));
+
+        let input = store.resolve_input(&subtract, &connections).unwrap();
+        assert_eq!(input, json!({"variables": [35, 15]}));
+    }
+
+    #[test]
+    fn test_unwired_node_keeps_static_data() {
+        let sum = node("sum-1", json!({"variables": [10, 20, 5]}));
+        let store = ValueStore::new();
+
+        let input = store.resolve_input(&sum, &[]).unwrap();
+        assert_eq!(input, json!({"variables": [10, 20, 5]}));
+    }
+
+    #[test]
+    fn test_wired_input_beyond_static_data() {
+        // Sem dados estáticos: as entradas conectadas criam o array
+        let target = node("sum-2", json!({"label": "Somar"}));
+        let connections = vec![
+            conn_at("a", 0, "sum-2", 1),
+            conn_at("b", 0, "sum-2", 0),
+        ];
+
+        let mut store = ValueStore::new();
+        store.insert("a", json!({"response": 7}));
+        store.insert("b", json!({"response": 3}));
+
+        let input = store.resolve_input(&target, &connections).unwrap();
+        assert_eq!(input, json!({"label": "Somar", "variables": [3, 7]}));
+    }
+
+    #[test]
+    fn test_missing_upstream_output_is_error() {
+        let target = node("subtract-1", json!({"variables": [1, 2]}));
+        let connections = vec![conn_at("sum-1", 0, "subtract-1", 0)];
+
+        let store = ValueStore::new();
+        assert!(store.resolve_input(&target, &connections).is_err());
+    }
+
+    #[test]
+    fn test_play_passthrough_output() {
+        // Play com duas entradas: ignora os dados estáticos e repassa o que chegou
+        let play = node("play-a", json!({"action": "execute"}));
+        let connections = vec![conn_at("sum-1", 0, "play-a", 0), conn_at("mult-1", 0, "play-a", 1)];
+
+        let mut store = ValueStore::new();
+        store.insert("sum-1", json!({"response": 35}));
+        store.insert("mult-1", json!({"response": 70}));
+
+        let output = store.passthrough_output(&play, &connections).unwrap();
+        assert_eq!(output, json!({"outputs": [35, 70]}));
+        assert_eq!(select_output(&output, 1).unwrap(), json!(70));
+    }
+
+    #[test]
+    fn test_select_output() {
+        assert_eq!(select_output(&json!({"response": 35}), 0).unwrap(), json!(35));
+        assert_eq!(select_output(&json!({"outputs": [1, 2]}), 1).unwrap(), json!(2));
+        assert!(select_output(&json!({"outputs": [1]}), 3).is_err());
+
+        let full = json!({"width": 512, "height": 512});
+        assert_eq!(select_output(&full, 0).unwrap(), full);
+    }
+}
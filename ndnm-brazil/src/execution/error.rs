@@ -0,0 +1,150 @@
+// ndnm-brazil/src/execution/error.rs
+//
+// Erros de execução estruturados
+// O frontend precisa do run_id e do node que quebrou (pra destacar no canvas),
+// então o engine não devolve mais só uma String.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Falha de um node específico
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeFailure {
+    pub node_id: String,
+    pub label: String,
+    /// Status HTTP devolvido pelo /run do node (None = nem chegou a responder)
+    pub http_status: Option<u16>,
+    /// `error.code` do ErrorEnvelope do ndnm-core (ex: "BAD_REQUEST")
+    pub code: Option<String>,
+    pub message: String,
+    /// Quantas vezes o /run foi tentado antes de desistir
+    pub attempts: u32,
+}
+
+impl NodeFailure {
+    /// Falha sem resposta HTTP (conexão, input inválido, etc)
+    pub fn new(node_id: &str, label: &str, message: impl Into<String>) -> Self {
+        Self {
+            node_id: node_id.to_string(),
+            label: label.to_string(),
+            http_status: None,
+            code: None,
+            message: message.into(),
+            attempts: 1,
+        }
+    }
+
+    /// Falha a partir de uma resposta de erro do node.
+    /// Se o corpo for o ErrorEnvelope do ndnm-core, usa o code/message dele; senão, o texto cru.
+    pub fn from_response(node_id: &str, label: &str, http_status: u16, body: &str) -> Self {
+        let (code, message) = match serde_json::from_str::<Envelope>(body) {
+            Ok(envelope) => (Some(envelope.error.code), envelope.error.message),
+            Err(_) if body.trim().is_empty() => (None, format!("Node retornou erro {}", http_status)),
+            Err(_) => (None, body.trim().to_string()),
+        };
+
+        Self {
+            node_id: node_id.to_string(),
+            label: label.to_string(),
+            http_status: Some(http_status),
+            code,
+            message,
+            attempts: 1,
+        }
+    }
+}
+
+impl fmt::Display for NodeFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.http_status, &self.code) {
+            (Some(status), Some(code)) => write!(f, "[{} {}] {}", status, code, self.message),
+            (Some(status), None) => write!(f, "[{}] {}", status, self.message),
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// Erro de um run
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutionError {
+    pub run_id: String,
+    /// Mensagem resumida (o que vai pro log e pro metadata.json)
+    pub message: String,
+    /// Node que falhou (None = erro do run em si, ex: grafo inválido)
+    pub failed_node: Option<NodeFailure>,
+    /// Nodes que terminaram antes da falha
+    pub completed_nodes: Vec<String>,
+}
+
+impl ExecutionError {
+    /// Erro do run sem node específico
+    pub fn run(run_id: &str, message: impl Into<String>) -> Self {
+        Self {
+            run_id: run_id.to_string(),
+            message: message.into(),
+            failed_node: None,
+            completed_nodes: Vec::new(),
+        }
+    }
+
+    /// Erro causado por um node
+    pub fn node(run_id: &str, failure: NodeFailure) -> Self {
+        Self {
+            run_id: run_id.to_string(),
+            message: format!("Node {} falhou: {}", failure.node_id, failure),
+            failed_node: Some(failure),
+            completed_nodes: Vec::new(),
+        }
+    }
+
+    pub fn with_completed_nodes(mut self, completed_nodes: Vec<String>) -> Self {
+        self.completed_nodes = completed_nodes;
+        self
+    }
+}
+
+impl fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ExecutionError {}
+
+/// Formato do corpo de erro dos nodes (AppError do ndnm-core)
+#[derive(Deserialize)]
+struct Envelope {
+    error: EnvelopeDetail,
+}
+
+#[derive(Deserialize)]
+struct EnvelopeDetail {
+    code: String,
+    message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failure_from_error_envelope() {
+        let body = r#"{"status":"error","error":{"code":"BAD_REQUEST","message":"Division by zero"}}"#;
+        let failure = NodeFailure::from_response("node-sum-3", "➕ Somar", 400, body);
+
+        assert_eq!(failure.http_status, Some(400));
+        assert_eq!(failure.code.as_deref(), Some("BAD_REQUEST"));
+        assert_eq!(failure.message, "Division by zero");
+        assert_eq!(failure.to_string(), "[400 BAD_REQUEST] Division by zero");
+    }
+
+    #[test]
+    fn test_failure_from_plain_body() {
+        let failure = NodeFailure::from_response("n", "N", 502, "Bad Gateway\n");
+        assert_eq!(failure.code, None);
+        assert_eq!(failure.message, "Bad Gateway");
+
+        let empty = NodeFailure::from_response("n", "N", 500, "");
+        assert_eq!(empty.message, "Node retornou erro 500");
+    }
+}
@@ -0,0 +1,236 @@
+// ndnm-brazil/src/execution/events.rs
+//
+// Stream de progresso do run
+// O ExecutionEngine chama o ProgressTracker a cada mudança e ele manda um
+// snapshot (ExecutionStatus) pro canal - o /ws repassa como EXECUTION_STATUS
+// O output de cada node que termina também sai pelo canal - o /ws repassa como NODE_OUTPUT
+
+use super::types::{ExecutionEvent, ExecutionState, ExecutionStatus, GraphNode};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Instant;
+use tokio::sync::mpsc;
+
+/// O que sai de um run pelo canal de eventos
+#[derive(Debug, Clone)]
+pub enum RunEvent {
+    /// Snapshot de progresso
+    Status(ExecutionStatus),
+    /// Output de um node que terminou (executado ou do cache), pro editor mostrar no card
+    NodeOutput { run_id: String, node_id: String, output: Value },
+}
+
+/// Canal por onde saem os snapshots de progresso e os outputs dos nodes
+pub type EventSender = mpsc::UnboundedSender<RunEvent>;
+
+/// Acompanha o progresso de um run e emite os eventos
+pub struct ProgressTracker {
+    run_id: String,
+    sender: Option<EventSender>,
+    total_nodes: usize,
+    completed: Vec<String>,
+    running: Vec<String>,
+    remaining: Vec<String>,
+    cached: usize,
+    /// Início de cada node em execução (pra calcular duração em caso de falha)
+    started: HashMap<String, Instant>,
+}
+
+impl ProgressTracker {
+    pub fn new(run_id: &str, order: &[&GraphNode], sender: Option<EventSender>) -> Self {
+        Self {
+            run_id: run_id.to_string(),
+            sender,
+            total_nodes: order.len(),
+            completed: Vec::new(),
+            running: Vec::new(),
+            remaining: order.iter().map(|n| n.id.clone()).collect(),
+            cached: 0,
+            started: HashMap::new(),
+        }
+    }
+
+    pub fn starting(&self) {
+        self.emit(ExecutionState::Starting, None, ExecutionEvent::Starting);
+    }
+
+    /// Node saiu da fila (Play nodes, que não executam nada)
+    pub fn skip(&mut self, node_id: &str) {
+        self.remaining.retain(|id| id != node_id);
+    }
+
+    pub fn node_executing(&mut self, node_id: &str) {
+        self.remaining.retain(|id| id != node_id);
+        self.running.push(node_id.to_string());
+        self.started.insert(node_id.to_string(), Instant::now());
+
+        self.emit(
+            ExecutionState::Executing,
+            Some(node_id),
+            ExecutionEvent::NodeExecuting { node_id: node_id.to_string() },
+        );
+    }
+
+    pub fn node_finished(&mut self, node_id: &str, cached: bool, duration_ms: u64) {
+        self.finish_node(node_id);
+        self.completed.push(node_id.to_string());
+
+        let event = if cached {
+            self.cached += 1;
+            ExecutionEvent::NodeCached { node_id: node_id.to_string() }
+        } else {
+            ExecutionEvent::NodeCompleted { node_id: node_id.to_string(), duration_ms }
+        };
+        self.emit(ExecutionState::Executing, Some(node_id), event);
+    }
+
+    pub fn node_failed(&mut self, node_id: &str, error: &str) {
+        let duration_ms = self.finish_node(node_id);
+
+        self.emit(
+            ExecutionState::Executing,
+            Some(node_id),
+            ExecutionEvent::NodeFailed {
+                node_id: node_id.to_string(),
+                error: error.to_string(),
+                duration_ms,
+            },
+        );
+    }
+
+    /// Node não vai rodar porque `failed_node_id` falhou (modo continue)
+    pub fn node_skipped(&mut self, node_id: &str, failed_node_id: &str) {
+        self.remaining.retain(|id| id != node_id);
+
+        self.emit(
+            ExecutionState::Executing,
+            Some(node_id),
+            ExecutionEvent::NodeSkipped {
+                node_id: node_id.to_string(),
+                failed_node_id: failed_node_id.to_string(),
+            },
+        );
+    }
+
+    /// Output de um node que terminou (só clona se alguém estiver ouvindo)
+    pub fn node_output(&self, node_id: &str, output: &Value) {
+        let Some(sender) = &self.sender else { return };
+
+        let _ = sender.send(RunEvent::NodeOutput {
+            run_id: self.run_id.clone(),
+            node_id: node_id.to_string(),
+            output: output.clone(),
+        });
+    }
+
+    pub fn finished(&self, status: ExecutionState, duration_ms: u64) {
+        self.emit(status, None, ExecutionEvent::Finished { duration_ms });
+    }
+
+    /// Tira o node da lista de rodando e devolve há quanto tempo ele começou
+    fn finish_node(&mut self, node_id: &str) -> u64 {
+        self.running.retain(|id| id != node_id);
+        self.remaining.retain(|id| id != node_id);
+        self.started
+            .remove(node_id)
+            .map(|start| start.elapsed().as_millis() as u64)
+            .unwrap_or(0)
+    }
+
+    fn emit(&self, status: ExecutionState, current_node: Option<&str>, event: ExecutionEvent) {
+        let Some(sender) = &self.sender else { return };
+
+        let snapshot = ExecutionStatus {
+            run_id: self.run_id.clone(),
+            status,
+            current_node: current_node.map(|id| id.to_string()),
+            completed_nodes: self.completed.clone(),
+            running_nodes: self.running.clone(),
+            remaining_nodes: self.remaining.clone(),
+            total_nodes: self.total_nodes,
+            cached_nodes: self.cached,
+            event,
+        };
+
+        // Se ninguém está ouvindo (WS caiu), a execução continua mesmo assim
+        let _ = sender.send(RunEvent::Status(snapshot));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::types::test_graph::node;
+    use serde_json::json;
+
+    fn status(event: RunEvent) -> Value {
+        match event {
+            RunEvent::Status(status) => serde_json::to_value(status).unwrap(),
+            other => panic!("esperava um snapshot, veio {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_progress_snapshots() {
+        let (a, b, play) = (node("A", "add"), node("B", "add"), node("play", "add"));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut tracker = ProgressTracker::new("run_x", &[&a, &b, &play], Some(tx));
+
+        tracker.starting();
+        tracker.node_executing("A");
+        tracker.node_finished("A", false, 12);
+        tracker.node_output("A", &json!({"response": 35}));
+        tracker.node_executing("B");
+        tracker.node_finished("B", true, 0);
+        tracker.skip("play");
+        tracker.finished(ExecutionState::Completed, 20);
+
+        let mut events: Vec<RunEvent> = std::iter::from_fn(|| rx.try_recv().ok()).collect();
+        assert_eq!(events.len(), 7);
+
+        // Output do A sai logo depois do snapshot de node_completed
+        match events.remove(3) {
+            RunEvent::NodeOutput { run_id, node_id, output } => {
+                assert_eq!((run_id.as_str(), node_id.as_str()), ("run_x", "A"));
+                assert_eq!(output, json!({"response": 35}));
+            }
+            other => panic!("esperava NodeOutput, veio {:?}", other),
+        }
+        let events: Vec<Value> = events.into_iter().map(status).collect();
+
+        let executing_a = &events[1];
+        assert_eq!(executing_a["event"], json!("node_executing"));
+        assert_eq!(executing_a["running_nodes"], json!(["A"]));
+        assert_eq!(executing_a["remaining_nodes"], json!(["B", "play"]));
+
+        let completed_a = &events[2];
+        assert_eq!(completed_a["event"], json!("node_completed"));
+        assert_eq!(completed_a["duration_ms"], json!(12));
+        assert_eq!(completed_a["completed_nodes"], json!(["A"]));
+
+        let cached_b = &events[4];
+        assert_eq!(cached_b["event"], json!("node_cached"));
+        assert_eq!(cached_b["cached_nodes"], json!(1));
+
+        let finished = &events[5];
+        assert_eq!(finished["event"], json!("finished"));
+        assert_eq!(finished["status"], json!("completed"));
+        assert_eq!(finished["remaining_nodes"], json!([]));
+    }
+
+    #[test]
+    fn test_failure_reports_node_and_error() {
+        let a = node("A", "add");
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut tracker = ProgressTracker::new("run_x", &[&a], Some(tx));
+
+        tracker.node_executing("A");
+        tracker.node_failed("A", "boom");
+
+        let failed = status(rx.try_recv().and_then(|_| rx.try_recv()).unwrap());
+        assert_eq!(failed["event"], json!("node_failed"));
+        assert_eq!(failed["node_id"], json!("A"));
+        assert_eq!(failed["error"], json!("boom"));
+        assert_eq!(failed["running_nodes"], json!([]));
+    }
+}
@@ -1,120 +1,587 @@
 // ndnm-brazil/src/execution/executor.rs
 //
-// Executor sequencial de nodes
-// Fase 2: Execução básica SEM cache (executa tudo sempre)
+// Executor de nodes
+// Fase 2: Cache por hash de input (cache.rs) - só executa o que mudou
+// Cada node declara se pode vir do cache (cache_policy.rs: pure, volatile, ttl, content_addressed)
+// Os outputs de cada node alimentam os próximos via ValueStore (dataflow.rs)
+// Branches independentes rodam em paralelo (scheduler.rs), respeitando os limites por run e por porta
 
 use super::types::*;
 use super::resolver::DependencyResolver;
+use super::dataflow::{multi_output, ValueStore};
+use super::cache::{hash_value, RunCache, RunMetadata, RunNodeMetadata};
+use super::cache_policy::CachePolicy;
+use super::cascade::downstream_tails;
+use super::selection::SelectionMode;
+use super::scheduler::{PortLimiter, Schedule, DEFAULT_MAX_PARALLEL_NODES};
+use super::events::{EventSender, ProgressTracker};
+use super::error::{ExecutionError, NodeFailure};
+use super::policy::{ExecutionPolicy, TIMEOUT_CODE};
+use ndnm_core::{CachePolicyConfig, ExecutionPolicyConfig};
+use futures_util::stream::{FuturesUnordered, StreamExt};
 use reqwest::Client;
-use std::time::Instant;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use chrono::Utc;
+use tokio_util::sync::CancellationToken;
 
+#[derive(Debug)]
 pub struct ExecutionEngine {
     /// Cliente HTTP para chamar os nodes
     http_client: Client,
+    /// Cache de outputs por hash de input (workspaces/runs)
+    cache: RunCache,
+    /// Limite de requests simultâneos por porta (vale pra todos os runs)
+    port_limiter: PortLimiter,
+    /// Máximo de nodes em paralelo num run (quando o request não define)
+    max_parallel_nodes: usize,
+    /// Timeout/retries do config.yaml de cada node (porta → política)
+    node_policies: HashMap<u16, ExecutionPolicyConfig>,
+    /// Seção `cache:` do config.yaml de cada node (porta → política de cache)
+    cache_policies: HashMap<u16, CachePolicyConfig>,
 }
 
 impl ExecutionEngine {
     pub fn new() -> Self {
+        Self::with_cache(RunCache::default())
+    }
+
+    /// Cria o engine usando outra pasta de runs (ex: testes)
+    pub fn with_cache(cache: RunCache) -> Self {
         Self {
             http_client: Client::new(),
+            cache,
+            port_limiter: PortLimiter::default(),
+            max_parallel_nodes: DEFAULT_MAX_PARALLEL_NODES,
+            node_policies: HashMap::new(),
+            cache_policies: HashMap::new(),
         }
     }
 
+    /// Define o limite de requests simultâneos por porta (porta → máximo)
+    pub fn with_port_limits(mut self, limits: HashMap<u16, usize>) -> Self {
+        self.port_limiter = PortLimiter::new(limits);
+        self
+    }
+
+    /// Define timeout/retries por porta de node (vem da seção `execution:` do config.yaml)
+    pub fn with_node_policies(mut self, policies: HashMap<u16, ExecutionPolicyConfig>) -> Self {
+        self.node_policies = policies;
+        self
+    }
+
+    /// Pasta de runs usada pelo engine (Cache Cleaner, pin, invalidação)
+    pub fn cache(&self) -> &RunCache {
+        &self.cache
+    }
+
+    /// Define quando o output de cada node pode vir do cache (seção `cache:` do config.yaml)
+    pub fn with_cache_policies(mut self, policies: HashMap<u16, CachePolicyConfig>) -> Self {
+        self.cache_policies = policies;
+        self
+    }
+
+    fn cache_policy_for(&self, node: &GraphNode) -> CachePolicy {
+        CachePolicy::from_config(self.cache_policies.get(&node.port))
+    }
+
+    /// Política efetiva de um node: override do grafo → config.yaml → defaults
+    fn policy_for(&self, node: &GraphNode) -> ExecutionPolicy {
+        ExecutionPolicy::resolve(self.node_policies.get(&node.port), &node.execution)
+    }
+
     /// Executa um workflow a partir de um node Play
-    pub async fn execute(&self, request: ExecutionRequest) -> Result<ExecutionResult, String> {
+    #[cfg(test)]
+    pub async fn execute(&self, request: ExecutionRequest) -> Result<ExecutionResult, ExecutionError> {
+        self.execute_run(request, RunContext::new()).await
+    }
+
+    /// Executa um workflow com um contexto já criado (run_id conhecido antes + canal de progresso)
+    pub async fn execute_run(&self, request: ExecutionRequest, context: RunContext) -> Result<ExecutionResult, ExecutionError> {
         let start_time = Instant::now();
+        let started_at = Utc::now();
 
-        // Gera run_id único
-        let run_id = Self::generate_run_id();
+        let RunContext { run_id, events, cancel, mut seed, parent_run_id, .. } = context;
 
         println!("🚀 Iniciando execução: run_id={}", run_id);
-        println!("   Play node: {}", request.play_node_id);
+        match &request.selection {
+            Some(selection) => println!("   Nodes selecionados: {:?} ({:?})", selection.node_ids, selection.mode),
+            None => println!("   Play node: {}", request.play_node_id.as_deref().unwrap_or("-")),
+        }
 
-        // Resolve dependências (grafo → lista ordenada)
+        // Valida o grafo e resolve dependências (grafo → lista ordenada)
         let resolver = DependencyResolver::new(&request.graph);
-        let execution_order = resolver
-            .resolve_from(&request.play_node_id)
-            .map_err(|e| format!("Erro ao resolver dependências: {}", e))?;
+        resolver
+            .validate()
+            .map_err(|e| ExecutionError::run(&run_id, format!("Grafo inválido: {}", e)))?;
+
+        // EXECUTE_NODES self-only: quem alimenta a seleção entra com o último output em cache
+        // (replay já chega com o seed gravado no snapshot)
+        if let Some(selection) = &request.selection {
+            selection.validate(&request.graph).map_err(|e| ExecutionError::run(&run_id, e))?;
+            if selection.mode == SelectionMode::SelfOnlyUsingCachedInputs && seed.is_empty() {
+                seed = selection
+                    .cached_inputs(&self.cache, &request.workspace_id, &request.graph)
+                    .map_err(|e| ExecutionError::run(&run_id, e))?;
+            }
+        }
+
+        // Nodes com output pronto (seed) ficam fora do run: já rodaram em outro (Plays em cascata)
+        let excluded: HashSet<String> = seed.keys().cloned().collect();
+        let execution_order = match (&request.selection, &request.play_node_id) {
+            (Some(selection), _) => resolver.resolve_from_multiple_excluding(&selection.node_ids, &excluded),
+            (None, Some(play_node_id)) if request.trigger_payload.is_some() => {
+                let mut targets = downstream_tails(&request.graph, play_node_id);
+                targets.push(play_node_id.clone());
+                resolver.resolve_from_multiple_excluding(&targets, &excluded)
+            }
+            (None, Some(play_node_id)) => resolver.resolve_from_excluding(play_node_id, &excluded),
+            (None, None) => return Err(ExecutionError::run(&run_id, "Run sem Play e sem nodes selecionados")),
+        }
+        .map_err(|e| ExecutionError::run(&run_id, format!("Erro ao resolver dependências: {}", e)))?;
+        // Nodes selecionados sempre rodam (sem cache); o resto do run usa o cache normalmente
+        let forced: HashSet<&str> = request
+            .selection
+            .iter()
+            .flat_map(|s| s.node_ids.iter().map(String::as_str))
+            .collect();
+
+        let max_parallel = request.max_parallel_nodes.unwrap_or(self.max_parallel_nodes).max(1);
+
+        // Grafo executado fica na pasta do run (histórico e replay, mesmo depois de editar o workspace)
+        let snapshot = RunSnapshot {
+            graph: request.graph.clone(),
+            max_parallel_nodes: request.max_parallel_nodes,
+            on_error: request.on_error,
+            trigger_payload: request.trigger_payload.clone(),
+            seed: seed.clone(),
+            selection: request.selection.clone(),
+        };
+        if let Err(e) = self.cache.write_snapshot(&run_id, &snapshot) {
+            println!("⚠️  {}", e);
+        }
 
         println!("   Ordem de execução: {:?}", execution_order.iter().map(|n| &n.id).collect::<Vec<_>>());
-        println!("   Total de nodes: {}", execution_order.len());
+        println!("   Total de nodes: {} (até {} em paralelo)", execution_order.len(), max_parallel);
+
+        let connections = &request.graph.connections;
+        let run_id_ref = run_id.as_str();
+        let workspace_id = request.workspace_id.as_str();
+        let mut schedule = Schedule::new(&execution_order, connections);
+        let mut progress = ProgressTracker::new(&run_id, &execution_order, events);
+        progress.starting();
+
+        // Outputs de cada node neste run (alimentam os nodes seguintes)
+        let mut values = ValueStore::new();
+        // Hash do output de cada node (entra no hash de input dos nodes seguintes)
+        let mut output_hashes: HashMap<String, String> = HashMap::new();
+        for (node_id, output) in seed {
+            output_hashes.insert(node_id.clone(), hash_value(&output));
+            values.insert(&node_id, output);
+        }
 
-        // Executa cada node sequencialmente
         let mut executed_count = 0;
-        for node in &execution_order {
-            // Skip do próprio Play node (ele não tem lógica de processamento)
-            if node.node_type == "playButton" || node.node_type == "comfyPlay" {
-                println!("⏭️  Pulando Play node: {}", node.id);
-                continue;
+        let mut cached_count = 0;
+        let mut node_records = Vec::new();
+        let mut failure: Option<ExecutionError> = None;
+        let mut failed_count = 0;
+        let mut cancelled = false;
+        let mut running = FuturesUnordered::new();
+        let continue_on_error = request.on_error == ErrorMode::Continue;
+
+        loop {
+            // Dispara tudo que está pronto, até o limite do run.
+            // Para de agendar se foi cancelado ou se algo falhou (no modo continue, só os dependentes
+            // do node que falhou deixam de ficar prontos - o Schedule cuida disso).
+            while (failure.is_none() || continue_on_error) && !cancelled && running.len() < max_parallel {
+                let Some(node) = schedule.pop_ready() else { break };
+
+                // Play node não tem /run: só repassa o que chegou nele (vira o output pros Plays em cascata).
+                // Play disparado por gatilho com payload (webhook, ...) manda o payload inteiro na saída 0.
+                if node.is_play() {
+                    println!("⏭️  Pulando Play node: {}", node.id);
+                    let output = match &request.trigger_payload {
+                        Some(payload) if request.play_node_id.as_ref() == Some(&node.id) => Ok(multi_output(vec![payload.clone()])),
+                        _ => values.passthrough_output(node, connections),
+                    };
+                    match output {
+                        Ok(output) => {
+                            output_hashes.insert(node.id.clone(), hash_value(&output));
+                            values.insert(&node.id, output);
+                        }
+                        Err(e) => println!("⚠️  Output do Play {} indisponível: {}", node.id, e),
+                    }
+                    progress.skip(&node.id);
+                    schedule.complete(&node.id);
+                    continue;
+                }
+
+                println!("⚙️  Executando node: {} ({})", node.id, node.label);
+                progress.node_executing(&node.id);
+
+                // Input e hash são montados aqui: os predecessores já terminaram.
+                // Erro ao montar o input vira falha do node, igual a um erro do /run.
+                let prepared = Self::prepare_node(node, connections, &values, &output_hashes);
+                let force = forced.contains(node.id.as_str());
+                running.push(async move {
+                    let result = match prepared {
+                        Ok((input, input_hash)) => self.run_node(run_id_ref, workspace_id, node, input, input_hash, force).await,
+                        Err(e) => Err(NodeFailure::new(&node.id, &node.label, e)),
+                    };
+                    (node, result)
+                });
             }
 
-            println!("⚙️  Executando node: {} ({})", node.id, node.label);
+            // Espera o próximo node terminar (ou acaba se não tem mais nada rodando).
+            // Cancelamento sai na hora: os requests em andamento são dropados junto com o `running`.
+            let next = tokio::select! {
+                _ = cancel.cancelled() => {
+                    println!("🛑 Run cancelado: {} ({} nodes interrompidos)", run_id, running.len());
+                    cancelled = true;
+                    break;
+                }
+                next = running.next() => next,
+            };
+            let Some((node, result)) = next else { break };
 
-            match self.execute_node(node).await {
-                Ok(result) => {
-                    println!("   ✅ Sucesso: {} em {}ms", node.id, result.duration_ms);
-                    executed_count += 1;
+            match result {
+                Ok((result, output_hash)) => {
+                    if result.cached {
+                        cached_count += 1;
+                    } else {
+                        println!("   ✅ Sucesso: {} em {}ms", node.id, result.duration_ms);
+                        executed_count += 1;
+                    }
+                    progress.node_finished(&node.id, result.cached, result.duration_ms);
+                    if let Some(output) = &result.output {
+                        progress.node_output(&node.id, output);
+                    }
+                    node_records.push(RunNodeMetadata {
+                        node_id: node.id.clone(),
+                        status: result.status,
+                        cached: result.cached,
+                        duration_ms: result.duration_ms,
+                        attempts: result.attempts,
+                        error: None,
+                    });
+                    if let Some(output) = result.output {
+                        values.insert(&node.id, output);
+                        output_hashes.insert(node.id.clone(), output_hash);
+                    }
+                    schedule.complete(&node.id);
                 }
                 Err(e) => {
                     println!("   ❌ Erro: {} - {}", node.id, e);
-                    return Err(format!("Node {} falhou: {}", node.id, e));
+                    failed_count += 1;
+                    progress.node_failed(&node.id, &e.to_string());
+                    node_records.push(RunNodeMetadata {
+                        node_id: node.id.clone(),
+                        status: NodeExecutionStatus::Failed,
+                        cached: false,
+                        duration_ms: 0,
+                        attempts: e.attempts,
+                        error: Some(e.to_string()),
+                    });
+
+                    // Modo continue: pula só quem depende (direta ou indiretamente) do node que falhou
+                    if continue_on_error {
+                        for skipped in schedule.fail(&node.id) {
+                            println!("   ⏭️  Pulando {} (depende de {})", skipped.id, node.id);
+                            progress.node_skipped(&skipped.id, &node.id);
+                            node_records.push(RunNodeMetadata {
+                                node_id: skipped.id.clone(),
+                                status: NodeExecutionStatus::Skipped,
+                                cached: false,
+                                duration_ms: 0,
+                                attempts: 0,
+                                error: Some(format!("Node anterior {} falhou", node.id)),
+                            });
+                        }
+                    }
+
+                    // Mantém o primeiro erro; os nodes que já estão rodando terminam normalmente
+                    if failure.is_none() {
+                        failure = Some(ExecutionError::node(run_id_ref, e));
+                    }
                 }
             }
         }
 
+        // Nada mais rodando (libera os empréstimos do run_id; no cancelamento, aborta os requests)
+        drop(running);
+
+        if failure.is_none() && !cancelled && !schedule.is_finished() {
+            failure = Some(ExecutionError::run(
+                &run_id,
+                "Execução terminou com nodes pendentes (dependência não resolvida)",
+            ));
+        }
+
         let duration = start_time.elapsed().as_millis() as u64;
+        let status = if cancelled {
+            ExecutionState::Cancelled
+        } else if failure.is_some() {
+            ExecutionState::Failed
+        } else {
+            ExecutionState::Completed
+        };
+        let completed_nodes: Vec<String> = node_records
+            .iter()
+            .filter(|n| n.is_done())
+            .map(|n| n.node_id.clone())
+            .collect();
 
-        println!("🎉 Execução completa: run_id={}", run_id);
-        println!("   Nodes executados: {}", executed_count);
-        println!("   Duração total: {}ms", duration);
+        // Grava metadata.json do run (mesmo em caso de erro, pra ficar no histórico)
+        let metadata = RunMetadata {
+            run_id: run_id.clone(),
+            workspace_id: request.workspace_id.clone(),
+            play_node_id: request.play_node_id.clone(),
+            selection: request.selection.as_ref().map(|s| s.node_ids.clone()),
+            status: status.clone(),
+            started_at: started_at.to_rfc3339(),
+            finished_at: Utc::now().to_rfc3339(),
+            duration_ms: duration,
+            total_nodes: execution_order.len(),
+            executed_nodes: executed_count,
+            cached_nodes: cached_count,
+            nodes: node_records.clone(),
+            error: failure.as_ref().map(|e| e.message.clone()),
+            parent_run_id,
+        };
+        if let Err(e) = self.cache.write_metadata(&metadata) {
+            println!("⚠️  {}", e);
+        }
+
+        progress.finished(status.clone(), duration);
 
-        Ok(ExecutionResult {
-            run_id,
-            status: ExecutionState::Completed,
+        // Resultado do grafo: o que chegou no Play, os nodes marcados como output e, com payload
+        // de gatilho, o fim da cadeia depois do Play (EXECUTE_NODES: os nodes selecionados)
+        let output_ids = match (&request.selection, &request.play_node_id) {
+            (Some(selection), _) => selection.node_ids.clone(),
+            (None, Some(play)) => {
+                let mut ids = request.graph.output_node_ids(play);
+                if request.trigger_payload.is_some() {
+                    ids.extend(downstream_tails(&request.graph, play));
+                }
+                ids
+            }
+            (None, None) => Vec::new(),
+        };
+        let outputs = output_ids
+            .iter()
+            .filter_map(|id| Some((id.clone(), values.get(id)?.clone())))
+            .collect();
+
+        let result = ExecutionResult {
+            run_id: run_id.clone(),
+            status,
             total_nodes: execution_order.len(),
             executed_nodes: executed_count,
-            cached_nodes: 0, // Fase 2: sem cache ainda
+            cached_nodes: cached_count,
             duration_ms: duration,
-            error: None,
-        })
+            error: failure.as_ref().map(|e| e.message.clone()),
+            completed_nodes,
+            nodes: node_records,
+            output: request.play_node_id.as_ref().and_then(|play| values.get(play)).cloned(),
+            outputs,
+            child_runs: Vec::new(),
+        };
+
+        if cancelled {
+            println!("🛑 Run {} cancelado depois de {} nodes", run_id, result.completed_nodes.len());
+            return Ok(result);
+        }
+
+        // Modo continue: falhas de node não viram Err, o resultado traz o status de cada node
+        // (erros do run em si, tipo dependência não resolvida, continuam sendo Err)
+        if let Some(error) = failure {
+            if continue_on_error && error.failed_node.is_some() {
+                println!("⚠️  Execução terminou com {} nodes falhos: run_id={}", failed_count, run_id);
+                return Ok(result);
+            }
+            return Err(error.with_completed_nodes(result.completed_nodes));
+        }
+
+        println!("🎉 Execução completa: run_id={}", run_id);
+        println!("   Nodes executados: {}", executed_count);
+        println!("   Nodes do cache: {}", cached_count);
+        println!("   Duração total: {}ms", duration);
+
+        Ok(result)
+    }
+
+    /// Monta o input final e o hash de input de um node cujos predecessores já terminaram
+    fn prepare_node(
+        node: &GraphNode,
+        connections: &[Connection],
+        values: &ValueStore,
+        output_hashes: &HashMap<String, String>,
+    ) -> Result<(serde_json::Value, String), String> {
+        // Junta os dados estáticos do node com os outputs conectados nele
+        let input = values.resolve_input(node, connections)?;
+
+        // Hash = tipo + dados + hashes dos outputs que chegam pelas conexões
+        let upstream: Vec<(usize, usize, String)> = connections
+            .iter()
+            .filter(|c| c.to_node_id == node.id)
+            .map(|c| {
+                let hash = output_hashes.get(&c.from_node_id).cloned().unwrap_or_default();
+                (c.to_input_index, c.from_output_index, hash)
+            })
+            .collect();
+
+        Ok((input, RunCache::input_hash(node, &upstream)))
+    }
+
+    /// Tenta o cache (runs do mesmo workspace) e, se não achar, executa o node via HTTP.
+    /// `force` pula o cache (nodes selecionados no EXECUTE_NODES).
+    /// Retorna o resultado e o hash do output.
+    async fn run_node(
+        &self,
+        run_id: &str,
+        workspace_id: &str,
+        node: &GraphNode,
+        input: serde_json::Value,
+        input_hash: String,
+        force: bool,
+    ) -> Result<(NodeExecutionResult, String), NodeFailure> {
+        if let Err(e) = self.cache.store_input(run_id, &node.id, &input) {
+            println!("⚠️  {}", e);
+        }
+
+        // content_addressed: os arquivos do input entram no hash (lê o disco, fora do runtime)
+        let cache_policy = self.cache_policy_for(node);
+        let input_hash = match &cache_policy {
+            CachePolicy::ContentAddressed { .. } => {
+                let (policy, key_input) = (cache_policy.clone(), input.clone());
+                tokio::task::spawn_blocking(move || policy.cache_key(&key_input, input_hash))
+                    .await
+                    .map_err(|e| NodeFailure::new(&node.id, &node.label, format!("Erro ao identificar arquivos do input: {}", e)))?
+            }
+            _ => input_hash,
+        };
+
+        // Procura nas pastas de run e copia o hit pro run atual (lê o disco, fora do runtime)
+        let hit = match cache_policy.uses_cache() && !force {
+            true => {
+                let cache = self.cache.clone();
+                let (workspace_id, run_id, node_id, key) =
+                    (workspace_id.to_string(), run_id.to_string(), node.id.clone(), input_hash.clone());
+                tokio::task::spawn_blocking(move || {
+                    let hit = cache
+                        .lookup(&workspace_id, &node_id, &key)
+                        .filter(|hit| cache_policy.accepts(hit, Utc::now()))?;
+                    // Copia pro run atual: o run mais recente sempre tem o cache de todos os nodes
+                    if let Err(e) = cache.store_hit(&run_id, &node_id, &key, &hit) {
+                        println!("⚠️  {}", e);
+                    }
+                    Some(hit)
+                })
+                .await
+                .map_err(|e| NodeFailure::new(&node.id, &node.label, format!("Erro ao ler o cache: {}", e)))?
+            }
+            false => None,
+        };
+        if let Some(hit) = hit {
+            println!("   ⚡ Usando cache: {} (hash igual ao do {})", node.id, hit.run_id);
+
+            let result = NodeExecutionResult {
+                node_id: node.id.clone(),
+                status: NodeExecutionStatus::Cached,
+                output: Some(hit.output),
+                error: None,
+                duration_ms: 0,
+                cached: true,
+                attempts: 0,
+            };
+            return Ok((result, hit.output_hash));
+        }
+
+        // Tenta de novo só o que a política deixa (conexão/timeout, ou 5xx com retry_on: server_error)
+        let policy = self.policy_for(node);
+        let mut attempt = 1;
+        let result = loop {
+            // Espera vaga na porta do node a cada tentativa (node de GPU com max_concurrency: 1 não
+            // recebe dois /run ao mesmo tempo); a vaga é solta antes do backoff pra não travar a porta
+            let permit = self.port_limiter.acquire(node.port).await;
+            let attempt_result = self.execute_node(node, &input, policy.timeout).await;
+            drop(permit);
+
+            match attempt_result {
+                Ok(mut result) => {
+                    result.attempts = attempt;
+                    break result;
+                }
+                Err(mut failure) => {
+                    if attempt > policy.max_retries || !policy.should_retry(&failure) {
+                        failure.attempts = attempt;
+                        return Err(failure);
+                    }
+                    let wait = policy.backoff_for(attempt);
+                    println!("   🔁 Retry {}/{} de {} em {}ms: {}",
+                        attempt, policy.max_retries, node.id, wait.as_millis(), failure);
+                    tokio::time::sleep(wait).await;
+                    attempt += 1;
+                }
+            }
+        };
+
+        let output_hash = match &result.output {
+            Some(output) => match self.cache.store(run_id, &node.id, &input_hash, output) {
+                Ok(hash) => hash,
+                Err(e) => {
+                    println!("⚠️  {}", e);
+                    hash_value(output)
+                }
+            },
+            None => String::new(),
+        };
+
+        Ok((result, output_hash))
     }
 
-    /// Executa um node individual via HTTP POST
-    async fn execute_node(&self, node: &GraphNode) -> Result<NodeExecutionResult, String> {
+    /// Executa um node individual via HTTP POST com o input já resolvido (uma tentativa)
+    async fn execute_node(
+        &self,
+        node: &GraphNode,
+        input_data: &serde_json::Value,
+        timeout: Duration,
+    ) -> Result<NodeExecutionResult, NodeFailure> {
         let start_time = Instant::now();
 
         // Monta URL do node
         let url = format!("http://localhost:{}/run", node.port);
 
-        // Fase 2: Por enquanto, envia os dados do node como input
-        // Fase 3: Vai incluir outputs dos nodes anteriores
-        let input_data = &node.data;
-
         // Faz POST HTTP pro node
         let response = self
             .http_client
             .post(&url)
+            .timeout(timeout)
             .json(input_data)
             .send()
             .await
-            .map_err(|e| format!("Erro ao conectar com node {}: {}", node.id, e))?;
+            .map_err(|e| request_failure(node, e, timeout, "Erro ao conectar com node"))?;
 
         let status_code = response.status();
 
         if !status_code.is_success() {
+            // Nodes do ndnm-core respondem com o ErrorEnvelope ({"status":"error","error":{code,message}})
             let error_text = response
                 .text()
                 .await
                 .unwrap_or_else(|_| "Erro desconhecido".to_string());
-            return Err(format!("Node retornou erro {}: {}", status_code, error_text));
+            return Err(NodeFailure::from_response(&node.id, &node.label, status_code.as_u16(), &error_text));
         }
 
         // Parse da resposta JSON
         let output: serde_json::Value = response
             .json()
             .await
-            .map_err(|e| format!("Erro ao parsear resposta do node {}: {}", node.id, e))?;
+            .map_err(|e| NodeFailure {
+                http_status: Some(status_code.as_u16()),
+                ..request_failure(node, e, timeout, "Erro ao parsear resposta do node")
+            })?;
 
         let duration = start_time.elapsed().as_millis() as u64;
 
@@ -125,13 +592,807 @@ impl ExecutionEngine {
             error: None,
             duration_ms: duration,
             cached: false,
+            attempts: 1,
         })
     }
 
-    /// Gera run_id único (timestamp + random)
-    fn generate_run_id() -> String {
-        let now = Utc::now();
-        format!("run_{}_{:x}", now.format("%Y-%m-%d_%H-%M-%S"), rand::random::<u32>())
+}
+
+/// Converte um erro do reqwest em falha do node (timeout ganha o code TIMEOUT)
+fn request_failure(node: &GraphNode, error: reqwest::Error, timeout: Duration, context: &str) -> NodeFailure {
+    if error.is_timeout() {
+        return NodeFailure {
+            code: Some(TIMEOUT_CODE.to_string()),
+            ..NodeFailure::new(&node.id, &node.label, format!("Timeout de {}ms no node {}", timeout.as_millis(), node.id))
+        };
     }
+    NodeFailure::new(&node.id, &node.label, format!("{} {}: {}", context, node.id, error))
 }
 
+/// Runs filhos em andamento de uma cascata (run_id → token de cancelamento de cada um)
+#[derive(Debug, Clone, Default)]
+pub struct ChildRuns(Arc<Mutex<HashMap<String, CancellationToken>>>);
+
+impl ChildRuns {
+    pub fn insert(&self, run_id: &str, cancel: CancellationToken) {
+        self.lock().insert(run_id.to_string(), cancel);
+    }
+
+    pub fn remove(&self, run_id: &str) {
+        self.lock().remove(run_id);
+    }
+
+    /// Cancela só o filho `run_id`. Retorna false se ele não está rodando.
+    pub fn cancel(&self, run_id: &str) -> bool {
+        match self.lock().get(run_id) {
+            Some(cancel) => {
+                cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, CancellationToken>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Contexto de um run: o id (gerado antes de começar, pra poder responder na hora),
+/// o canal opcional de progresso (EXECUTION_STATUS) e o token de cancelamento (CANCEL_RUN).
+/// Runs filhos de uma cascata de Plays também trazem o run pai e os outputs já prontos.
+pub struct RunContext {
+    pub run_id: String,
+    pub events: Option<EventSender>,
+    pub cancel: CancellationToken,
+    /// Outputs de nodes que rodaram fora deste run (node_id → output); ficam fora da ordem
+    pub seed: HashMap<String, serde_json::Value>,
+    pub parent_run_id: Option<String>,
+    /// Onde a cascata registra os filhos enquanto eles rodam (CANCEL_RUN com o id de um filho)
+    pub children: ChildRuns,
+}
+
+impl RunContext {
+    /// Contexto com run_id novo, sem canal de progresso e com token de cancelamento próprio
+    pub fn new() -> Self {
+        Self {
+            run_id: generate_run_id(),
+            events: None,
+            cancel: CancellationToken::new(),
+            seed: HashMap::new(),
+            parent_run_id: None,
+            children: ChildRuns::default(),
+        }
+    }
+
+    /// Manda o progresso do run pelo canal
+    pub fn with_events(mut self, events: EventSender) -> Self {
+        self.events = Some(events);
+        self
+    }
+}
+
+impl Default for RunContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Gera run_id único (timestamp + random)
+/// Inclui milissegundos: a ordem alfabética dos run_ids é usada pelo cache como ordem cronológica
+pub fn generate_run_id() -> String {
+    let now = Utc::now();
+    format!("run_{}_{:x}", now.format("%Y-%m-%d_%H-%M-%S-%3f"), rand::random::<u32>())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::events::RunEvent;
+    use crate::execution::types::test_graph::{conn, node_on};
+    use ndnm_core::{async_trait, AppError, Node};
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[derive(Deserialize)]
+    struct MathInput {
+        variables: Vec<i64>,
+    }
+
+    #[derive(Serialize)]
+    struct MathOutput {
+        response: i64,
+    }
+
+    /// Node de teste: soma ou subtrai `variables` e conta quantas vezes foi chamado
+    struct MathNode {
+        subtract: bool,
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Node for MathNode {
+        type Input = MathInput;
+        type Output = MathOutput;
+
+        async fn process(&self, input: Self::Input) -> Result<Self::Output, AppError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let first = input.variables.first().copied().unwrap_or(0);
+            let response = if self.subtract {
+                input.variables.iter().skip(1).fold(first, |acc, n| acc - n)
+            } else {
+                input.variables.iter().sum()
+            };
+            Ok(MathOutput { response })
+        }
+    }
+
+    /// Sobe o node numa porta livre e devolve a porta
+    async fn spawn_node(node: MathNode) -> u16 {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            axum::serve(listener, ndnm_core::router(node).into_make_service()).await.unwrap();
+        });
+        port
+    }
+
+    /// sum-1 → subtract-1 → play-1 (igual ao test_execution.json)
+    fn sum_subtract_request(sum_port: u16, subtract_port: u16, sum_values: serde_json::Value) -> ExecutionRequest {
+        ExecutionRequest {
+            play_node_id: Some("play-1".to_string()),
+            workspace_id: "test-workspace".to_string(),
+            graph: WorkflowGraph {
+                nodes: vec![
+                    node_on("sum-1", "add", sum_port, json!({ "variables": sum_values })),
+                    node_on("subtract-1", "subtract", subtract_port, json!({"variables": [100, 15]})),
+                    node_on("play-1", "playButton", 3020, json!({"action": "execute"})),
+                ],
+                connections: vec![conn("sum-1", "subtract-1"), conn("subtract-1", "play-1")],
+            },
+            max_parallel_nodes: None,
+            on_error: Default::default(),
+            trigger_payload: None,
+            selection: None,
+        }
+    }
+
+    fn setup_engine(dir_name: &str) -> ExecutionEngine {
+        let root = std::env::temp_dir().join(dir_name);
+        if root.exists() {
+            std::fs::remove_dir_all(&root).unwrap();
+        }
+        ExecutionEngine::with_cache(RunCache::new(root))
+    }
+
+    #[tokio::test]
+    async fn test_execute_uses_cache_on_second_run() {
+        let sum_calls = Arc::new(AtomicUsize::new(0));
+        let subtract_calls = Arc::new(AtomicUsize::new(0));
+        let sum_port = spawn_node(MathNode { subtract: false, calls: sum_calls.clone() }).await;
+        let subtract_port = spawn_node(MathNode { subtract: true, calls: subtract_calls.clone() }).await;
+
+        let engine = setup_engine("ndnm_test_engine_cache");
+
+        // Primeira execução: tudo executa, sum-1 (35) alimenta subtract-1 → 35 - 15 = 20
+        let first = engine
+            .execute(sum_subtract_request(sum_port, subtract_port, json!([10, 20, 5])))
+            .await
+            .unwrap();
+        assert_eq!(first.executed_nodes, 2);
+        assert_eq!(first.cached_nodes, 0);
+
+        let output = std::fs::read_to_string(engine.cache.node_dir(&first.run_id, "subtract-1").join("output.json")).unwrap();
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&output).unwrap(), json!({"response": 20}));
+        assert!(engine.cache.run_dir(&first.run_id).join("metadata.json").exists());
+
+        // Segunda execução sem mudanças: tudo vem do cache
+        let second = engine
+            .execute(sum_subtract_request(sum_port, subtract_port, json!([10, 20, 5])))
+            .await
+            .unwrap();
+        assert_eq!(second.executed_nodes, 0);
+        assert_eq!(second.cached_nodes, 2);
+        assert_eq!(sum_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(subtract_calls.load(Ordering::SeqCst), 1);
+
+        // Muda o sum → a mudança invalida o subtract também
+        let third = engine
+            .execute(sum_subtract_request(sum_port, subtract_port, json!([1, 2])))
+            .await
+            .unwrap();
+        assert_eq!(third.executed_nodes, 2);
+        assert_eq!(third.cached_nodes, 0);
+
+        std::fs::remove_dir_all(engine.cache.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_volatile_node_always_runs() {
+        let sum_calls = Arc::new(AtomicUsize::new(0));
+        let subtract_calls = Arc::new(AtomicUsize::new(0));
+        let sum_port = spawn_node(MathNode { subtract: false, calls: sum_calls.clone() }).await;
+        let subtract_port = spawn_node(MathNode { subtract: true, calls: subtract_calls.clone() }).await;
+
+        let volatile = CachePolicyConfig { policy: Some(ndnm_core::CacheMode::Volatile), ..Default::default() };
+        let engine = setup_engine("ndnm_test_engine_volatile")
+            .with_cache_policies(HashMap::from([(sum_port, volatile)]));
+
+        engine.execute(sum_subtract_request(sum_port, subtract_port, json!([10, 20, 5]))).await.unwrap();
+        let second = engine
+            .execute(sum_subtract_request(sum_port, subtract_port, json!([10, 20, 5])))
+            .await
+            .unwrap();
+
+        // sum-1 roda de novo; o output saiu igual, então o subtract-1 (pure) continua no cache
+        assert_eq!(sum_calls.load(Ordering::SeqCst), 2);
+        assert_eq!(subtract_calls.load(Ordering::SeqCst), 1);
+        assert_eq!((second.executed_nodes, second.cached_nodes), (1, 1));
+
+        std::fs::remove_dir_all(engine.cache.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_result_collects_terminal_outputs() {
+        let sum_port = spawn_node(MathNode { subtract: false, calls: Arc::new(AtomicUsize::new(0)) }).await;
+        let subtract_port = spawn_node(MathNode { subtract: true, calls: Arc::new(AtomicUsize::new(0)) }).await;
+        let engine = setup_engine("ndnm_test_engine_outputs");
+
+        // subtract-1 está ligado no Play; sum-1 é marcado como output no data
+        let mut request = sum_subtract_request(sum_port, subtract_port, json!([10, 20, 5]));
+        request.graph.nodes[0].data.insert("output".to_string(), json!(true));
+
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let result = engine.execute_run(request, RunContext::new().with_events(tx)).await.unwrap();
+        assert_eq!(
+            result.outputs,
+            HashMap::from([
+                ("sum-1".to_string(), json!({"response": 35})),
+                ("subtract-1".to_string(), json!({"response": 20})),
+            ])
+        );
+
+        // Cada node que terminou mandou o output pelo canal (NODE_OUTPUT)
+        let node_outputs: Vec<(String, serde_json::Value)> = std::iter::from_fn(|| rx.try_recv().ok())
+            .filter_map(|event| match event {
+                RunEvent::NodeOutput { node_id, output, .. } => Some((node_id, output)),
+                RunEvent::Status(_) => None,
+            })
+            .collect();
+        assert_eq!(node_outputs, vec![
+            ("sum-1".to_string(), json!({"response": 35})),
+            ("subtract-1".to_string(), json!({"response": 20})),
+        ]);
+
+        std::fs::remove_dir_all(engine.cache.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_execute_selected_nodes() {
+        use crate::execution::selection::{NodeSelection, SelectionMode};
+
+        let sum_calls = Arc::new(AtomicUsize::new(0));
+        let subtract_calls = Arc::new(AtomicUsize::new(0));
+        let sum_port = spawn_node(MathNode { subtract: false, calls: sum_calls.clone() }).await;
+        let subtract_port = spawn_node(MathNode { subtract: true, calls: subtract_calls.clone() }).await;
+        let engine = setup_engine("ndnm_test_engine_selection");
+
+        let select = |mode| {
+            let mut request = sum_subtract_request(sum_port, subtract_port, json!([10, 20, 5]));
+            request.play_node_id = None;
+            request.selection = Some(NodeSelection { node_ids: vec!["subtract-1".to_string()], mode });
+            request
+        };
+
+        // Sem output do sum-1 em cache, self-only não tem com o que rodar
+        let error = engine.execute(select(SelectionMode::SelfOnlyUsingCachedInputs)).await.unwrap_err();
+        assert!(error.message.contains("sum-1"));
+
+        // upstream-and-self roda o sum-1 e o subtract-1 (e não o Play)
+        let first = engine.execute(select(SelectionMode::UpstreamAndSelf)).await.unwrap();
+        assert_eq!(first.total_nodes, 2);
+        assert_eq!(first.output, None);
+        assert_eq!(first.outputs, HashMap::from([("subtract-1".to_string(), json!({"response": 20}))]));
+
+        // Histórico mostra a seleção (sem Play inventado) e o replay roda a mesma seleção
+        let summary = &crate::execution::history::list_runs(&engine.cache, None, Some(1))[0];
+        assert_eq!(summary.play_node_id, None);
+        assert_eq!(summary.selection, Some(vec!["subtract-1".to_string()]));
+        let (replay, _) = crate::execution::history::replay_request(&engine.cache, &first.run_id).unwrap();
+        assert_eq!(replay.play_node_id, None);
+        assert!(replay.selection.is_some());
+
+        // self-only: sum-1 vem do último output; subtract-1 roda de novo mesmo com o hash igual
+        let second = engine.execute(select(SelectionMode::SelfOnlyUsingCachedInputs)).await.unwrap();
+        assert_eq!((second.total_nodes, second.executed_nodes), (1, 1));
+        assert_eq!(second.outputs["subtract-1"], json!({"response": 20}));
+        assert_eq!(sum_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(subtract_calls.load(Ordering::SeqCst), 2);
+
+        std::fs::remove_dir_all(engine.cache.root()).unwrap();
+    }
+
+    /// Node de teste que sempre falha com BadRequest (vira ErrorEnvelope no ndnm-core)
+    struct FailingNode;
+
+    #[async_trait]
+    impl Node for FailingNode {
+        type Input = serde_json::Value;
+        type Output = MathOutput;
+
+        async fn process(&self, _input: Self::Input) -> Result<Self::Output, AppError> {
+            Err(AppError::bad("Division by zero"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failure_reports_run_id_and_failing_node() {
+        let sum_port = spawn_node(MathNode { subtract: false, calls: Arc::new(AtomicUsize::new(0)) }).await;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let failing_port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            axum::serve(listener, ndnm_core::router(FailingNode).into_make_service()).await.unwrap();
+        });
+
+        let engine = setup_engine("ndnm_test_engine_failure");
+        let context = RunContext::new();
+        let run_id = context.run_id.clone();
+
+        let error = engine
+            .execute_run(sum_subtract_request(sum_port, failing_port, json!([10, 20, 5])), context)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.run_id, run_id);
+        assert_eq!(error.completed_nodes, vec!["sum-1".to_string()]);
+
+        let failed = error.failed_node.unwrap();
+        assert_eq!(failed.node_id, "subtract-1");
+        assert_eq!(failed.label, "subtract-1");
+        assert_eq!(failed.http_status, Some(400));
+        assert_eq!(failed.code.as_deref(), Some("BAD_REQUEST"));
+        assert_eq!(failed.message, "Division by zero");
+
+        std::fs::remove_dir_all(engine.cache.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_continue_on_error_runs_independent_branches() {
+        let math_port = spawn_node(MathNode { subtract: false, calls: Arc::new(AtomicUsize::new(0)) }).await;
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let failing_port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            axum::serve(listener, ndnm_core::router(FailingNode).into_make_service()).await.unwrap();
+        });
+
+        // broken → after-broken → play   e   independent → play
+        let mut request = ExecutionRequest {
+            play_node_id: Some("play".to_string()),
+            workspace_id: "test-workspace".to_string(),
+            graph: WorkflowGraph {
+                nodes: vec![
+                    node_on("broken", "fail", failing_port, json!({})),
+                    node_on("after-broken", "add", math_port, json!({"variables": [0, 1]})),
+                    node_on("independent", "add", math_port, json!({"variables": [2, 3]})),
+                    node_on("play", "playButton", 3020, json!({})),
+                ],
+                connections: vec![
+                    conn("broken", "after-broken"),
+                    conn("after-broken", "play"),
+                    conn("independent", "play"),
+                ],
+            },
+            max_parallel_nodes: None,
+            on_error: ErrorMode::Continue,
+            trigger_payload: None,
+            selection: None,
+        };
+
+        let engine = setup_engine("ndnm_test_engine_continue");
+        let result = engine.execute(request.clone()).await.unwrap();
+
+        assert_eq!(result.status, ExecutionState::Failed);
+        assert_eq!(result.completed_nodes, vec!["independent".to_string()]);
+        let status_of = |id: &str| result.nodes.iter().find(|n| n.node_id == id).unwrap().status;
+        assert_eq!(status_of("broken"), NodeExecutionStatus::Failed);
+        assert_eq!(status_of("after-broken"), NodeExecutionStatus::Skipped);
+        assert_eq!(status_of("independent"), NodeExecutionStatus::Completed);
+
+        // Modo padrão (abort): a mesma falha vira Err
+        request.on_error = ErrorMode::Abort;
+        assert!(engine.execute(request).await.is_err());
+
+        std::fs::remove_dir_all(engine.cache.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_play_cascade_runs_child_with_parent_output() {
+        let sum_port = spawn_node(MathNode { subtract: false, calls: Arc::new(AtomicUsize::new(0)) }).await;
+        let subtract_port = spawn_node(MathNode { subtract: true, calls: Arc::new(AtomicUsize::new(0)) }).await;
+
+        // sum-1 → play-a → subtract-1 → play-b
+        let request = ExecutionRequest {
+            play_node_id: Some("play-a".to_string()),
+            workspace_id: "test-workspace".to_string(),
+            graph: WorkflowGraph {
+                nodes: vec![
+                    node_on("sum-1", "add", sum_port, json!({"variables": [10, 20, 5]})),
+                    node_on("play-a", "playButton", 3020, json!({})),
+                    node_on("subtract-1", "subtract", subtract_port, json!({"variables": [100, 15]})),
+                    node_on("play-b", "playButton", 3020, json!({})),
+                ],
+                connections: vec![
+                    conn("sum-1", "play-a"),
+                    conn("play-a", "subtract-1"),
+                    conn("subtract-1", "play-b"),
+                ],
+            },
+            max_parallel_nodes: None,
+            on_error: Default::default(),
+            trigger_payload: None,
+            selection: None,
+        };
+
+        let engine = setup_engine("ndnm_test_engine_cascade");
+        let result = engine.execute_cascade(request, RunContext::new()).await.unwrap();
+
+        // Run pai só tem a cadeia do play-a
+        assert_eq!(result.completed_nodes, vec!["sum-1".to_string()]);
+        assert_eq!(result.output, Some(json!({"outputs": [35]})));
+
+        // Run filho: subtract-1 recebe o 35 que passou pelo play-a → 35 - 15 = 20
+        assert_eq!(result.child_runs.len(), 1);
+        let child = &result.child_runs[0];
+        assert_eq!(child.play_node_id, "play-b");
+        assert_eq!(child.triggered_by, "play-a");
+        assert_eq!(child.status, ExecutionState::Completed);
+        assert_ne!(child.run_id, result.run_id);
+
+        let output = std::fs::read_to_string(engine.cache.node_dir(&child.run_id, "subtract-1").join("output.json")).unwrap();
+        assert_eq!(serde_json::from_str::<serde_json::Value>(&output).unwrap(), json!({"response": 20}));
+
+        let metadata = std::fs::read_to_string(engine.cache.run_dir(&child.run_id).join("metadata.json")).unwrap();
+        let metadata: RunMetadata = serde_json::from_str(&metadata).unwrap();
+        assert_eq!(metadata.parent_run_id.as_deref(), Some(result.run_id.as_str()));
+
+        std::fs::remove_dir_all(engine.cache.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_trigger_payload_feeds_downstream_chain() {
+        let subtract_port = spawn_node(MathNode { subtract: true, calls: Arc::new(AtomicUsize::new(0)) }).await;
+
+        // hook-1 (payload 50) → subtract-1 → play-b
+        let request = ExecutionRequest {
+            play_node_id: Some("hook-1".to_string()),
+            workspace_id: "test-workspace".to_string(),
+            graph: WorkflowGraph {
+                nodes: vec![
+                    node_on("hook-1", "playWebhook", 3023, json!({})),
+                    node_on("subtract-1", "subtract", subtract_port, json!({"variables": [100, 15]})),
+                    node_on("play-b", "comfyPlay", 3021, json!({})),
+                ],
+                connections: vec![conn("hook-1", "subtract-1"), conn("subtract-1", "play-b")],
+            },
+            max_parallel_nodes: None,
+            on_error: Default::default(),
+            trigger_payload: Some(json!(50)),
+            selection: None,
+        };
+
+        let engine = setup_engine("ndnm_test_engine_trigger_payload");
+        let result = engine.execute_cascade(request, RunContext::new()).await.unwrap();
+
+        assert_eq!(result.output, Some(json!({"outputs": [50]})));
+        // 50 - 15 = 35, que chega no play-b
+        assert_eq!(result.child_runs.len(), 1);
+        assert_eq!(result.child_runs[0].output, Some(json!({"outputs": [35]})));
+
+        std::fs::remove_dir_all(engine.cache.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_trigger_payload_runs_chain_without_play() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let subtract_port = spawn_node(MathNode { subtract: true, calls: calls.clone() }).await;
+
+        // hook-1 (payload 50) → subtract-1, sem outro Play no caminho
+        let request = ExecutionRequest {
+            play_node_id: Some("hook-1".to_string()),
+            workspace_id: "test-workspace".to_string(),
+            graph: WorkflowGraph {
+                nodes: vec![
+                    node_on("hook-1", "playWebhook", 3023, json!({})),
+                    node_on("subtract-1", "subtract", subtract_port, json!({"variables": [100, 15]})),
+                ],
+                connections: vec![conn("hook-1", "subtract-1")],
+            },
+            max_parallel_nodes: None,
+            on_error: Default::default(),
+            trigger_payload: Some(json!(50)),
+            selection: None,
+        };
+
+        let engine = setup_engine("ndnm_test_engine_trigger_tail");
+        let result = engine.execute_cascade(request.clone(), RunContext::new()).await.unwrap();
+
+        assert_eq!(result.completed_nodes, vec!["subtract-1".to_string()]);
+        assert_eq!(result.outputs, HashMap::from([("subtract-1".to_string(), json!({"response": 35}))]));
+        assert!(result.child_runs.is_empty());
+        let outputs = crate::triggers::webhook::terminal_outputs(&result, "hook-1");
+        assert_eq!(serde_json::Value::Object(outputs), json!({"subtract-1": {"response": 35}}));
+
+        // Sem payload (botão), o Play continua rodando só o que está ligado nele
+        let mut button = request;
+        button.trigger_payload = None;
+        let result = engine.execute_cascade(button, RunContext::new()).await.unwrap();
+        assert_eq!(result.total_nodes, 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        std::fs::remove_dir_all(engine.cache.root()).unwrap();
+    }
+
+    /// Node de teste que responde 500 nas primeiras `failures` chamadas
+    struct FlakyNode {
+        failures: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Node for FlakyNode {
+        type Input = serde_json::Value;
+        type Output = MathOutput;
+
+        async fn process(&self, _input: Self::Input) -> Result<Self::Output, AppError> {
+            let left = self.failures.load(Ordering::SeqCst);
+            if left > 0 {
+                self.failures.store(left - 1, Ordering::SeqCst);
+                return Err(AppError::Internal);
+            }
+            Ok(MathOutput { response: 7 })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_on_server_error_reports_attempts() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let node = FlakyNode { failures: Arc::new(AtomicUsize::new(2)) };
+        tokio::spawn(async move {
+            axum::serve(listener, ndnm_core::router(node).into_make_service()).await.unwrap();
+        });
+
+        let mut request = sum_subtract_request(port, port, json!([1]));
+        request.graph.nodes.retain(|n| n.id != "subtract-1");
+        request.graph.connections = vec![conn("sum-1", "play-1")];
+
+        // Sem retry (default): o primeiro 500 derruba o run
+        let engine = setup_engine("ndnm_test_engine_retry");
+        let error = engine.execute(request.clone()).await.unwrap_err();
+        assert_eq!(error.failed_node.unwrap().attempts, 1);
+
+        // Override no grafo: 2 retries em 5xx → o node falha mais uma vez e passa na segunda tentativa
+        request.graph.nodes[0].execution = ExecutionPolicyConfig {
+            max_retries: Some(2),
+            backoff_ms: Some(10),
+            retry_on: Some(ndnm_core::RetryOn::ServerError),
+            ..Default::default()
+        };
+        let result = engine.execute(request).await.unwrap();
+        assert_eq!(result.executed_nodes, 1);
+
+        let metadata = std::fs::read_to_string(engine.cache.run_dir(&result.run_id).join("metadata.json")).unwrap();
+        let metadata: RunMetadata = serde_json::from_str(&metadata).unwrap();
+        assert_eq!(metadata.nodes[0].attempts, 2);
+
+        std::fs::remove_dir_all(engine.cache.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_node_timeout_from_config() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+        let port = spawn_slow_node(&active, &max_active).await;
+
+        // config.yaml do node: timeout de 30ms (o SlowNode leva 150ms), 1 retry
+        let engine = setup_engine("ndnm_test_engine_timeout").with_node_policies(HashMap::from([(
+            port,
+            ExecutionPolicyConfig { timeout_ms: Some(30), max_retries: Some(1), backoff_ms: Some(1), ..Default::default() },
+        )]));
+
+        let mut request = sum_subtract_request(port, port, json!([1]));
+        request.graph.nodes.retain(|n| n.id != "subtract-1");
+        request.graph.connections = vec![conn("sum-1", "play-1")];
+
+        let error = engine.execute(request).await.unwrap_err();
+        let failed = error.failed_node.unwrap();
+        assert_eq!(failed.code.as_deref(), Some(TIMEOUT_CODE));
+        assert_eq!(failed.http_status, None);
+        assert_eq!(failed.attempts, 2);
+
+        std::fs::remove_dir_all(engine.cache.root()).unwrap();
+    }
+
+    /// Node de teste que demora e registra quantos estavam rodando ao mesmo tempo
+    struct SlowNode {
+        active: Arc<AtomicUsize>,
+        max_active: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Node for SlowNode {
+        type Input = serde_json::Value;
+        type Output = MathOutput;
+
+        async fn process(&self, _input: Self::Input) -> Result<Self::Output, AppError> {
+            let now = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_active.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(std::time::Duration::from_millis(150)).await;
+            self.active.fetch_sub(1, Ordering::SeqCst);
+            Ok(MathOutput { response: 1 })
+        }
+    }
+
+    /// Node que avisa quando recebe o /run e nunca responde (só sai cancelando o run)
+    struct StuckNode {
+        started: tokio::sync::mpsc::UnboundedSender<()>,
+    }
+
+    #[async_trait]
+    impl Node for StuckNode {
+        type Input = serde_json::Value;
+        type Output = MathOutput;
+
+        async fn process(&self, _input: Self::Input) -> Result<Self::Output, AppError> {
+            let _ = self.started.send(());
+            std::future::pending().await
+        }
+    }
+
+    async fn spawn_slow_node(active: &Arc<AtomicUsize>, max_active: &Arc<AtomicUsize>) -> u16 {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let node = SlowNode { active: active.clone(), max_active: max_active.clone() };
+        tokio::spawn(async move {
+            axum::serve(listener, ndnm_core::router(node).into_make_service()).await.unwrap();
+        });
+        port
+    }
+
+    /// Diamante A → (B, D) → C → Play, cada node numa porta
+    fn diamond_request(ports: [u16; 4], max_parallel_nodes: Option<usize>) -> ExecutionRequest {
+        let [a, b, d, c] = ports;
+        ExecutionRequest {
+            play_node_id: Some("play".to_string()),
+            workspace_id: "test-workspace".to_string(),
+            graph: WorkflowGraph {
+                nodes: vec![
+                    node_on("A", "slow", a, json!({"seed": rand::random::<u64>()})),
+                    node_on("B", "slow", b, json!({})),
+                    node_on("D", "slow", d, json!({})),
+                    node_on("C", "slow", c, json!({})),
+                    node_on("play", "playButton", 3020, json!({})),
+                ],
+                connections: vec![
+                    conn("A", "B"),
+                    conn("A", "D"),
+                    conn("B", "C"),
+                    conn("D", "C"),
+                    conn("C", "play"),
+                ],
+            },
+            max_parallel_nodes,
+            on_error: Default::default(),
+            trigger_payload: None,
+            selection: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_independent_branches_run_in_parallel() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+        let mut ports = [0u16; 4];
+        for port in ports.iter_mut() {
+            *port = spawn_slow_node(&active, &max_active).await;
+        }
+
+        let engine = setup_engine("ndnm_test_engine_parallel");
+        let result = engine.execute(diamond_request(ports, None)).await.unwrap();
+
+        assert_eq!(result.executed_nodes, 4);
+        // B e D rodaram ao mesmo tempo
+        assert_eq!(max_active.load(Ordering::SeqCst), 2);
+
+        std::fs::remove_dir_all(engine.cache.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run_concurrency_limit() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+        let mut ports = [0u16; 4];
+        for port in ports.iter_mut() {
+            *port = spawn_slow_node(&active, &max_active).await;
+        }
+
+        // Limite de 1 por run = volta a ser sequencial
+        let engine = setup_engine("ndnm_test_engine_run_limit");
+        let result = engine.execute(diamond_request(ports, Some(1))).await.unwrap();
+
+        assert_eq!(result.executed_nodes, 4);
+        assert_eq!(max_active.load(Ordering::SeqCst), 1);
+
+        std::fs::remove_dir_all(engine.cache.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_port_limit_serializes_same_node() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+        let shared = spawn_slow_node(&active, &max_active).await;
+        let a = spawn_slow_node(&active, &max_active).await;
+        let c = spawn_slow_node(&active, &max_active).await;
+
+        // B e D apontam pra mesma porta: sem max_concurrency rodam juntos
+        let engine = setup_engine("ndnm_test_engine_port_free");
+        let result = engine.execute(diamond_request([a, shared, shared, c], None)).await.unwrap();
+        assert_eq!(result.executed_nodes, 4);
+        assert_eq!(max_active.load(Ordering::SeqCst), 2);
+        std::fs::remove_dir_all(engine.cache.root()).unwrap();
+
+        // Com max_concurrency: 1 (ex: um node Python de GPU), um de cada vez
+        max_active.store(0, Ordering::SeqCst);
+        let engine = setup_engine("ndnm_test_engine_port_limit").with_port_limits(HashMap::from([(shared, 1)]));
+        let result = engine.execute(diamond_request([a, shared, shared, c], None)).await.unwrap();
+        assert_eq!(result.executed_nodes, 4);
+        assert_eq!(max_active.load(Ordering::SeqCst), 1);
+        std::fs::remove_dir_all(engine.cache.root()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cancel_stops_run_and_reports_completed_nodes() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+        let mut ports = [0u16; 4];
+        ports[0] = spawn_slow_node(&active, &max_active).await;
+        ports[3] = spawn_slow_node(&active, &max_active).await;
+
+        // B e D avisam quando começam e nunca respondem
+        let (started_tx, mut started_rx) = tokio::sync::mpsc::unbounded_channel();
+        for port in &mut ports[1..3] {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            *port = listener.local_addr().unwrap().port();
+            let node = StuckNode { started: started_tx.clone() };
+            tokio::spawn(async move {
+                axum::serve(listener, ndnm_core::router(node).into_make_service()).await.unwrap();
+            });
+        }
+
+        let engine = setup_engine("ndnm_test_engine_cancel");
+        let context = RunContext::new();
+        let cancel = context.cancel.clone();
+
+        // Cancela com B e D em andamento (A já terminou)
+        tokio::spawn(async move {
+            for _ in 0..2 {
+                started_rx.recv().await;
+            }
+            cancel.cancel();
+        });
+
+        let result = engine.execute_run(diamond_request(ports, None), context).await.unwrap();
+
+        assert_eq!(result.status, ExecutionState::Cancelled);
+        assert_eq!(result.completed_nodes, vec!["A".to_string()]);
+        assert_eq!(result.executed_nodes, 1);
+
+        let metadata = std::fs::read_to_string(engine.cache.run_dir(&result.run_id).join("metadata.json")).unwrap();
+        let metadata: serde_json::Value = serde_json::from_str(&metadata).unwrap();
+        assert_eq!(metadata["status"], "cancelled");
+
+        std::fs::remove_dir_all(engine.cache.root()).unwrap();
+    }
+}
@@ -0,0 +1,132 @@
+// ndnm-brazil/src/execution/history.rs
+//
+// Histórico de runs, lido direto das pastas em workspaces/runs
+// - metadata.json: status, duração, contagens e nodes pedidos (gravado no fim do run; basta pra listagem)
+// - snapshot.json: grafo executado (base do replay)
+// - <node>/input.json + output.json: o que cada node recebeu e devolveu
+
+use super::cache::{RunCache, RunMetadata, RunNodeMetadata};
+use super::types::{ExecutionRequest, ExecutionState, RunSnapshot};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Um run na listagem
+#[derive(Debug, Clone, Serialize)]
+pub struct RunSummary {
+    pub run_id: String,
+    pub workspace_id: String,
+    /// None = EXECUTE_NODES (os nodes pedidos estão em `selection`)
+    pub play_node_id: Option<String>,
+    /// Nodes pedidos pelo EXECUTE_NODES
+    pub selection: Option<Vec<String>>,
+    pub status: ExecutionState,
+    pub started_at: String,
+    pub finished_at: String,
+    pub duration_ms: u64,
+    pub total_nodes: usize,
+    pub executed_nodes: usize,
+    pub cached_nodes: usize,
+    pub error: Option<String>,
+    pub parent_run_id: Option<String>,
+    pub pinned: bool,
+    /// Tem snapshot.json (dá pra fazer replay)
+    pub replayable: bool,
+}
+
+/// Um node dentro do detalhe de um run
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeDetail {
+    #[serde(flatten)]
+    pub metadata: RunNodeMetadata,
+    pub input: Option<Value>,
+    pub output: Option<Value>,
+}
+
+/// Detalhe de um run: resumo + input/output/tempo de cada node + grafo executado
+#[derive(Debug, Clone, Serialize)]
+pub struct RunDetail {
+    #[serde(flatten)]
+    pub summary: RunSummary,
+    pub nodes: Vec<NodeDetail>,
+    pub snapshot: Option<RunSnapshot>,
+}
+
+fn summary(cache: &RunCache, metadata: &RunMetadata) -> RunSummary {
+    RunSummary {
+        run_id: metadata.run_id.clone(),
+        workspace_id: metadata.workspace_id.clone(),
+        play_node_id: metadata.play_node_id.clone(),
+        selection: metadata.selection.clone(),
+        status: metadata.status.clone(),
+        started_at: metadata.started_at.clone(),
+        finished_at: metadata.finished_at.clone(),
+        duration_ms: metadata.duration_ms,
+        total_nodes: metadata.total_nodes,
+        executed_nodes: metadata.executed_nodes,
+        cached_nodes: metadata.cached_nodes,
+        error: metadata.error.clone(),
+        parent_run_id: metadata.parent_run_id.clone(),
+        pinned: cache.is_pinned(&metadata.run_id),
+        replayable: cache.has_snapshot(&metadata.run_id),
+    }
+}
+
+/// Runs terminados (mais recentes primeiro), opcionalmente só de um workspace
+pub fn list_runs(cache: &RunCache, workspace_id: Option<&str>, limit: Option<usize>) -> Vec<RunSummary> {
+    cache
+        .list_runs()
+        .iter()
+        .filter_map(|run_id| cache.read_metadata(run_id))
+        .filter(|m| workspace_id.is_none_or(|ws| m.workspace_id == ws))
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|m| summary(cache, &m))
+        .collect()
+}
+
+/// Detalhe de um run terminado (None = não existe ou ainda está rodando)
+pub fn run_detail(cache: &RunCache, run_id: &str) -> Option<RunDetail> {
+    if !RunCache::is_valid_run_id(run_id) {
+        return None;
+    }
+    let metadata = cache.read_metadata(run_id)?;
+
+    let nodes = metadata
+        .nodes
+        .iter()
+        .map(|node| {
+            let (input, output) = cache.read_node_io(run_id, &node.node_id);
+            NodeDetail { metadata: node.clone(), input, output }
+        })
+        .collect();
+
+    Some(RunDetail {
+        summary: summary(cache, &metadata),
+        nodes,
+        snapshot: cache.read_snapshot(run_id),
+    })
+}
+
+/// Monta o request pra rodar um run de novo, com o mesmo grafo/payload/seed do original
+pub fn replay_request(cache: &RunCache, run_id: &str) -> Result<(ExecutionRequest, HashMap<String, Value>), String> {
+    if !RunCache::is_valid_run_id(run_id) {
+        return Err(format!("Run não encontrado: {}", run_id));
+    }
+    let metadata = cache
+        .read_metadata(run_id)
+        .ok_or_else(|| format!("Run não encontrado (ou ainda rodando): {}", run_id))?;
+    let snapshot = cache
+        .read_snapshot(run_id)
+        .ok_or_else(|| format!("Run {} não tem snapshot do grafo (gravado antes do histórico)", run_id))?;
+
+    let request = ExecutionRequest {
+        play_node_id: metadata.play_node_id,
+        workspace_id: metadata.workspace_id,
+        graph: snapshot.graph,
+        max_parallel_nodes: snapshot.max_parallel_nodes,
+        on_error: snapshot.on_error,
+        trigger_payload: snapshot.trigger_payload,
+        selection: snapshot.selection,
+    };
+    Ok((request, snapshot.seed))
+}
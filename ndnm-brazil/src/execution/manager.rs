@@ -0,0 +1,287 @@
+// ndnm-brazil/src/execution/manager.rs
+//
+// Gerenciador de runs
+// Cada execução roda numa task própria; o loop do WebSocket só dispara e segue a vida.
+// Mantém o registro dos runs ativos (por run_id) enquanto eles executam,
+// junto com o token de cancelamento de cada um (CANCEL_RUN).
+
+use super::cache::RunCache;
+use super::events::EventSender;
+use super::executor::{ChildRuns, ExecutionEngine, RunContext};
+use super::error::ExecutionError;
+use super::retention::{self, CleanupReport, RetentionPolicy};
+use super::types::{ExecutionRequest, ExecutionResult};
+use chrono::Utc;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio_util::sync::CancellationToken;
+
+/// Info de um run em andamento
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveRun {
+    pub run_id: String,
+    /// None = EXECUTE_NODES
+    pub play_node_id: Option<String>,
+    pub workspace_id: String,
+    pub started_at: String,
+}
+
+/// Entrada do registro: info pública + token pra cancelar (o do run e os dos filhos da cascata)
+#[derive(Debug)]
+struct RunEntry {
+    info: ActiveRun,
+    cancel: CancellationToken,
+    children: ChildRuns,
+}
+
+#[derive(Debug)]
+pub struct RunManager {
+    engine: Arc<ExecutionEngine>,
+    /// Runs ativos: run_id → entrada
+    active: Mutex<HashMap<String, RunEntry>>,
+}
+
+impl RunManager {
+    pub fn new(engine: Arc<ExecutionEngine>) -> Self {
+        Self {
+            engine,
+            active: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Dispara um run em background e devolve o run_id na hora.
+    /// `seed` = outputs de Plays já prontos (replay de um run filho de cascata; vazio nos runs normais).
+    /// `on_finish` recebe o resultado quando o run termina (já fora do registro de ativos).
+    pub fn start_seeded<F, Fut>(
+        self: &Arc<Self>,
+        request: ExecutionRequest,
+        seed: HashMap<String, Value>,
+        events: Option<EventSender>,
+        on_finish: F,
+    ) -> String
+    where
+        F: FnOnce(Result<ExecutionResult, ExecutionError>) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let mut context = RunContext::new();
+        context.seed = seed;
+        if let Some(events) = events {
+            context = context.with_events(events);
+        }
+        let run_id = context.run_id.clone();
+
+        // Registra antes de spawnar: a task remove quando terminar
+        self.lock_active().insert(
+            run_id.clone(),
+            RunEntry {
+                info: ActiveRun {
+                    run_id: run_id.clone(),
+                    play_node_id: request.play_node_id.clone(),
+                    workspace_id: request.workspace_id.clone(),
+                    started_at: Utc::now().to_rfc3339(),
+                },
+                cancel: context.cancel.clone(),
+                children: context.children.clone(),
+            },
+        );
+
+        let manager = Arc::clone(self);
+        tokio::spawn(async move {
+            let run_id = context.run_id.clone();
+            let result = manager.engine.execute_cascade(request, context).await;
+            manager.lock_active().remove(&run_id);
+            on_finish(result).await;
+        });
+
+        run_id
+    }
+
+    /// Lista os runs em andamento (mais antigos primeiro)
+    pub fn active_runs(&self) -> Vec<ActiveRun> {
+        let mut runs: Vec<ActiveRun> = self.lock_active().values().map(|e| e.info.clone()).collect();
+        runs.sort_by(|a, b| a.run_id.cmp(&b.run_id));
+        runs
+    }
+
+    /// Pede o cancelamento de um run. Retorna false se o run não existe (ou já terminou).
+    /// O run sai do registro quando o engine terminar de parar.
+    /// Run filho de uma cascata para sozinho: os Plays depois dele não disparam, o resto da cascata segue.
+    pub fn cancel(&self, run_id: &str) -> bool {
+        let active = self.lock_active();
+        match active.get(run_id) {
+            Some(entry) => {
+                entry.cancel.cancel();
+                true
+            }
+            None => active.values().any(|entry| entry.children.cancel(run_id)),
+        }
+    }
+
+    /// Roda o Cache Cleaner sem encostar nos runs em andamento
+    pub fn cleanup(&self, policy: &RetentionPolicy) -> CleanupReport {
+        let active: HashSet<String> = self.lock_active().keys().cloned().collect();
+        retention::cleanup(self.engine.cache(), policy, &active, Utc::now())
+    }
+
+    /// Pasta de runs do engine
+    pub fn cache(&self) -> &RunCache {
+        self.engine.cache()
+    }
+
+    fn lock_active(&self) -> std::sync::MutexGuard<'_, HashMap<String, RunEntry>> {
+        self.active.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::types::test_graph::{conn, node_on};
+    use crate::execution::types::WorkflowGraph;
+    use serde_json::json;
+    use tokio::sync::oneshot;
+
+    /// Run que só tem o Play (não chama nenhum node)
+    fn play_only_request(play_id: &str) -> ExecutionRequest {
+        ExecutionRequest {
+            play_node_id: Some(play_id.to_string()),
+            workspace_id: "test-workspace".to_string(),
+            graph: WorkflowGraph {
+                nodes: vec![node_on(play_id, "playButton", 3020, json!({}))],
+                connections: vec![],
+            },
+            max_parallel_nodes: None,
+            on_error: Default::default(),
+            trigger_payload: None,
+            selection: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_runs_in_background_and_leave_registry() {
+        let root = std::env::temp_dir().join("ndnm_test_run_manager");
+        let engine = Arc::new(ExecutionEngine::with_cache(RunCache::new(root.clone())));
+        let manager = Arc::new(RunManager::new(engine));
+
+        let (done_tx, done_rx) = oneshot::channel();
+        let run_id = manager.start_seeded(play_only_request("play-1"), HashMap::new(), None, move |result| async move {
+            let _ = done_tx.send(result);
+        });
+
+        let result = done_rx.await.unwrap().unwrap();
+        assert_eq!(result.run_id, run_id);
+        assert!(manager.active_runs().is_empty());
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn test_several_runs_at_once() {
+        // Porta que aceita conexão mas nunca responde: os runs ficam "executando"
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let root = std::env::temp_dir().join("ndnm_test_run_manager_many");
+        let engine = Arc::new(ExecutionEngine::with_cache(RunCache::new(root.clone())));
+        let manager = Arc::new(RunManager::new(engine));
+
+        let mut ids = Vec::new();
+        for (i, play) in ["play-a", "play-b"].iter().enumerate() {
+            let mut request = play_only_request(play);
+            let slow = format!("slow-{}", i);
+            request.graph.nodes.push(node_on(&slow, "slow", port, json!({})));
+            request.graph.connections.push(conn(&slow, play));
+            ids.push(manager.start_seeded(request, HashMap::new(), None, |_| async {}));
+        }
+
+        // start_seeded() voltou na hora, com os dois runs ativos ao mesmo tempo
+        assert_ne!(ids[0], ids[1]);
+        let active: Vec<String> = manager.active_runs().into_iter().map(|r| r.run_id).collect();
+        assert_eq!(active.len(), 2);
+        assert!(ids.iter().all(|id| active.contains(id)));
+
+        drop(listener);
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_active_run() {
+        // Node que nunca responde: o run só termina se for cancelado
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let root = std::env::temp_dir().join("ndnm_test_run_manager_cancel");
+        let engine = Arc::new(ExecutionEngine::with_cache(RunCache::new(root.clone())));
+        let manager = Arc::new(RunManager::new(engine));
+
+        let mut request = play_only_request("play-1");
+        request.graph.nodes.push(node_on("stuck", "slow", port, json!({})));
+        request.graph.connections.push(conn("stuck", "play-1"));
+
+        let (done_tx, done_rx) = oneshot::channel();
+        let run_id = manager.start_seeded(request, HashMap::new(), None, move |result| async move {
+            let _ = done_tx.send(result);
+        });
+
+        assert!(!manager.cancel("run_que_nao_existe"));
+        assert!(manager.cancel(&run_id));
+
+        let result = done_rx.await.unwrap().unwrap();
+        assert_eq!(result.status, crate::execution::types::ExecutionState::Cancelled);
+        assert!(result.completed_nodes.is_empty());
+        assert!(manager.active_runs().is_empty());
+
+        drop(listener);
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_child_run_of_cascade() {
+        // play-1 → stuck → play-2: o run filho (Play 2) fica preso no node que nunca responde
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let root = std::env::temp_dir().join("ndnm_test_run_manager_cancel_child");
+        let engine = Arc::new(ExecutionEngine::with_cache(RunCache::new(root.clone())));
+        let manager = Arc::new(RunManager::new(engine));
+
+        let mut request = play_only_request("play-1");
+        for (id, node_type, port) in [("stuck", "slow", port), ("play-2", "playButton", 3020)] {
+            request.graph.nodes.push(node_on(id, node_type, port, json!({})));
+        }
+        for (from, to) in [("play-1", "stuck"), ("stuck", "play-2")] {
+            request.graph.connections.push(conn(from, to));
+        }
+
+        // Payload de gatilho: o Play 1 sai com ele na saída 0
+        request.trigger_payload = Some(json!(1));
+
+        let (events_tx, mut events_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (done_tx, done_rx) = oneshot::channel();
+        let run_id = manager.start_seeded(request, HashMap::new(), Some(events_tx), move |result| async move {
+            let _ = done_tx.send(result);
+        });
+
+        // O id do filho chega no EXECUTION_STATUS, como no frontend
+        let child_id = loop {
+            if let Some(crate::execution::events::RunEvent::Status(status)) = events_rx.recv().await {
+                if status.run_id != run_id {
+                    break status.run_id;
+                }
+            }
+        };
+        assert!(manager.cancel(&child_id));
+
+        let result = done_rx.await.unwrap().unwrap();
+        assert_eq!(result.status, crate::execution::types::ExecutionState::Completed);
+        assert_eq!(result.child_runs[0].run_id, child_id);
+        assert_eq!(result.child_runs[0].status, crate::execution::types::ExecutionState::Cancelled);
+        assert!(!manager.cancel(&child_id));
+
+        drop(listener);
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
@@ -1,11 +1,25 @@
 // ndnm-brazil/src/execution/mod.rs
 //
 // Motor de execução do ndnm - Fase 2
-// Responsável por executar nodes sequencialmente seguindo o grafo de dependências
+// Responsável por executar nodes seguindo o grafo de dependências (branches independentes em paralelo)
 
 pub mod types;
 pub mod resolver;
 pub mod executor;
+pub mod dataflow;
+pub mod cache;
+pub mod cache_policy;
+pub mod scheduler;
+pub mod events;
+pub mod error;
+pub mod policy;
+pub mod cascade;
+pub mod manager;
+pub mod retention;
+pub mod history;
+pub mod selection;
 
 pub use types::*;
+pub use error::{ExecutionError, NodeFailure};
 pub use executor::ExecutionEngine;
+pub use manager::RunManager;
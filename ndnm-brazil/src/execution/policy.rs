@@ -0,0 +1,118 @@
+// ndnm-brazil/src/execution/policy.rs
+//
+// Política de execução por node: timeout e retries do /run
+// Ordem de prioridade: GraphNode.execution (grafo) → config.yaml do node → defaults daqui
+
+use super::error::NodeFailure;
+use ndnm_core::{ExecutionPolicyConfig, RetryOn};
+use std::time::Duration;
+
+/// Timeout padrão de um /run. Generoso: nodes tipo KSampler levam minutos.
+pub const DEFAULT_TIMEOUT_MS: u64 = 10 * 60 * 1000;
+
+/// Sem retry por padrão (nodes não são necessariamente idempotentes)
+pub const DEFAULT_MAX_RETRIES: u32 = 0;
+
+/// Espera antes do primeiro retry
+pub const DEFAULT_BACKOFF_MS: u64 = 500;
+
+/// Code usado no NodeFailure quando o /run estoura o timeout
+pub const TIMEOUT_CODE: &str = "TIMEOUT";
+
+/// Política efetiva de um node (já com os defaults aplicados)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionPolicy {
+    pub timeout: Duration,
+    pub max_retries: u32,
+    pub backoff: Duration,
+    pub retry_on: RetryOn,
+}
+
+impl ExecutionPolicy {
+    /// Junta as camadas: o override do grafo ganha do config.yaml, que ganha do default
+    pub fn resolve(node_config: Option<&ExecutionPolicyConfig>, graph_override: &ExecutionPolicyConfig) -> Self {
+        let empty = ExecutionPolicyConfig::default();
+        let config = node_config.unwrap_or(&empty);
+
+        Self {
+            timeout: Duration::from_millis(
+                graph_override.timeout_ms.or(config.timeout_ms).unwrap_or(DEFAULT_TIMEOUT_MS),
+            ),
+            max_retries: graph_override.max_retries.or(config.max_retries).unwrap_or(DEFAULT_MAX_RETRIES),
+            backoff: Duration::from_millis(
+                graph_override.backoff_ms.or(config.backoff_ms).unwrap_or(DEFAULT_BACKOFF_MS),
+            ),
+            retry_on: graph_override.retry_on.or(config.retry_on).unwrap_or(RetryOn::Connection),
+        }
+    }
+
+    /// Espera antes do retry `retry` (1 = primeiro retry): backoff, 2×backoff, 4×backoff...
+    pub fn backoff_for(&self, retry: u32) -> Duration {
+        let factor = 1u32 << retry.saturating_sub(1).min(16);
+        self.backoff.saturating_mul(factor)
+    }
+
+    /// A falha vale outra tentativa?
+    /// Sem status HTTP = conexão/timeout (sempre); 5xx só com `retry_on: server_error`.
+    /// 4xx nunca: o input está errado e vai continuar errado.
+    pub fn should_retry(&self, failure: &NodeFailure) -> bool {
+        match failure.http_status {
+            None => true,
+            Some(status) => self.retry_on == RetryOn::ServerError && (500..600).contains(&status),
+        }
+    }
+}
+
+impl Default for ExecutionPolicy {
+    fn default() -> Self {
+        Self::resolve(None, &ExecutionPolicyConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph_override_wins_over_node_config() {
+        let node_config = ExecutionPolicyConfig {
+            timeout_ms: Some(60_000),
+            max_retries: Some(2),
+            backoff_ms: None,
+            retry_on: Some(RetryOn::ServerError),
+        };
+        let graph_override = ExecutionPolicyConfig {
+            timeout_ms: Some(5_000),
+            ..Default::default()
+        };
+
+        let policy = ExecutionPolicy::resolve(Some(&node_config), &graph_override);
+        assert_eq!(policy.timeout, Duration::from_millis(5_000));
+        assert_eq!(policy.max_retries, 2);
+        assert_eq!(policy.backoff, Duration::from_millis(DEFAULT_BACKOFF_MS));
+        assert_eq!(policy.retry_on, RetryOn::ServerError);
+
+        assert_eq!(ExecutionPolicy::default().max_retries, DEFAULT_MAX_RETRIES);
+    }
+
+    #[test]
+    fn test_should_retry_and_backoff() {
+        let connection_only = ExecutionPolicy::default();
+        let server_error = ExecutionPolicy {
+            retry_on: RetryOn::ServerError,
+            ..ExecutionPolicy::default()
+        };
+
+        let refused = NodeFailure::new("n", "N", "connection refused");
+        let internal = NodeFailure::from_response("n", "N", 500, "");
+        let bad_request = NodeFailure::from_response("n", "N", 400, "");
+
+        assert!(connection_only.should_retry(&refused));
+        assert!(!connection_only.should_retry(&internal));
+        assert!(server_error.should_retry(&internal));
+        assert!(!server_error.should_retry(&bad_request));
+
+        assert_eq!(connection_only.backoff_for(1), Duration::from_millis(500));
+        assert_eq!(connection_only.backoff_for(3), Duration::from_millis(2000));
+    }
+}
@@ -5,9 +5,52 @@
 
 use super::types::{WorkflowGraph, GraphNode};
 use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Erros de resolução do grafo
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResolveError {
+    /// Node pedido (ou dependência) não existe no grafo
+    NodeNotFound(String),
+    /// Ciclo no grafo, na direção do fluxo de dados (ex: ["A", "B", "A"] = A → B → A)
+    Cycle(Vec<String>),
+    /// Conexão apontando pra node que não existe em `WorkflowGraph::nodes`
+    DanglingConnection {
+        from_node_id: String,
+        to_node_id: String,
+        missing_node_id: String,
+    },
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::NodeNotFound(id) => write!(f, "Node não encontrado: {}", id),
+            ResolveError::Cycle(ids) => write!(f, "Ciclo detectado: {}", ids.join(" → ")),
+            ResolveError::DanglingConnection { from_node_id, to_node_id, missing_node_id } => write!(
+                f,
+                "Conexão {} → {} aponta pra node inexistente: {}",
+                from_node_id, to_node_id, missing_node_id
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ResolveError {}
+
+/// Cor de cada node na busca em profundidade (detecção de ciclo)
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VisitState {
+    /// Na pilha atual (ainda resolvendo dependências) - se aparecer de novo, é ciclo
+    InProgress,
+    /// Já resolvido e adicionado na ordem
+    Done,
+}
 
 /// Resolvedor de dependências
 pub struct DependencyResolver<'a> {
+    /// Grafo original (pra validação das conexões)
+    graph: &'a WorkflowGraph,
     /// Mapa de node_id → GraphNode (pra busca rápida)
     node_map: HashMap<String, &'a GraphNode>,
     /// Mapa de node_id → lista de dependências (nodes que alimentam ele)
@@ -31,16 +74,34 @@ impl<'a> DependencyResolver<'a> {
         for conn in &graph.connections {
             dependencies
                 .entry(conn.to_node_id.clone())
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(conn.from_node_id.clone());
         }
 
         Self {
+            graph,
             node_map,
             dependencies,
         }
     }
 
+    /// Valida o grafo antes de executar qualquer coisa:
+    /// toda conexão precisa ligar dois nodes que existem
+    pub fn validate(&self) -> Result<(), ResolveError> {
+        for conn in &self.graph.connections {
+            for endpoint in [&conn.from_node_id, &conn.to_node_id] {
+                if !self.node_map.contains_key(endpoint) {
+                    return Err(ResolveError::DanglingConnection {
+                        from_node_id: conn.from_node_id.clone(),
+                        to_node_id: conn.to_node_id.clone(),
+                        missing_node_id: endpoint.clone(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Resolve dependências a partir de um node (ex: Play node)
     /// Retorna lista ordenada de nodes pra executar (depth-first)
     ///
@@ -55,57 +116,105 @@ impl<'a> DependencyResolver<'a> {
     ///
     /// Resultado: [A, B, D, C]
     /// (A primeiro porque B e D dependem dele, depois B e D por ordem de ID, depois C)
-    pub fn resolve_from(&self, start_node_id: &str) -> Result<Vec<&'a GraphNode>, String> {
-        let mut visited = HashSet::new();
+    #[cfg(test)]
+    pub fn resolve_from(&self, start_node_id: &str) -> Result<Vec<&'a GraphNode>, ResolveError> {
+        self.resolve_from_excluding(start_node_id, &HashSet::new())
+    }
+
+    /// Igual ao `resolve_from`, mas não entra nos nodes de `excluded` (nem nas dependências deles).
+    /// Usado nos Plays em cascata: o Play anterior e a cadeia dele já rodaram em outro run.
+    pub fn resolve_from_excluding(
+        &self,
+        start_node_id: &str,
+        excluded: &HashSet<String>,
+    ) -> Result<Vec<&'a GraphNode>, ResolveError> {
+        // Excluídos contam como já resolvidos, mas nunca entram na ordem
+        let mut states: HashMap<String, VisitState> = excluded
+            .iter()
+            .filter(|id| id.as_str() != start_node_id)
+            .map(|id| (id.clone(), VisitState::Done))
+            .collect();
+        let mut path = Vec::new();
         let mut execution_order = Vec::new();
 
-        self.visit_node(start_node_id, &mut visited, &mut execution_order)?;
+        self.visit_node(start_node_id, &mut states, &mut path, &mut execution_order)?;
 
         Ok(execution_order)
     }
 
-    /// Visita um node recursivamente (depth-first)
+    /// Visita um node recursivamente (depth-first, com detecção de ciclo em três cores:
+    /// não visitado / em andamento / resolvido)
     fn visit_node(
         &self,
         node_id: &str,
-        visited: &mut HashSet<String>,
+        states: &mut HashMap<String, VisitState>,
+        path: &mut Vec<String>,
         execution_order: &mut Vec<&'a GraphNode>,
-    ) -> Result<(), String> {
-        // Se já visitou, pula (evita loops infinitos)
-        if visited.contains(node_id) {
-            return Ok(());
+    ) -> Result<(), ResolveError> {
+        match states.get(node_id) {
+            // Já resolvido por outro caminho (ex: A no diamante)
+            Some(VisitState::Done) => return Ok(()),
+            // Voltou pra um node que ainda está na pilha → ciclo
+            Some(VisitState::InProgress) => return Err(ResolveError::Cycle(Self::cycle_from_path(path, node_id))),
+            None => {}
         }
 
-        // Marca como visitado
-        visited.insert(node_id.to_string());
-
         // Busca o node
         let node = self
             .node_map
             .get(node_id)
-            .ok_or_else(|| format!("Node não encontrado: {}", node_id))?;
+            .ok_or_else(|| ResolveError::NodeNotFound(node_id.to_string()))?;
+
+        states.insert(node_id.to_string(), VisitState::InProgress);
+        path.push(node_id.to_string());
 
         // Primeiro, resolve todas as dependências (depth-first!)
         if let Some(deps) = self.dependencies.get(node_id) {
             // Ordena dependências por ID (pra ser determinístico)
             let mut sorted_deps = deps.clone();
             sorted_deps.sort();
+            sorted_deps.dedup();
 
             for dep_id in sorted_deps {
-                self.visit_node(&dep_id, visited, execution_order)?;
+                self.visit_node(&dep_id, states, path, execution_order)?;
             }
         }
 
+        path.pop();
+        states.insert(node_id.to_string(), VisitState::Done);
+
         // Depois de resolver dependências, adiciona o node atual à ordem de execução
         execution_order.push(node);
 
         Ok(())
     }
 
-    /// Resolve múltiplos nodes de partida (útil se tiver múltiplos Plays)
-    #[allow(dead_code)]
-    pub fn resolve_from_multiple(&self, start_node_ids: &[String]) -> Result<Vec<&'a GraphNode>, String> {
-        let mut visited = HashSet::new();
+    /// Extrai o ciclo da pilha de visita.
+    /// A pilha está na direção das dependências (node → quem alimenta ele),
+    /// então inverte pra devolver na direção do fluxo: [X, Y, Z, X] = X → Y → Z → X
+    fn cycle_from_path(path: &[String], repeated_id: &str) -> Vec<String> {
+        let start = path.iter().position(|id| id == repeated_id).unwrap_or(0);
+        let segment = &path[start..];
+
+        let mut cycle = vec![repeated_id.to_string()];
+        cycle.extend(segment.iter().skip(1).rev().cloned());
+        cycle.push(repeated_id.to_string());
+        cycle
+    }
+
+    /// Resolve múltiplos nodes de partida (EXECUTE_NODES, Play com payload),
+    /// sem entrar nos nodes de `excluded` (EXECUTE_NODES self-only)
+    pub fn resolve_from_multiple_excluding(
+        &self,
+        start_node_ids: &[String],
+        excluded: &HashSet<String>,
+    ) -> Result<Vec<&'a GraphNode>, ResolveError> {
+        let mut states: HashMap<String, VisitState> = excluded
+            .iter()
+            .filter(|id| !start_node_ids.contains(id))
+            .map(|id| (id.clone(), VisitState::Done))
+            .collect();
+        let mut path = Vec::new();
         let mut execution_order = Vec::new();
 
         // Ordena por ID pra ser determinístico
@@ -113,7 +222,7 @@ impl<'a> DependencyResolver<'a> {
         sorted_starts.sort();
 
         for node_id in sorted_starts {
-            self.visit_node(&node_id, &mut visited, &mut execution_order)?;
+            self.visit_node(&node_id, &mut states, &mut path, &mut execution_order)?;
         }
 
         Ok(execution_order)
@@ -123,8 +232,8 @@ impl<'a> DependencyResolver<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::super::types::test_graph::{conn, node};
     use super::super::types::Connection;
-    use serde_json::json;
 
     #[test]
     fn test_simple_chain() {
@@ -137,6 +246,7 @@ mod tests {
                     port: 3000,
                     label: "Node A".to_string(),
                     data: Default::default(),
+                    execution: Default::default(),
                 },
                 GraphNode {
                     id: "B".to_string(),
@@ -144,6 +254,7 @@ mod tests {
                     port: 3001,
                     label: "Node B".to_string(),
                     data: Default::default(),
+                    execution: Default::default(),
                 },
                 GraphNode {
                     id: "C".to_string(),
@@ -151,6 +262,7 @@ mod tests {
                     port: 3020,
                     label: "Play".to_string(),
                     data: Default::default(),
+                    execution: Default::default(),
                 },
             ],
             connections: vec![
@@ -194,6 +306,7 @@ mod tests {
                     port: 3000,
                     label: "Node A".to_string(),
                     data: Default::default(),
+                    execution: Default::default(),
                 },
                 GraphNode {
                     id: "B".to_string(),
@@ -201,6 +314,7 @@ mod tests {
                     port: 3001,
                     label: "Node B".to_string(),
                     data: Default::default(),
+                    execution: Default::default(),
                 },
                 GraphNode {
                     id: "D".to_string(),
@@ -208,6 +322,7 @@ mod tests {
                     port: 3002,
                     label: "Node D".to_string(),
                     data: Default::default(),
+                    execution: Default::default(),
                 },
                 GraphNode {
                     id: "C".to_string(),
@@ -215,6 +330,7 @@ mod tests {
                     port: 3020,
                     label: "Play".to_string(),
                     data: Default::default(),
+                    execution: Default::default(),
                 },
             ],
             connections: vec![
@@ -256,4 +372,100 @@ mod tests {
         assert_eq!(order[2].id, "D");
         assert_eq!(order[3].id, "C");
     }
+
+    #[test]
+    fn test_cycle_is_error() {
+        // A → B → A, B → Play
+        let graph = WorkflowGraph {
+            nodes: vec![node("A", "add"), node("B", "add"), node("Play", "add")],
+            connections: vec![conn("A", "B"), conn("B", "A"), conn("B", "Play")],
+        };
+
+        let resolver = DependencyResolver::new(&graph);
+        let err = resolver.resolve_from("Play").unwrap_err();
+
+        assert_eq!(err, ResolveError::Cycle(vec!["B".to_string(), "A".to_string(), "B".to_string()]));
+        assert_eq!(err.to_string(), "Ciclo detectado: B → A → B");
+    }
+
+    #[test]
+    fn test_cycle_lists_only_cycle_nodes() {
+        // Start → X → Y → Z → X, Z → Play (Start fica fora do ciclo)
+        let graph = WorkflowGraph {
+            nodes: vec![
+                node("Start", "add"),
+                node("X", "add"),
+                node("Y", "add"),
+                node("Z", "add"),
+                node("Play", "add"),
+            ],
+            connections: vec![
+                conn("Start", "X"),
+                conn("X", "Y"),
+                conn("Y", "Z"),
+                conn("Z", "X"),
+                conn("Z", "Play"),
+            ],
+        };
+
+        let resolver = DependencyResolver::new(&graph);
+        match resolver.resolve_from("Play") {
+            Err(ResolveError::Cycle(ids)) => assert_eq!(ids, vec!["Z", "X", "Y", "Z"]),
+            other => panic!("Esperava ciclo, veio {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_self_loop_is_error() {
+        let graph = WorkflowGraph {
+            nodes: vec![node("A", "add")],
+            connections: vec![conn("A", "A")],
+        };
+
+        let resolver = DependencyResolver::new(&graph);
+        assert_eq!(
+            resolver.resolve_from("A").unwrap_err(),
+            ResolveError::Cycle(vec!["A".to_string(), "A".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_dangling_connection() {
+        let graph = WorkflowGraph {
+            nodes: vec![node("A", "add"), node("Play", "add")],
+            connections: vec![conn("A", "Play"), conn("ghost", "Play")],
+        };
+
+        let resolver = DependencyResolver::new(&graph);
+        assert_eq!(
+            resolver.validate().unwrap_err(),
+            ResolveError::DanglingConnection {
+                from_node_id: "ghost".to_string(),
+                to_node_id: "Play".to_string(),
+                missing_node_id: "ghost".to_string(),
+            }
+        );
+
+        let valid = WorkflowGraph {
+            nodes: vec![node("A", "add"), node("Play", "add")],
+            connections: vec![conn("A", "Play")],
+        };
+        assert!(DependencyResolver::new(&valid).validate().is_ok());
+    }
+
+    #[test]
+    fn test_resolve_excluding_stops_at_excluded() {
+        // X → PlayA → Y → PlayB: PlayB excluindo PlayA não inclui X
+        let graph = WorkflowGraph {
+            nodes: vec![node("X", "add"), node("PlayA", "add"), node("Y", "add"), node("PlayB", "add")],
+            connections: vec![conn("X", "PlayA"), conn("PlayA", "Y"), conn("Y", "PlayB")],
+        };
+
+        let resolver = DependencyResolver::new(&graph);
+        let excluded = HashSet::from(["PlayA".to_string()]);
+        let order = resolver.resolve_from_excluding("PlayB", &excluded).unwrap();
+        let ids: Vec<&str> = order.iter().map(|n| n.id.as_str()).collect();
+
+        assert_eq!(ids, vec!["Y", "PlayB"]);
+    }
 }
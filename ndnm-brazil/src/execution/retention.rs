@@ -0,0 +1,211 @@
+// ndnm-brazil/src/execution/retention.rs
+//
+// Cache Cleaner (Fase 6 do RESUMO_SESSAO_FASE2.md)
+// Cada Play cria uma pasta em workspaces/runs; sem limpeza, o disco só cresce.
+//
+// Regras, nessa ordem (runs fixados e runs em andamento nunca saem):
+// 1. Idade: run terminado há mais de `max_age` sai
+// 2. Por workspace: só os `max_runs_per_workspace` mais recentes ficam
+// 3. Tamanho: enquanto a pasta passar de `max_total_bytes`, sai o run mais antigo
+//
+// Apagar um run só perde o cache dele: o node roda de novo no próximo Play.
+
+use super::cache::RunCache;
+use chrono::{DateTime, Utc};
+use ndnm_core::RetentionConfig;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+/// Intervalo padrão entre limpezas
+pub const DEFAULT_CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Limites efetivos (None = sem limite)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RetentionPolicy {
+    pub max_runs_per_workspace: Option<usize>,
+    pub max_total_bytes: Option<u64>,
+    pub max_age: Option<chrono::Duration>,
+    pub interval: Duration,
+}
+
+impl RetentionPolicy {
+    pub fn from_config(config: &RetentionConfig) -> Self {
+        Self {
+            max_runs_per_workspace: config.max_runs_per_workspace,
+            max_total_bytes: config.max_total_mb.map(|mb| mb.saturating_mul(1024 * 1024)),
+            max_age: config.max_age_days.and_then(|days| chrono::Duration::try_days(days as i64)),
+            interval: config
+                .interval_minutes
+                .map(|m| Duration::from_secs(m.max(1) * 60))
+                .unwrap_or(DEFAULT_CLEANUP_INTERVAL),
+        }
+    }
+
+    /// Nenhum limite configurado (o cleaner nem precisa rodar)
+    pub fn is_unlimited(&self) -> bool {
+        self.max_runs_per_workspace.is_none() && self.max_total_bytes.is_none() && self.max_age.is_none()
+    }
+}
+
+/// O que o cleaner precisa saber de cada run
+#[derive(Debug, Clone)]
+pub struct StoredRun {
+    pub run_id: String,
+    /// None = sem metadata.json (run em andamento ou interrompido)
+    pub workspace_id: Option<String>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub bytes: u64,
+    pub pinned: bool,
+}
+
+/// Resultado de uma limpeza
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CleanupReport {
+    pub deleted_runs: Vec<String>,
+    pub freed_bytes: u64,
+    pub kept_runs: usize,
+}
+
+/// Decide quais runs apagar (sem tocar no disco). `runs` pode vir em qualquer ordem.
+/// Runs sem metadata não são considerados: podem estar rodando agora.
+pub fn plan(runs: &[StoredRun], policy: &RetentionPolicy, active: &HashSet<String>, now: DateTime<Utc>) -> Vec<String> {
+    // Mais recentes primeiro (run_id começa com o timestamp)
+    let mut finished: Vec<&StoredRun> = runs.iter().filter(|r| r.workspace_id.is_some()).collect();
+    finished.sort_by(|a, b| b.run_id.cmp(&a.run_id));
+
+    let removable = |run: &StoredRun| !run.pinned && !active.contains(&run.run_id);
+    let mut delete: HashSet<&str> = HashSet::new();
+
+    // 1. Idade
+    if let Some(max_age) = policy.max_age {
+        for run in finished.iter().filter(|r| removable(r)) {
+            if run.finished_at.is_some_and(|at| now - at > max_age) {
+                delete.insert(&run.run_id);
+            }
+        }
+    }
+
+    // 2. Quantidade por workspace (fixados não contam no limite)
+    if let Some(max_runs) = policy.max_runs_per_workspace {
+        let mut per_workspace: HashMap<&str, usize> = HashMap::new();
+        for run in finished.iter().filter(|r| removable(r)) {
+            if delete.contains(run.run_id.as_str()) {
+                continue;
+            }
+            let count = per_workspace.entry(run.workspace_id.as_deref().unwrap_or_default()).or_default();
+            *count += 1;
+            if *count > max_runs {
+                delete.insert(&run.run_id);
+            }
+        }
+    }
+
+    // 3. Tamanho total (conta tudo que fica, inclusive fixados e runs sem metadata)
+    if let Some(max_bytes) = policy.max_total_bytes {
+        let mut total: u64 = runs.iter().filter(|r| !delete.contains(r.run_id.as_str())).map(|r| r.bytes).sum();
+        for run in finished.iter().rev() {
+            if total <= max_bytes {
+                break;
+            }
+            if removable(run) && delete.insert(&run.run_id) {
+                total = total.saturating_sub(run.bytes);
+            }
+        }
+    }
+
+    let mut delete: Vec<String> = delete.into_iter().map(String::from).collect();
+    delete.sort();
+    delete
+}
+
+/// Lê os runs do disco, apaga o que a política mandar e devolve o relatório
+pub fn cleanup(cache: &RunCache, policy: &RetentionPolicy, active: &HashSet<String>, now: DateTime<Utc>) -> CleanupReport {
+    let runs: Vec<StoredRun> = cache
+        .list_runs()
+        .into_iter()
+        .map(|run_id| {
+            let metadata = cache.read_metadata(&run_id);
+            StoredRun {
+                workspace_id: metadata.as_ref().map(|m| m.workspace_id.clone()),
+                finished_at: metadata
+                    .as_ref()
+                    .and_then(|m| DateTime::parse_from_rfc3339(&m.finished_at).ok())
+                    .map(|at| at.with_timezone(&Utc)),
+                bytes: cache.run_size(&run_id),
+                pinned: cache.is_pinned(&run_id),
+                run_id,
+            }
+        })
+        .collect();
+
+    let mut report = CleanupReport::default();
+    for run_id in plan(&runs, policy, active, now) {
+        match cache.delete_run(&run_id) {
+            Ok(bytes) => {
+                report.freed_bytes += bytes;
+                report.deleted_runs.push(run_id);
+            }
+            Err(e) => println!("⚠️  {}", e),
+        }
+    }
+    report.kept_runs = runs.len() - report.deleted_runs.len();
+
+    if !report.deleted_runs.is_empty() {
+        println!("🧹 Cache Cleaner: {} runs apagados ({} bytes liberados, {} mantidos)",
+            report.deleted_runs.len(), report.freed_bytes, report.kept_runs);
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn run(day: u32, workspace: &str, bytes: u64) -> StoredRun {
+        StoredRun {
+            run_id: format!("run_2024-10-{:02}_00-00-00-000_aaaa", day),
+            workspace_id: Some(workspace.to_string()),
+            finished_at: Some(Utc.with_ymd_and_hms(2024, 10, day, 0, 0, 0).unwrap()),
+            bytes,
+            pinned: false,
+        }
+    }
+
+    fn day(ids: &[String]) -> Vec<String> {
+        ids.iter().map(|id| id[12..14].to_string()).collect()
+    }
+
+    #[test]
+    fn test_plan_age_count_and_size() {
+        let now = Utc.with_ymd_and_hms(2024, 10, 20, 0, 0, 0).unwrap();
+        let mut runs = vec![run(1, "ws-a", 100), run(10, "ws-a", 100), run(15, "ws-a", 100), run(18, "ws-a", 100), run(19, "ws-b", 100)];
+        runs[0].pinned = true;
+        let active = HashSet::new();
+
+        let by_age = RetentionPolicy { max_age: chrono::Duration::try_days(7), ..Default::default() };
+        // Dia 1 é velho mas está fixado; dia 10 sai
+        assert_eq!(day(&plan(&runs, &by_age, &active, now)), vec!["10"]);
+
+        let by_count = RetentionPolicy { max_runs_per_workspace: Some(2), ..Default::default() };
+        assert_eq!(day(&plan(&runs, &by_count, &active, now)), vec!["10"]);
+
+        // 500 bytes no total, limite 250: saem os mais antigos não fixados até caber
+        let by_size = RetentionPolicy { max_total_bytes: Some(250), ..Default::default() };
+        assert_eq!(day(&plan(&runs, &by_size, &active, now)), vec!["10", "15", "18"]);
+    }
+
+    #[test]
+    fn test_plan_skips_active_and_unfinished_runs() {
+        let now = Utc.with_ymd_and_hms(2024, 10, 20, 0, 0, 0).unwrap();
+        let mut unfinished = run(2, "ws-a", 100);
+        unfinished.workspace_id = None;
+        unfinished.finished_at = None;
+        let runs = vec![unfinished, run(3, "ws-a", 100)];
+
+        let policy = RetentionPolicy { max_age: chrono::Duration::try_days(1), ..Default::default() };
+        let active = HashSet::from([runs[1].run_id.clone()]);
+        assert!(plan(&runs, &policy, &active, now).is_empty());
+    }
+}
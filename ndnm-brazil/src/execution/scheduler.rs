@@ -0,0 +1,249 @@
+// ndnm-brazil/src/execution/scheduler.rs
+//
+// Agendador de nodes prontos pra execução
+// Um node fica pronto quando todos os nodes que alimentam ele terminaram,
+// então branches independentes (B e D no diamante) rodam em paralelo.
+
+use super::types::{Connection, GraphNode};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Máximo de nodes executando ao mesmo tempo num run (se o request não disser nada)
+pub const DEFAULT_MAX_PARALLEL_NODES: usize = 4;
+
+/// Controla quais nodes já podem executar
+pub struct Schedule<'a> {
+    /// Quantos predecessores (dentro do run) ainda faltam terminar
+    pending: HashMap<String, usize>,
+    /// Mapa de node_id → nodes que dependem dele
+    dependents: HashMap<String, Vec<&'a GraphNode>>,
+    /// Nodes prontos, na ordem do resolver (determinístico)
+    ready: VecDeque<&'a GraphNode>,
+    /// Nodes que ainda não terminaram
+    remaining: usize,
+    /// Nodes pulados porque algo antes deles falhou
+    skipped: HashSet<String>,
+    /// Posição de cada node na ordem do resolver
+    position: HashMap<String, usize>,
+}
+
+impl<'a> Schedule<'a> {
+    /// Monta o agendamento a partir da ordem do resolver.
+    /// Conexões com origem fora da ordem são ignoradas (não fazem parte do run).
+    pub fn new(order: &[&'a GraphNode], connections: &[Connection]) -> Self {
+        let in_run: HashSet<&str> = order.iter().map(|n| n.id.as_str()).collect();
+        let by_id: HashMap<&str, &'a GraphNode> = order.iter().map(|n| (n.id.as_str(), *n)).collect();
+
+        // Pares únicos (origem, destino): duas conexões entre os mesmos nodes contam uma vez
+        let mut edges: HashSet<(&str, &str)> = HashSet::new();
+        for conn in connections {
+            if in_run.contains(conn.from_node_id.as_str()) && in_run.contains(conn.to_node_id.as_str()) {
+                edges.insert((conn.from_node_id.as_str(), conn.to_node_id.as_str()));
+            }
+        }
+
+        let mut pending: HashMap<String, usize> = order.iter().map(|n| (n.id.clone(), 0)).collect();
+        let mut dependents: HashMap<String, Vec<&'a GraphNode>> = HashMap::new();
+        for (from, to) in edges {
+            *pending.entry(to.to_string()).or_default() += 1;
+            dependents.entry(from.to_string()).or_default().push(by_id[to]);
+        }
+
+        // Ordena os dependentes pela posição na ordem do resolver
+        let position: HashMap<String, usize> = order.iter().enumerate().map(|(i, n)| (n.id.clone(), i)).collect();
+        for deps in dependents.values_mut() {
+            deps.sort_by_key(|n| position[n.id.as_str()]);
+        }
+
+        let ready = order.iter().filter(|n| pending[&n.id] == 0).copied().collect();
+
+        Self {
+            pending,
+            dependents,
+            ready,
+            remaining: order.len(),
+            skipped: HashSet::new(),
+            position,
+        }
+    }
+
+    /// Próximo node pronto pra executar
+    pub fn pop_ready(&mut self) -> Option<&'a GraphNode> {
+        self.ready.pop_front()
+    }
+
+    /// Marca um node como terminado e libera os dependentes que ficaram prontos
+    pub fn complete(&mut self, node_id: &str) {
+        self.remaining = self.remaining.saturating_sub(1);
+
+        if let Some(deps) = self.dependents.get(node_id) {
+            for dep in deps {
+                if let Some(count) = self.pending.get_mut(&dep.id) {
+                    *count = count.saturating_sub(1);
+                    if *count == 0 {
+                        self.ready.push_back(dep);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Marca um node como falho: ele sai da conta e todos os dependentes (diretos e indiretos)
+    /// são pulados - nunca ficam prontos. Retorna os pulados, na ordem do resolver.
+    pub fn fail(&mut self, node_id: &str) -> Vec<&'a GraphNode> {
+        self.remaining = self.remaining.saturating_sub(1);
+
+        let mut skipped = Vec::new();
+        let mut stack = vec![node_id.to_string()];
+        while let Some(id) = stack.pop() {
+            let Some(deps) = self.dependents.get(&id) else { continue };
+            for dep in deps {
+                if self.skipped.insert(dep.id.clone()) {
+                    self.remaining = self.remaining.saturating_sub(1);
+                    skipped.push(*dep);
+                    stack.push(dep.id.clone());
+                }
+            }
+        }
+
+        skipped.sort_by_key(|n| self.position[n.id.as_str()]);
+        skipped
+    }
+
+    /// true quando todos os nodes terminaram
+    pub fn is_finished(&self) -> bool {
+        self.remaining == 0
+    }
+}
+
+/// Limita requests simultâneos por porta de node (compartilhado entre runs).
+/// Porta sem `max_concurrency` não tem limite próprio: só o `max_parallel_nodes` do run segura.
+#[derive(Debug, Default)]
+pub struct PortLimiter {
+    /// Limite configurado por porta (vem do `max_concurrency` do config.yaml de cada node)
+    limits: HashMap<u16, usize>,
+    /// Semáforos criados sob demanda
+    semaphores: Mutex<HashMap<u16, Arc<Semaphore>>>,
+}
+
+impl PortLimiter {
+    pub fn new(limits: HashMap<u16, usize>) -> Self {
+        Self {
+            limits,
+            semaphores: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Limite efetivo de uma porta (`None` = sem limite)
+    pub fn limit_for(&self, port: u16) -> Option<usize> {
+        self.limits.get(&port).map(|limit| (*limit).max(1))
+    }
+
+    /// Espera uma vaga na porta. A vaga é liberada quando o permit é dropado.
+    /// Porta sem limite não espera (`None`).
+    pub async fn acquire(&self, port: u16) -> Option<OwnedSemaphorePermit> {
+        let limit = self.limit_for(port)?;
+        let semaphore = {
+            let mut semaphores = self.semaphores.lock().unwrap_or_else(|e| e.into_inner());
+            semaphores
+                .entry(port)
+                .or_insert_with(|| Arc::new(Semaphore::new(limit)))
+                .clone()
+        };
+
+        // O semáforo nunca é fechado, então acquire_owned não falha
+        Some(semaphore.acquire_owned().await.expect("semáforo de porta fechado"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::types::test_graph::{conn, node};
+
+    #[test]
+    fn test_diamond_branches_are_ready_together() {
+        //     A
+        //    / \
+        //   B   D
+        //    \ /
+        //     C
+        let (a, b, d, c) = (node("A", "add"), node("B", "add"), node("D", "add"), node("C", "add"));
+        let order = vec![&a, &b, &d, &c];
+        let connections = vec![conn("A", "B"), conn("A", "D"), conn("B", "C"), conn("D", "C")];
+
+        let mut schedule = Schedule::new(&order, &connections);
+
+        assert_eq!(schedule.pop_ready().unwrap().id, "A");
+        assert!(schedule.pop_ready().is_none());
+
+        // A terminou: B e D ficam prontos juntos
+        schedule.complete("A");
+        assert_eq!(schedule.pop_ready().unwrap().id, "B");
+        assert_eq!(schedule.pop_ready().unwrap().id, "D");
+        assert!(schedule.pop_ready().is_none());
+
+        // C só fica pronto depois de B e D
+        schedule.complete("D");
+        assert!(schedule.pop_ready().is_none());
+        schedule.complete("B");
+        assert_eq!(schedule.pop_ready().unwrap().id, "C");
+
+        schedule.complete("C");
+        assert!(schedule.is_finished());
+    }
+
+    #[test]
+    fn test_duplicate_connections_count_once() {
+        // A alimenta duas entradas de B
+        let (a, b) = (node("A", "add"), node("B", "add"));
+        let order = vec![&a, &b];
+        let mut second = conn("A", "B");
+        second.to_input_index = 1;
+        let connections = vec![conn("A", "B"), second];
+
+        let mut schedule = Schedule::new(&order, &connections);
+        assert_eq!(schedule.pop_ready().unwrap().id, "A");
+        schedule.complete("A");
+        assert_eq!(schedule.pop_ready().unwrap().id, "B");
+    }
+
+    #[test]
+    fn test_fail_skips_only_dependents() {
+        // A → B → C   e   D (independente)
+        let (a, b, c, d) = (node("A", "add"), node("B", "add"), node("C", "add"), node("D", "add"));
+        let order = vec![&a, &d, &b, &c];
+        let connections = vec![conn("A", "B"), conn("B", "C")];
+
+        let mut schedule = Schedule::new(&order, &connections);
+        assert_eq!(schedule.pop_ready().unwrap().id, "A");
+        assert_eq!(schedule.pop_ready().unwrap().id, "D");
+
+        let skipped: Vec<&str> = schedule.fail("A").iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(skipped, vec!["B", "C"]);
+        assert!(schedule.pop_ready().is_none());
+        assert!(!schedule.is_finished());
+
+        // D continua e fecha o run
+        schedule.complete("D");
+        assert!(schedule.is_finished());
+    }
+
+    #[tokio::test]
+    async fn test_port_limiter_respects_limit() {
+        let limiter = PortLimiter::new(HashMap::from([(3050, 2)]));
+
+        let _p1 = limiter.acquire(3050).await;
+        let _p2 = limiter.acquire(3050).await;
+
+        // Terceira vaga na 3050 não sai até liberar uma
+        let third = tokio::time::timeout(std::time::Duration::from_millis(50), limiter.acquire(3050)).await;
+        assert!(third.is_err());
+
+        // Porta sem config não tem limite: dois `add` rodam juntos
+        let _other = limiter.acquire(3000).await;
+        let free = tokio::time::timeout(std::time::Duration::from_millis(50), limiter.acquire(3000)).await;
+        assert!(matches!(free, Ok(None)));
+    }
+}
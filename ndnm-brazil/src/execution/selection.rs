@@ -0,0 +1,132 @@
+// ndnm-brazil/src/execution/selection.rs
+//
+// EXECUTE_NODES: rodar só um node (ou uma seleção) do editor, sem Play
+// - upstream-and-self: roda o que for preciso pra calcular a seleção (acima dela, usando o cache)
+// - self-only-using-cached-inputs: roda só a seleção; quem alimenta ela entra com o último output em cache
+//   (de um run do mesmo workspace: os ids do editor se repetem entre workspaces)
+//
+// Os nodes selecionados sempre rodam de novo (é pra isso que o usuário clicou), e o run não
+// dispara Plays em cascata.
+
+use super::cache::RunCache;
+use super::types::WorkflowGraph;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// Como montar o run de uma seleção
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SelectionMode {
+    #[default]
+    UpstreamAndSelf,
+    SelfOnlyUsingCachedInputs,
+}
+
+/// Nodes pedidos pelo EXECUTE_NODES
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeSelection {
+    pub node_ids: Vec<String>,
+    #[serde(default)]
+    pub mode: SelectionMode,
+}
+
+impl NodeSelection {
+    /// Confere se a seleção não está vazia e se todos os nodes existem no grafo
+    pub fn validate(&self, graph: &WorkflowGraph) -> Result<(), String> {
+        if self.node_ids.is_empty() {
+            return Err("Nenhum node selecionado".to_string());
+        }
+        match self.node_ids.iter().find(|id| !graph.nodes.iter().any(|n| &n.id == *id)) {
+            Some(missing) => Err(format!("Node não encontrado no grafo: {}", missing)),
+            None => Ok(()),
+        }
+    }
+
+    /// Outputs em cache (do workspace) dos nodes que alimentam a seleção (modo self-only).
+    /// Node de fora sem output em cache é erro: precisa rodar upstream-and-self antes.
+    pub fn cached_inputs(
+        &self,
+        cache: &RunCache,
+        workspace_id: &str,
+        graph: &WorkflowGraph,
+    ) -> Result<HashMap<String, Value>, String> {
+        let selected: HashSet<&str> = self.node_ids.iter().map(String::as_str).collect();
+        let mut feeders: Vec<&str> = graph
+            .connections
+            .iter()
+            .filter(|c| selected.contains(c.to_node_id.as_str()) && !selected.contains(c.from_node_id.as_str()))
+            .map(|c| c.from_node_id.as_str())
+            .collect();
+        feeders.sort();
+        feeders.dedup();
+
+        feeders
+            .into_iter()
+            .map(|node_id| match cache.latest_output(workspace_id, node_id) {
+                Some(output) => Ok((node_id.to_string(), output)),
+                None => Err(format!(
+                    "Node {} não tem output em cache: rode a seleção com upstream-and-self primeiro",
+                    node_id
+                )),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::cache::RunMetadata;
+    use crate::execution::types::test_graph::{conn, node};
+    use serde_json::json;
+
+    fn graph() -> WorkflowGraph {
+        WorkflowGraph {
+            nodes: vec![node("a", "add"), node("b", "add"), node("c", "add")],
+            connections: vec![conn("a", "b"), conn("b", "c")],
+        }
+    }
+
+    #[test]
+    fn test_mode_names_and_validate() {
+        let selection: NodeSelection =
+            serde_json::from_value(json!({"node_ids": ["c"], "mode": "self-only-using-cached-inputs"})).unwrap();
+        assert_eq!(selection.mode, SelectionMode::SelfOnlyUsingCachedInputs);
+        assert!(selection.validate(&graph()).is_ok());
+
+        let missing = NodeSelection { node_ids: vec!["x".to_string()], mode: Default::default() };
+        assert!(missing.validate(&graph()).is_err());
+        let empty = NodeSelection { node_ids: vec![], mode: Default::default() };
+        assert!(empty.validate(&graph()).is_err());
+    }
+
+    #[test]
+    fn test_cached_inputs_only_from_outside_selection() {
+        let root = std::env::temp_dir().join("ndnm_test_selection_cached_inputs");
+        let _ = std::fs::remove_dir_all(&root);
+        let cache = RunCache::new(root.clone());
+
+        // Seleção b+c: só o "a" alimenta de fora
+        let selection = NodeSelection {
+            node_ids: vec!["b".to_string(), "c".to_string()],
+            mode: SelectionMode::SelfOnlyUsingCachedInputs,
+        };
+        assert!(selection.cached_inputs(&cache, "ws-1", &graph()).is_err());
+
+        for (run_id, workspace_id, response) in [
+            ("run_2024-01-01_00-00-00_aaaa", "ws-1", 1),
+            ("run_2024-01-02_00-00-00_bbbb", "ws-1", 2),
+            ("run_2024-01-03_00-00-00_cccc", "ws-2", 3),
+        ] {
+            cache.store(run_id, "a", "hash", &json!({"response": response})).unwrap();
+            cache.write_metadata(&RunMetadata::finished(run_id, workspace_id)).unwrap();
+        }
+        // O "a" mais novo é de outro workspace: não conta
+        let seed = selection.cached_inputs(&cache, "ws-1", &graph()).unwrap();
+        assert_eq!(seed, HashMap::from([("a".to_string(), json!({"response": 2}))]));
+        assert!(selection.cached_inputs(&cache, "ws-3", &graph()).is_err());
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
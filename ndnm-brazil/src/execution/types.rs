@@ -2,19 +2,65 @@
 //
 // Tipos para o sistema de execução
 
+use super::cache::RunNodeMetadata;
+use super::selection::NodeSelection;
+use ndnm_core::ExecutionPolicyConfig;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Requisição de execução vinda do frontend via WebSocket
 #[derive(Debug, Clone, Deserialize)]
 pub struct ExecutionRequest {
-    /// ID do node Play que foi disparado
-    pub play_node_id: String,
-    /// ID do workspace atual (será usado em fases futuras para logging/caching)
-    #[allow(dead_code)]
+    /// ID do node Play que foi disparado (None = EXECUTE_NODES, o run não tem Play)
+    #[serde(default)]
+    pub play_node_id: Option<String>,
+    /// ID do workspace atual (vai pro metadata.json do run)
     pub workspace_id: String,
     /// Grafo completo (nodes + conexões)
     pub graph: WorkflowGraph,
+    /// Máximo de nodes em paralelo neste run (None = padrão do engine)
+    #[serde(default)]
+    pub max_parallel_nodes: Option<usize>,
+    /// O que fazer quando um node falha (padrão: abortar o run)
+    #[serde(default)]
+    pub on_error: ErrorMode,
+    /// Output do Play vindo do gatilho (body do webhook, ...). None = o Play repassa o que chega nele.
+    #[serde(skip)]
+    pub trigger_payload: Option<serde_json::Value>,
+    /// Nodes pedidos pelo EXECUTE_NODES (None = run normal a partir do Play)
+    #[serde(default)]
+    pub selection: Option<NodeSelection>,
+}
+
+/// O que um run executou (snapshot.json na pasta do run): o histórico continua valendo
+/// depois que o workspace é editado, e o replay roda exatamente o mesmo grafo.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RunSnapshot {
+    pub graph: WorkflowGraph,
+    #[serde(default)]
+    pub max_parallel_nodes: Option<usize>,
+    #[serde(default)]
+    pub on_error: ErrorMode,
+    /// Output do Play vindo do gatilho (webhook, file watcher)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trigger_payload: Option<serde_json::Value>,
+    /// Outputs dos Plays anteriores (runs filhos de uma cascata)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub seed: HashMap<String, serde_json::Value>,
+    /// Seleção do EXECUTE_NODES
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub selection: Option<NodeSelection>,
+}
+
+/// Comportamento do run quando um node falha
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorMode {
+    /// Primeira falha para o run (os nodes em andamento terminam)
+    #[default]
+    Abort,
+    /// Só os dependentes do node que falhou são pulados; branches independentes continuam
+    Continue,
 }
 
 /// Representação do grafo de workflow
@@ -26,6 +72,42 @@ pub struct WorkflowGraph {
     pub connections: Vec<Connection>,
 }
 
+impl WorkflowGraph {
+    /// O node e tudo que depende dele (segue as conexões pra frente, passando pelos Plays)
+    pub fn downstream_of(&self, node_id: &str) -> Vec<String> {
+        let mut found = vec![node_id.to_string()];
+        let mut next = 0;
+        while next < found.len() {
+            let current = found[next].clone();
+            for conn in self.connections.iter().filter(|c| c.from_node_id == current) {
+                if !found.contains(&conn.to_node_id) {
+                    found.push(conn.to_node_id.clone());
+                }
+            }
+            next += 1;
+        }
+        found
+    }
+
+    /// Nodes cujo output é o resultado do grafo: os ligados direto no Play e os marcados
+    /// com `"output": true` no data
+    pub fn output_node_ids(&self, play_node_id: &str) -> Vec<String> {
+        let mut found: Vec<String> = Vec::new();
+        let wired = self.connections.iter().filter(|c| c.to_node_id == play_node_id).map(|c| &c.from_node_id);
+        let flagged = self
+            .nodes
+            .iter()
+            .filter(|n| n.data.get("output").and_then(|v| v.as_bool()).unwrap_or(false))
+            .map(|n| &n.id);
+        for node_id in wired.chain(flagged) {
+            if !found.contains(node_id) {
+                found.push(node_id.clone());
+            }
+        }
+        found
+    }
+}
+
 /// Node no grafo
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct GraphNode {
@@ -39,6 +121,19 @@ pub struct GraphNode {
     pub label: String,
     /// Dados específicos do node (inputs do usuário)
     pub data: HashMap<String, serde_json::Value>,
+    /// Timeout/retries só deste node (sobrescreve o `execution:` do config.yaml)
+    #[serde(default)]
+    pub execution: ExecutionPolicyConfig,
+}
+
+/// Tipos de node que são Play (disparam runs; no grafo só repassam o que chega neles)
+pub const PLAY_NODE_TYPES: &[&str] = &["playButton", "comfyPlay", "playTimer", "playWebhook", "playFileWatcher"];
+
+impl GraphNode {
+    /// true pros Play nodes (não têm /run de processamento)
+    pub fn is_play(&self) -> bool {
+        PLAY_NODE_TYPES.contains(&self.node_type.as_str())
+    }
 }
 
 /// Conexão entre dois nodes
@@ -55,7 +150,7 @@ pub struct Connection {
 }
 
 /// Status de execução de um node individual
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 #[allow(dead_code)]
 pub enum NodeExecutionStatus {
@@ -64,6 +159,8 @@ pub enum NodeExecutionStatus {
     Completed,
     Cached,
     Failed,
+    /// Não executou porque um node anterior falhou (modo continue)
+    Skipped,
 }
 
 /// Resultado de execução de um node
@@ -76,24 +173,49 @@ pub struct NodeExecutionResult {
     pub error: Option<String>,
     pub duration_ms: u64,
     pub cached: bool,
+    /// Quantas vezes o /run foi chamado (1 = sem retry, 0 = veio do cache)
+    pub attempts: u32,
 }
 
-/// Status geral da execução (enviado via WebSocket pro frontend)
-/// Será usado na Fase 3 para updates em tempo real
+/// Status geral da execução (enviado via WebSocket pro frontend como EXECUTION_STATUS)
+/// Um snapshot do progresso do run + o evento que gerou o snapshot
 #[derive(Debug, Clone, Serialize)]
-#[allow(dead_code)]
 pub struct ExecutionStatus {
     pub run_id: String,
     pub status: ExecutionState,
     pub current_node: Option<String>,
     pub completed_nodes: Vec<String>,
+    /// Nodes executando agora (branches em paralelo)
+    pub running_nodes: Vec<String>,
     pub remaining_nodes: Vec<String>,
     pub total_nodes: usize,
     pub cached_nodes: usize,
+    #[serde(flatten)]
+    pub event: ExecutionEvent,
 }
 
-/// Estado geral da execução
+/// Evento de progresso emitido pelo ExecutionEngine
 #[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ExecutionEvent {
+    /// Run começou (ordem já resolvida)
+    Starting,
+    /// Node foi despachado (cache ou HTTP)
+    NodeExecuting { node_id: String },
+    /// Node executou via HTTP
+    NodeCompleted { node_id: String, duration_ms: u64 },
+    /// Node veio do cache (hash de input igual)
+    NodeCached { node_id: String },
+    /// Node falhou
+    NodeFailed { node_id: String, error: String, duration_ms: u64 },
+    /// Node pulado porque `failed_node_id` (anterior a ele) falhou
+    NodeSkipped { node_id: String, failed_node_id: String },
+    /// Run terminou (com sucesso ou não)
+    Finished { duration_ms: u64 },
+}
+
+/// Estado geral da execução
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 #[allow(dead_code)]
 pub enum ExecutionState {
@@ -101,6 +223,8 @@ pub enum ExecutionState {
     Executing,
     Completed,
     Failed,
+    /// Cancelado pelo usuário (CANCEL_RUN)
+    Cancelled,
 }
 
 /// Resultado final da execução
@@ -113,4 +237,68 @@ pub struct ExecutionResult {
     pub cached_nodes: usize,
     pub duration_ms: u64,
     pub error: Option<String>,
+    /// Nodes que terminaram (executados ou do cache) - num run cancelado, os que deram tempo
+    pub completed_nodes: Vec<String>,
+    /// Status de cada node que saiu da fila (completed, cached, failed, skipped)
+    pub nodes: Vec<RunNodeMetadata>,
+    /// Output do Play (o que chegou nas entradas dele) - é o que os Plays em cascata recebem
+    pub output: Option<serde_json::Value>,
+    /// Outputs dos nodes terminais (ligados no Play, marcados como output ou selecionados no EXECUTE_NODES)
+    pub outputs: HashMap<String, serde_json::Value>,
+    /// Runs disparados em cascata pelos Plays ligados na saída deste
+    pub child_runs: Vec<ChildRun>,
+}
+
+/// Run filho de uma cascata de Plays
+#[derive(Debug, Clone, Serialize)]
+pub struct ChildRun {
+    pub run_id: String,
+    pub play_node_id: String,
+    /// Play cuja saída disparou este run
+    pub triggered_by: String,
+    pub status: ExecutionState,
+    pub executed_nodes: usize,
+    pub cached_nodes: usize,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+    /// Output do Play deste run (o que chegou nele)
+    pub output: Option<serde_json::Value>,
+}
+
+/// Grafos dos testes do execution: nodes com label = id e sem timeout/retries próprios
+#[cfg(test)]
+pub mod test_graph {
+    use super::{Connection, GraphNode};
+    use serde_json::Value;
+
+    /// Node na porta 3000, sem data
+    pub fn node(id: &str, node_type: &str) -> GraphNode {
+        node_on(id, node_type, 3000, Value::Object(Default::default()))
+    }
+
+    /// Node numa porta, com o `data` dado (objeto JSON)
+    pub fn node_on(id: &str, node_type: &str, port: u16, data: Value) -> GraphNode {
+        GraphNode {
+            id: id.to_string(),
+            node_type: node_type.to_string(),
+            port,
+            label: id.to_string(),
+            data: serde_json::from_value(data).unwrap(),
+            execution: Default::default(),
+        }
+    }
+
+    /// Saída 0 de `from` → entrada 0 de `to`
+    pub fn conn(from: &str, to: &str) -> Connection {
+        conn_at(from, 0, to, 0)
+    }
+
+    pub fn conn_at(from: &str, from_output_index: usize, to: &str, to_input_index: usize) -> Connection {
+        Connection {
+            from_node_id: from.to_string(),
+            from_output_index,
+            to_node_id: to.to_string(),
+            to_input_index,
+        }
+    }
 }
@@ -2,22 +2,25 @@
 
 // Módulo de execução (Fase 2)
 mod execution;
+mod triggers;
+mod workspace;
 
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
         Path, State,
     },
-    http::StatusCode,
+    body::Bytes,
+    http::{HeaderMap, StatusCode},
     response::IntoResponse,
-    routing::{get, post},
+    routing::{delete, get, post},
     Router,
 };
 use clap::Parser;
 use futures_util::{stream::StreamExt, sink::SinkExt};
-use ndnm_core::{AppError, load_config};
-use std::{fs, net::SocketAddr, path::Path as StdPath, sync::Arc};
-use tokio::sync::broadcast;
+use ndnm_core::{AppError, CachePolicyConfig, ExecutionPolicyConfig, Section, load_config};
+use std::{collections::HashMap, net::SocketAddr, path::PathBuf, sync::{Arc, Mutex}};
+use tokio::sync::{broadcast, oneshot};
 // FIX E0412: Importado DateTime
 use chrono::{Utc, DateTime};
 use serde::{Deserialize, Serialize};
@@ -33,6 +36,17 @@ struct NodeTypeInfo {
     r#type: String,
     label: String,
     default_data: Value,
+    // Usados só pelo Brazil (não vão pro frontend)
+    #[serde(skip)]
+    port: u16,
+    #[serde(skip)]
+    max_concurrency: Option<usize>,
+    #[serde(skip)]
+    execution: ExecutionPolicyConfig,
+    #[serde(skip)]
+    cache: CachePolicyConfig,
+    #[serde(skip)]
+    sections: Vec<Section>,
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -48,34 +62,67 @@ enum BrazilToFrontend {
         current_path: String,
         entries: Vec<DirectoryEntry>
     },
-    // NOVO (Fase 2): Status de execução em tempo real
-    // Será usado na Fase 3 para updates em tempo real
-    #[allow(dead_code)]
+    // Run aceito e disparado em background (chega antes de qualquer EXECUTION_STATUS)
+    #[serde(rename = "EXECUTION_STARTED")]
+    ExecutionStarted {
+        run_id: String,
+        /// None = EXECUTE_NODES (run sem Play)
+        play_node_id: Option<String>,
+        workspace_id: String,
+    },
+    // NOVO (Fase 3): Status de execução em tempo real (um por evento do run)
     #[serde(rename = "EXECUTION_STATUS")]
-    ExecutionStatus {
+    ExecutionStatus(execution::ExecutionStatus),
+    // Output de um node que terminou no run (o editor mostra o valor no card)
+    #[serde(rename = "NODE_OUTPUT")]
+    NodeOutput {
         run_id: String,
-        status: String,
-        current_node: Option<String>,
-        completed_nodes: Vec<String>,
-        remaining_nodes: Vec<String>,
+        node_id: String,
+        output: Value,
     },
     // NOVO (Fase 2): Resultado final da execução
     #[serde(rename = "EXECUTION_COMPLETE")]
     ExecutionComplete {
         run_id: String,
-        status: String,
+        /// "completed", ou "failed" no modo continue quando algum node falhou
+        status: execution::ExecutionState,
         total_nodes: usize,
         executed_nodes: usize,
         cached_nodes: usize,
         duration_ms: u64,
+        /// Status de cada node (completed, cached, failed, skipped)
+        nodes: Vec<execution::cache::RunNodeMetadata>,
+        /// Outputs dos nodes terminais (ligados no Play, marcados como output ou selecionados)
+        outputs: HashMap<String, Value>,
+        /// Runs disparados pelos Plays em cascata (cada um com o próprio run_id)
+        child_runs: Vec<execution::ChildRun>,
+    },
+    // Run parado por CANCEL_RUN (com os nodes que terminaram antes do cancelamento)
+    #[serde(rename = "EXECUTION_CANCELLED")]
+    ExecutionCancelled {
+        run_id: String,
+        completed_nodes: Vec<String>,
+        duration_ms: u64,
     },
     // NOVO (Fase 2): Erro durante execução
     #[serde(rename = "EXECUTION_ERROR")]
     ExecutionError {
         run_id: String,
         error: String,
-        failed_node: Option<String>,
+        /// Node que quebrou (id, label, status HTTP e code/message do ErrorEnvelope)
+        failed_node: Option<execution::NodeFailure>,
+        /// Nodes que terminaram antes da falha
+        completed_nodes: Vec<String>,
     },
+    // Agenda dos PlayTimers (no connect, a cada save de workspace e a cada disparo)
+    #[serde(rename = "TIMERS_UPDATED")]
+    TimersUpdated { timers: Vec<triggers::timer::TimerInfo> },
+    // Cache de nodes invalidado (INVALIDATE_CACHE ou POST /cache/invalidate): rodam de novo no próximo Play
+    #[serde(rename = "CACHE_INVALIDATED")]
+    CacheInvalidated { workspace_id: String, node_ids: Vec<String> },
+    // Lista de workspaces mudou (save, rename, duplicate, delete): outros editores abertos atualizam
+    #[serde(rename = "WORKSPACES_CHANGED")]
+    WorkspacesChanged { workspaces: Vec<workspace::store::WorkspaceSummary> },
 }
 
 #[derive(Deserialize, Debug)]
@@ -95,8 +142,38 @@ enum FrontendToBrazil {
     ExecutePlay {
         play_node_id: String,
         workspace_id: String,
-        graph: execution::WorkflowGraph,
+        /// Sem grafo, roda o workspace salvo (workspaces/<workspace_id>.json)
+        #[serde(default)]
+        graph: Option<execution::WorkflowGraph>,
+        /// Máximo de nodes em paralelo neste run (opcional)
+        #[serde(default)]
+        max_parallel_nodes: Option<usize>,
+        /// "abort" (padrão) ou "continue" (falha só pula os dependentes do node)
+        #[serde(default)]
+        on_error: execution::ErrorMode,
+    },
+    // Rodar só um node (ou uma seleção) do editor, sem Play
+    #[serde(rename = "EXECUTE_NODES")]
+    ExecuteNodes {
+        workspace_id: String,
+        node_ids: Vec<String>,
+        /// "upstream-and-self" (padrão) ou "self-only-using-cached-inputs"
+        #[serde(default)]
+        mode: execution::selection::SelectionMode,
+        /// Sem grafo, roda o workspace salvo
+        #[serde(default)]
+        graph: Option<execution::WorkflowGraph>,
+        #[serde(default)]
+        max_parallel_nodes: Option<usize>,
+        #[serde(default)]
+        on_error: execution::ErrorMode,
     },
+    // Cancelar um run em andamento
+    #[serde(rename = "CANCEL_RUN")]
+    CancelRun { run_id: String },
+    // Forçar um node (ou ele e tudo abaixo dele) a rodar de novo no próximo Play
+    #[serde(rename = "INVALIDATE_CACHE")]
+    InvalidateCache(InvalidateCacheRequest),
 }
 
 // Estrutura do node-fs-browser
@@ -130,14 +207,99 @@ struct Cli {
     port: Option<u16>,
 }
 
-#[derive(Debug)]
 struct AppState {
     tx: broadcast::Sender<String>,
     known_nodes: Vec<NodeTypeInfo>,
     http_client: Client, // Cliente HTTP
     fs_browser_port: u16, // Porta do node-fs-browser (configurada no main)
+    runs: Arc<execution::RunManager>, // Runs em background (engine compartilhada: limites por porta valem entre runs)
+    workspaces_dir: PathBuf, // Workspaces salvos pelo editor (workspaces/<nome>.json)
+    node_specs: HashMap<String, workspace::NodeSpec>, // Porta e handles de cada tipo de node (converte os workspaces salvos)
+    timers: Arc<triggers::timer::TimerScheduler>, // Agenda dos PlayTimers dos workspaces salvos
+    webhooks: triggers::webhook::WebhookRegistry, // Rotas /hooks dos PlayWebhooks dos workspaces salvos
+    file_watchers: Arc<triggers::file_watcher::FileWatcher>, // Pastas observadas pelos PlayFileWatchers
+    retention: execution::retention::RetentionPolicy, // Limites do Cache Cleaner (seção `retention:` do config.yaml)
+    max_workspace_versions: usize, // Versões guardadas por workspace (workspaces/.history)
+    workspace_writes: Mutex<()>, // Um save/rename/delete de workspace por vez (conferir revisão + gravar sem corrida)
 }
 
+impl AppState {
+    /// Grafo enviado pelo cliente ou, sem ele, o do workspace salvo (convertido do formato do editor)
+    fn resolve_graph(
+        &self,
+        workspace_id: &str,
+        graph: Option<execution::WorkflowGraph>,
+    ) -> Result<execution::WorkflowGraph, workspace::WorkspaceError> {
+        match graph {
+            Some(graph) => Ok(graph),
+            None => workspace::load_graph(&self.workspaces_dir, workspace_id, &self.node_specs),
+        }
+    }
+
+    /// Manda a agenda dos timers pra todos os clientes
+    fn broadcast_timers(&self) {
+        let msg = BrazilToFrontend::TimersUpdated { timers: self.timers.timers() };
+        if let Ok(json_str) = serde_json::to_string(&msg) {
+            if self.tx.send(json_str).is_err() { /* ignore */ }
+        }
+    }
+
+    /// Manda a lista de workspaces salvos pra todos os clientes
+    fn broadcast_workspaces(&self) {
+        let msg = BrazilToFrontend::WorkspacesChanged { workspaces: workspace::store::list(&self.workspaces_dir) };
+        if let Ok(json_str) = serde_json::to_string(&msg) {
+            if self.tx.send(json_str).is_err() { /* ignore */ }
+        }
+    }
+
+    /// Troca os triggers (timers, webhooks, file watchers) do workspace; None = workspace saiu do disco
+    fn reload_triggers(&self, name: &str, workspace: Option<&workspace::Workspace>) {
+        let saved = workspace.and_then(|w| serde_json::to_value(w).ok()).unwrap_or_else(|| json!({ "nodes": [] }));
+        self.timers.load_workspace(name, &saved, Utc::now());
+        self.webhooks.load_workspace(name, &saved);
+        self.file_watchers.load_workspace(name, &saved);
+        self.broadcast_timers();
+    }
+}
+
+/// Body do POST /runs (disparo de um Play por HTTP: curl, scripts, Play nodes)
+#[derive(Deserialize, Debug)]
+struct TriggerRunRequest {
+    workspace: String,
+    play_node_id: String,
+    /// Grafo a executar; sem ele, roda o workspace salvo (workspaces/<workspace>.json)
+    #[serde(default)]
+    graph: Option<execution::WorkflowGraph>,
+    /// true (padrão) = responde só quando o run terminar, com o status final
+    #[serde(default = "default_wait")]
+    wait: bool,
+    #[serde(default)]
+    max_parallel_nodes: Option<usize>,
+    #[serde(default)]
+    on_error: execution::ErrorMode,
+    /// Output do Play neste run (ex: caminho do arquivo, quando o node-play-file-watcher dispara na mão)
+    #[serde(default)]
+    payload: Option<Value>,
+}
+
+fn default_wait() -> bool { true }
+
+/// Body do POST /cache/invalidate e do INVALIDATE_CACHE
+#[derive(Deserialize, Debug)]
+struct InvalidateCacheRequest {
+    workspace_id: String,
+    node_id: String,
+    /// true = invalida também tudo que depende do node
+    #[serde(default)]
+    downstream: bool,
+    /// Grafo pra achar os dependentes; sem ele, usa o do workspace salvo
+    #[serde(default)]
+    graph: Option<execution::WorkflowGraph>,
+}
+
+/// Resultado final de um run (pra quem espera o fim, ex: POST /runs com wait)
+type RunOutcome = Result<execution::ExecutionResult, execution::ExecutionError>;
+
 fn discover_nodes() -> Vec<NodeTypeInfo> {
     let mut discovered_nodes = Vec::new();
     let current_dir = std::env::current_dir().expect("Não consegui ler o diretório atual");
@@ -176,6 +338,11 @@ fn discover_nodes() -> Vec<NodeTypeInfo> {
                             r#type: node_type,
                             label: label,
                             default_data,
+                            port: node_config.port,
+                            max_concurrency: node_config.max_concurrency,
+                            execution: node_config.execution,
+                            cache: node_config.cache,
+                            sections: node_config.sections,
                         });
                     }
                     Err(_) => { }
@@ -189,62 +356,212 @@ fn discover_nodes() -> Vec<NodeTypeInfo> {
 }
 
 async fn save_workspace(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
     axum::extract::Json(payload): axum::extract::Json<Value>,
-) -> impl IntoResponse {
-    let workspace_name = payload.get("name").and_then(|v| v.as_str());
-    if workspace_name.is_none() {
+) -> axum::response::Response {
+    if payload.get("name").and_then(|v| v.as_str()).is_none() {
         return (StatusCode::BAD_REQUEST, "Missing workspace name").into_response();
     }
-    
-    let workspace_name = workspace_name.unwrap();
-    let workspace_dir = StdPath::new("workspaces");
-    
-    if !workspace_dir.exists() {
-        if let Err(e) = fs::create_dir_all(workspace_dir) {
-            println!("{} | 🔴 Erro ao criar pasta workspaces: {}", Utc::now().to_rfc3339(), e);
-            return (StatusCode::INTERNAL_SERVER_ERROR, format!("Error: {}", e)).into_response();
-        }
+
+    // Formato antigo (sem schema_version) sobe pra versão atual; o que for salvo precisa dar pra executar
+    let mut workspace = match workspace::parse(payload) {
+        Ok(workspace) => workspace,
+        Err(e) => return workspace_error_response(&e),
+    };
+    if let Err(e) = workspace::check_name(&workspace.name) {
+        return workspace_error_response(&e);
     }
-    
-    let file_path = workspace_dir.join(format!("{}.json", workspace_name));
-    
-    match fs::write(&file_path, serde_json::to_string_pretty(&payload).unwrap_or_default()) {
-        Ok(_) => {
-            println!("{} | 💾 [Workspace] '{}' salvo em {:?}", 
-                Utc::now().to_rfc3339(), workspace_name, file_path);
-            (StatusCode::OK, axum::Json(json!({"status": "saved"}))).into_response()
+    let issues = workspace.validate(&state.node_specs);
+    if !issues.is_empty() {
+        println!("{} | 🔴 [Workspace] '{}' recusado: {}", Utc::now().to_rfc3339(), workspace.name, issues.join("; "));
+        return workspace_error_response(&workspace::WorkspaceError::Validation(issues));
+    }
+    workspace.schema_version = workspace::CURRENT_SCHEMA_VERSION;
+
+    // `revision` tem que ser a que o editor carregou: outro editor salvou antes = 409
+    let _writing = state.workspace_writes.lock().unwrap_or_else(|e| e.into_inner());
+    match workspace::history::save(&state.workspaces_dir, &mut workspace, state.max_workspace_versions) {
+        Ok(version) => {
+            println!("{} | 💾 [Workspace] '{}' salvo em {:?} (versão {})",
+                Utc::now().to_rfc3339(), workspace.name, state.workspaces_dir, version);
+            state.reload_triggers(&workspace.name, Some(&workspace));
+            state.broadcast_workspaces();
+            (StatusCode::OK, axum::Json(json!({
+                "status": "saved",
+                "schema_version": workspace.schema_version,
+                "revision": workspace.revision,
+                "version": version,
+            }))).into_response()
         }
         Err(e) => {
             println!("{} | 🔴 [Workspace] Erro ao salvar: {}", Utc::now().to_rfc3339(), e);
-            (StatusCode::INTERNAL_SERVER_ERROR, format!("Error: {}", e)).into_response()
+            workspace_error_response(&e)
         }
     }
 }
 
 async fn load_workspace(
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
     Path(name): Path<String>,
-) -> impl IntoResponse {
-    let workspace_dir = StdPath::new("workspaces");
-    let file_path = workspace_dir.join(format!("{}.json", name));
-    
-    match fs::read_to_string(&file_path) {
-        Ok(content) => {
-            match serde_json::from_str::<Value>(&content) {
-                Ok(data) => {
-                    println!("{} | 📂 [Workspace] '{}' carregado", Utc::now().to_rfc3339(), name);
-                    (StatusCode::OK, axum::Json(data)).into_response()
-                }
-                Err(e) => {
-                    println!("{} | 🔴 [Workspace] Erro ao parsear JSON: {}", Utc::now().to_rfc3339(), e);
-                    (StatusCode::BAD_REQUEST, format!("Invalid JSON: {}", e)).into_response()
-                }
-            }
+) -> axum::response::Response {
+    // Arquivos antigos voltam já migrados (o arquivo em si só muda no próximo save)
+    match workspace::read(&state.workspaces_dir, &name) {
+        Ok(workspace) => {
+            println!("{} | 📂 [Workspace] '{}' carregado", Utc::now().to_rfc3339(), name);
+            (StatusCode::OK, axum::Json(workspace)).into_response()
+        }
+        Err(e) => {
+            println!("{} | 🔴 [Workspace] {}", Utc::now().to_rfc3339(), e);
+            workspace_error_response(&e)
+        }
+    }
+}
+
+/// Body do rename/duplicate de workspace
+#[derive(Deserialize, Debug)]
+struct WorkspaceNameRequest {
+    new_name: String,
+}
+
+/// GET /workspaces - workspaces salvos (nome, última modificação, quantidade de nodes)
+async fn list_workspaces_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    axum::Json(workspace::store::list(&state.workspaces_dir))
+}
+
+/// POST /workspaces/:name/rename - renomeia o arquivo e move os triggers pro nome novo
+async fn rename_workspace_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    axum::extract::Json(body): axum::extract::Json<WorkspaceNameRequest>,
+) -> axum::response::Response {
+    let _writing = state.workspace_writes.lock().unwrap_or_else(|e| e.into_inner());
+    match workspace::store::rename(&state.workspaces_dir, &name, &body.new_name) {
+        Ok(renamed) => {
+            println!("{} | ✏️  [Workspace] '{}' renomeado pra '{}'", Utc::now().to_rfc3339(), name, renamed.name);
+            state.reload_triggers(&name, None);
+            state.reload_triggers(&renamed.name, Some(&renamed));
+            state.broadcast_workspaces();
+            (StatusCode::OK, axum::Json(json!({"status": "renamed", "name": renamed.name}))).into_response()
+        }
+        Err(e) => {
+            println!("{} | 🔴 [Workspace] {}", Utc::now().to_rfc3339(), e);
+            workspace_error_response(&e)
+        }
+    }
+}
+
+/// POST /workspaces/:name/duplicate - cópia com outro nome (triggers da cópia já ficam ativos)
+async fn duplicate_workspace_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    axum::extract::Json(body): axum::extract::Json<WorkspaceNameRequest>,
+) -> axum::response::Response {
+    let _writing = state.workspace_writes.lock().unwrap_or_else(|e| e.into_inner());
+    match workspace::store::duplicate(&state.workspaces_dir, &name, &body.new_name) {
+        Ok(copy) => {
+            println!("{} | 📑 [Workspace] '{}' duplicado como '{}'", Utc::now().to_rfc3339(), name, copy.name);
+            state.reload_triggers(&copy.name, Some(&copy));
+            state.broadcast_workspaces();
+            (StatusCode::CREATED, axum::Json(json!({"status": "duplicated", "name": copy.name}))).into_response()
+        }
+        Err(e) => {
+            println!("{} | 🔴 [Workspace] {}", Utc::now().to_rfc3339(), e);
+            workspace_error_response(&e)
+        }
+    }
+}
+
+/// DELETE /workspaces/:name - move pra workspaces/.trash e desliga os triggers
+async fn delete_workspace_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> axum::response::Response {
+    let _writing = state.workspace_writes.lock().unwrap_or_else(|e| e.into_inner());
+    match workspace::store::delete(&state.workspaces_dir, &name) {
+        Ok(trash_path) => {
+            println!("{} | 🗑️  [Workspace] '{}' movido pra {:?}", Utc::now().to_rfc3339(), name, trash_path);
+            state.reload_triggers(&name, None);
+            state.broadcast_workspaces();
+            (StatusCode::OK, axum::Json(json!({"status": "deleted", "trash_path": trash_path}))).into_response()
+        }
+        Err(e) => {
+            println!("{} | 🔴 [Workspace] {}", Utc::now().to_rfc3339(), e);
+            workspace_error_response(&e)
+        }
+    }
+}
+
+/// GET /workspaces/:name/versions - versões guardadas (mais novas primeiro)
+async fn list_versions_handler(State(state): State<Arc<AppState>>, Path(name): Path<String>) -> impl IntoResponse {
+    axum::Json(workspace::history::list(&state.workspaces_dir, &name))
+}
+
+/// GET /workspaces/:name/versions/:version - conteúdo de uma versão
+async fn version_detail_handler(
+    State(state): State<Arc<AppState>>,
+    Path((name, version)): Path<(String, u32)>,
+) -> axum::response::Response {
+    match workspace::history::read(&state.workspaces_dir, &name, version) {
+        Ok(snapshot) => (StatusCode::OK, axum::Json(snapshot)).into_response(),
+        Err(e) => workspace_error_response(&e),
+    }
+}
+
+/// Query do GET /workspaces/:name/diff (sem `to` = compara com o workspace salvo agora)
+#[derive(Deserialize, Debug)]
+struct WorkspaceDiffQuery {
+    from: u32,
+    to: Option<u32>,
+}
+
+/// GET /workspaces/:name/diff?from=N&to=M - nodes/edges adicionados, removidos e alterados
+async fn workspace_diff_handler(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    axum::extract::Query(query): axum::extract::Query<WorkspaceDiffQuery>,
+) -> axum::response::Response {
+    let dir = &state.workspaces_dir;
+    let before = workspace::history::read(dir, &name, query.from);
+    let after = match query.to {
+        Some(version) => workspace::history::read(dir, &name, version),
+        None => workspace::read(dir, &name),
+    };
+    match (before, after) {
+        (Ok(before), Ok(after)) => (StatusCode::OK, axum::Json(workspace::diff::diff(&before, &after))).into_response(),
+        (Err(e), _) | (_, Err(e)) => workspace_error_response(&e),
+    }
+}
+
+/// POST /workspaces/:name/versions/:version/restore - a versão volta a ser o workspace atual
+async fn restore_version_handler(
+    State(state): State<Arc<AppState>>,
+    Path((name, version)): Path<(String, u32)>,
+) -> axum::response::Response {
+    // Não revalida: a ideia é recuperar o que foi perdido, mesmo que um tipo de node tenha sumido
+    let _writing = state.workspace_writes.lock().unwrap_or_else(|e| e.into_inner());
+    let restored = workspace::history::read(&state.workspaces_dir, &name, version).and_then(|mut snapshot| {
+        snapshot.schema_version = workspace::CURRENT_SCHEMA_VERSION;
+        // Restore é explícito: vale por cima da revisão atual
+        snapshot.revision = workspace::current_revision(&state.workspaces_dir, &name)?.unwrap_or(0);
+        let new_version = workspace::history::save(&state.workspaces_dir, &mut snapshot, state.max_workspace_versions)?;
+        Ok((snapshot, new_version))
+    });
+    match restored {
+        Ok((snapshot, new_version)) => {
+            println!("{} | ⏪ [Workspace] '{}' restaurado da versão {} (nova versão {})",
+                Utc::now().to_rfc3339(), name, version, new_version);
+            state.reload_triggers(&name, Some(&snapshot));
+            state.broadcast_workspaces();
+            (StatusCode::OK, axum::Json(json!({
+                "status": "restored",
+                "restored_from": version,
+                "revision": snapshot.revision,
+                "version": new_version,
+            }))).into_response()
         }
         Err(e) => {
-            println!("{} | 🔴 [Workspace] Não encontrado: {}", Utc::now().to_rfc3339(), e);
-            (StatusCode::NOT_FOUND, format!("Workspace not found: {}", e)).into_response()
+            println!("{} | 🔴 [Workspace] {}", Utc::now().to_rfc3339(), e);
+            workspace_error_response(&e)
         }
     }
 }
@@ -268,16 +585,114 @@ async fn main() -> Result<(), AppError> {
     println!("{} | 🟢 [WS Brazil] Node de Navegação de Arquivos (fsBrowser) na porta: {}", Utc::now().to_rfc3339(), fs_browser_port);
 
 
+    // Limite de /run simultâneos por porta, vindo do max_concurrency de cada config.yaml
+    let port_limits: HashMap<u16, usize> = discovered_nodes.iter()
+        .filter_map(|n| n.max_concurrency.map(|max| (n.port, max)))
+        .collect();
+    // Timeout/retries de cada node, vindos da seção `execution:` do config.yaml
+    let node_policies: HashMap<u16, ExecutionPolicyConfig> = discovered_nodes.iter()
+        .map(|n| (n.port, n.execution.clone()))
+        .collect();
+    // Quando o output de cada node pode vir do cache, da seção `cache:` do config.yaml
+    let cache_policies: HashMap<u16, CachePolicyConfig> = discovered_nodes.iter()
+        .map(|n| (n.port, n.cache.clone()))
+        .collect();
+    let engine = Arc::new(
+        execution::ExecutionEngine::new()
+            .with_port_limits(port_limits)
+            .with_node_policies(node_policies)
+            .with_cache_policies(cache_policies),
+    );
+    let runs = Arc::new(execution::RunManager::new(engine));
+
     let http_client = Client::new();
     let (tx, _) = broadcast::channel(100);
+
+    // Timers dos workspaces salvos
+    let workspaces_dir = PathBuf::from("workspaces");
+    let timers = Arc::new(triggers::timer::TimerScheduler::default());
+    timers.load_dir(&workspaces_dir, Utc::now());
+    let webhooks = triggers::webhook::WebhookRegistry::default();
+    webhooks.load_dir(&workspaces_dir);
+    let file_watchers = Arc::new(triggers::file_watcher::FileWatcher::default());
+    file_watchers.load_dir(&workspaces_dir);
+
+    // Porta e handles de cada tipo de node: o Brazil converte os workspaces salvos sozinho
+    let node_specs: HashMap<String, workspace::NodeSpec> = discovered_nodes.iter()
+        .map(|n| {
+            let spec = workspace::NodeSpec::new(n.port, &n.label, &n.sections);
+            // Saídas fixas (outputs_mode != "n") limitam os out_N que o save aceita
+            let spec = match (n.default_data["outputsMode"].as_str(), n.default_data["outputsCount"].as_u64()) {
+                (Some(mode), Some(count)) if mode != "n" => spec.with_outputs_count(count as usize),
+                _ => spec,
+            };
+            (n.r#type.clone(), spec)
+        })
+        .collect();
+
+    let retention = execution::retention::RetentionPolicy::from_config(&brazil_config.retention);
+    let max_workspace_versions = brazil_config.retention.max_workspace_versions.unwrap_or(workspace::history::DEFAULT_MAX_VERSIONS);
     
     let app_state = Arc::new(AppState { 
         tx, 
         known_nodes: discovered_nodes,
         http_client,
         fs_browser_port,
+        runs,
+        workspaces_dir,
+        node_specs,
+        timers,
+        webhooks,
+        file_watchers,
+        retention,
+        max_workspace_versions,
+        workspace_writes: Mutex::new(()),
+    });
+
+    let fire_state = Arc::clone(&app_state);
+    let notify_state = Arc::clone(&app_state);
+    app_state.timers.spawn(
+        move |workspace_id, play_node_id| {
+            let state = Arc::clone(&fire_state);
+            async move {
+                let (done_tx, done_rx) = oneshot::channel();
+                if start_saved_play(&state, workspace_id, play_node_id, None, Some(done_tx)).is_ok() {
+                    let _ = done_rx.await;
+                }
+            }
+        },
+        move |_| notify_state.broadcast_timers(),
+    );
+
+    let watch_state = Arc::clone(&app_state);
+    app_state.file_watchers.spawn(move |workspace_id, play_node_id, path| {
+        let state = Arc::clone(&watch_state);
+        async move {
+            let (done_tx, done_rx) = oneshot::channel();
+            if start_saved_play(&state, workspace_id, play_node_id, Some(path), Some(done_tx)).is_err() {
+                return Value::Null;
+            }
+            // O resultado inteiro: os caminhos que o run escreveu não disparam o watcher de novo
+            match done_rx.await {
+                Ok(Ok(result)) => serde_json::to_value(&result).unwrap_or_default(),
+                _ => Value::Null,
+            }
+        }
     });
     
+    // Cache Cleaner: limpa workspaces/runs na subida e depois a cada intervalo
+    if !app_state.retention.is_unlimited() {
+        let cleaner_state = Arc::clone(&app_state);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(cleaner_state.retention.interval);
+            loop {
+                interval.tick().await;
+                let state = Arc::clone(&cleaner_state);
+                let _ = tokio::task::spawn_blocking(move || state.runs.cleanup(&state.retention)).await;
+            }
+        });
+    }
+
     let cors = CorsLayer::permissive();
     
     let app = Router::new()
@@ -285,6 +700,23 @@ async fn main() -> Result<(), AppError> {
         .route("/ws", get(ws_handler))
         .route("/workspace/save", post(save_workspace))
         .route("/workspace/load/:name", get(load_workspace))
+        .route("/workspaces", get(list_workspaces_handler))
+        .route("/workspaces/:name", delete(delete_workspace_handler))
+        .route("/workspaces/:name/rename", post(rename_workspace_handler))
+        .route("/workspaces/:name/duplicate", post(duplicate_workspace_handler))
+        .route("/workspaces/:name/versions", get(list_versions_handler))
+        .route("/workspaces/:name/versions/:version", get(version_detail_handler))
+        .route("/workspaces/:name/versions/:version/restore", post(restore_version_handler))
+        .route("/workspaces/:name/diff", get(workspace_diff_handler))
+        .route("/runs", get(list_runs_handler).post(trigger_run))
+        .route("/runs/active", get(active_runs_handler))
+        .route("/runs/:run_id", get(run_detail_handler))
+        .route("/runs/:run_id/replay", post(replay_run_handler))
+        .route("/runs/:run_id/pin", post(pin_run_handler).delete(unpin_run_handler))
+        .route("/cache/cleanup", post(cache_cleanup_handler))
+        .route("/cache/invalidate", post(invalidate_cache_handler))
+        .route("/timers", get(timers_handler))
+        .route("/hooks/:workspace/:play_id", post(webhook_handler))
         .with_state(app_state)
         .layer(cors);
     
@@ -297,6 +729,375 @@ async fn main() -> Result<(), AppError> {
 
 async fn health_handler() -> impl IntoResponse { (StatusCode::OK, "Brazil is alive!") }
 
+/// Runs em andamento (disparados por qualquer cliente)
+async fn active_runs_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    (StatusCode::OK, axum::Json(state.runs.active_runs()))
+}
+
+/// Fixa um run: o Cache Cleaner nunca apaga
+async fn pin_run_handler(State(state): State<Arc<AppState>>, Path(run_id): Path<String>) -> impl IntoResponse {
+    set_run_pinned(&state, &run_id, true)
+}
+
+async fn unpin_run_handler(State(state): State<Arc<AppState>>, Path(run_id): Path<String>) -> impl IntoResponse {
+    set_run_pinned(&state, &run_id, false)
+}
+
+fn set_run_pinned(state: &AppState, run_id: &str, pinned: bool) -> (StatusCode, axum::Json<Value>) {
+    match state.runs.cache().set_pinned(run_id, pinned) {
+        Ok(()) => (StatusCode::OK, axum::Json(json!({ "run_id": run_id, "pinned": pinned }))),
+        Err(e) => (StatusCode::NOT_FOUND, axum::Json(json!({ "error": e }))),
+    }
+}
+
+/// Roda o Cache Cleaner agora (mesmas regras da limpeza periódica)
+async fn cache_cleanup_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let cleaner_state = Arc::clone(&state);
+    match tokio::task::spawn_blocking(move || cleaner_state.runs.cleanup(&cleaner_state.retention)).await {
+        Ok(report) => (StatusCode::OK, axum::Json(json!(report))),
+        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, axum::Json(json!({ "error": "Cache Cleaner falhou" }))),
+    }
+}
+
+/// Invalida o cache de um node (e, com `downstream`, de tudo que depende dele)
+async fn invalidate_cache_handler(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Json(request): axum::extract::Json<InvalidateCacheRequest>,
+) -> axum::response::Response {
+    // Varre as pastas de run: fora do runtime, igual ao Cache Cleaner
+    let cache_state = Arc::clone(&state);
+    match tokio::task::spawn_blocking(move || invalidate_cache(&cache_state, request)).await {
+        Ok(Ok(node_ids)) => (StatusCode::OK, axum::Json(json!({ "node_ids": node_ids }))).into_response(),
+        Ok(Err(e)) => workspace_error_response(&e),
+        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, axum::Json(json!({ "error": "Invalidação do cache falhou" }))).into_response(),
+    }
+}
+
+/// Apaga o input-hash dos nodes nos runs do workspace e avisa os clientes (CACHE_INVALIDATED)
+fn invalidate_cache(state: &AppState, request: InvalidateCacheRequest) -> Result<Vec<String>, workspace::WorkspaceError> {
+    let node_ids = if request.downstream {
+        let graph = state.resolve_graph(&request.workspace_id, request.graph)?;
+        graph.downstream_of(&request.node_id)
+    } else {
+        vec![request.node_id]
+    };
+
+    for node_id in &node_ids {
+        let runs = state.runs.cache().invalidate_node(&request.workspace_id, node_id);
+        println!("{} | 🧹 [Cache] {} invalidado ({} runs)", Utc::now().to_rfc3339(), node_id, runs);
+    }
+
+    let msg = BrazilToFrontend::CacheInvalidated { workspace_id: request.workspace_id, node_ids: node_ids.clone() };
+    if let Ok(json_str) = serde_json::to_string(&msg) {
+        if state.tx.send(json_str).is_err() { /* ignore */ }
+    }
+    Ok(node_ids)
+}
+
+/// Agenda dos PlayTimers (com o próximo disparo de cada um)
+async fn timers_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    (StatusCode::OK, axum::Json(state.timers.timers()))
+}
+
+/// Dispara um Play por HTTP. Com `wait` (padrão) responde com o status final do run;
+/// sem, responde 202 com o run_id. O progresso vai pro WS igual a um EXECUTE_PLAY.
+async fn trigger_run(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Json(body): axum::extract::Json<TriggerRunRequest>,
+) -> axum::response::Response {
+    println!("{} | 🚀 [HTTP Brazil] POST /runs - play_node: {}, workspace: {}",
+        Utc::now().to_rfc3339(), body.play_node_id, body.workspace);
+
+    let graph = match state.resolve_graph(&body.workspace, body.graph) {
+        Ok(graph) => graph,
+        Err(e) => return workspace_error_response(&e),
+    };
+
+    let request = execution::ExecutionRequest {
+        play_node_id: Some(body.play_node_id),
+        workspace_id: body.workspace,
+        graph,
+        max_parallel_nodes: body.max_parallel_nodes,
+        on_error: body.on_error,
+        trigger_payload: body.payload,
+        selection: None,
+    };
+
+    run_response(&state, request, HashMap::new(), body.wait).await
+}
+
+/// Query do GET /runs
+#[derive(Deserialize, Debug)]
+struct RunHistoryQuery {
+    workspace: Option<String>,
+    limit: Option<usize>,
+}
+
+/// Histórico de runs terminados (mais recentes primeiro), com status, duração e contagens
+async fn list_runs_handler(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(query): axum::extract::Query<RunHistoryQuery>,
+) -> impl IntoResponse {
+    let cache_state = Arc::clone(&state);
+    let runs = tokio::task::spawn_blocking(move || {
+        execution::history::list_runs(cache_state.runs.cache(), query.workspace.as_deref(), query.limit)
+    })
+    .await
+    .unwrap_or_default();
+    (StatusCode::OK, axum::Json(runs))
+}
+
+/// Um run: input/output/tempo de cada node e o grafo que ele executou
+async fn run_detail_handler(State(state): State<Arc<AppState>>, Path(run_id): Path<String>) -> axum::response::Response {
+    // Lê a pasta do run inteira (input/output de cada node + snapshot): fora do runtime
+    let (cache_state, detail_id) = (Arc::clone(&state), run_id.clone());
+    let detail = tokio::task::spawn_blocking(move || execution::history::run_detail(cache_state.runs.cache(), &detail_id))
+        .await
+        .unwrap_or_default();
+    match detail {
+        Some(detail) => (StatusCode::OK, axum::Json(detail)).into_response(),
+        None => (StatusCode::NOT_FOUND, format!("Run não encontrado (ou ainda rodando): {}", run_id)).into_response(),
+    }
+}
+
+/// Body opcional do POST /runs/:run_id/replay
+#[derive(Deserialize, Debug)]
+struct ReplayRunRequest {
+    #[serde(default = "default_wait")]
+    wait: bool,
+}
+
+/// Roda um run de novo com o grafo gravado nele (não o do workspace atual)
+async fn replay_run_handler(
+    State(state): State<Arc<AppState>>,
+    Path(run_id): Path<String>,
+    body: Option<axum::extract::Json<ReplayRunRequest>>,
+) -> axum::response::Response {
+    println!("{} | 🔁 [HTTP Brazil] POST /runs/{}/replay", Utc::now().to_rfc3339(), run_id);
+
+    let (request, seed) = match execution::history::replay_request(state.runs.cache(), &run_id) {
+        Ok(replay) => replay,
+        Err(e) => return (StatusCode::NOT_FOUND, e).into_response(),
+    };
+    let wait = body.map(|b| b.wait).unwrap_or(true);
+    run_response(&state, request, seed, wait).await
+}
+
+/// Dispara o run e responde: 202 com o run_id, ou (com `wait`) o status final
+async fn run_response(
+    state: &Arc<AppState>,
+    request: execution::ExecutionRequest,
+    seed: HashMap<String, Value>,
+    wait: bool,
+) -> axum::response::Response {
+    if !wait {
+        let run_id = start_seeded_execution(state, request, seed, None);
+        return (StatusCode::ACCEPTED, axum::Json(json!({"run_id": run_id, "status": "started"}))).into_response();
+    }
+
+    let (done_tx, done_rx) = oneshot::channel();
+    let run_id = start_seeded_execution(state, request, seed, Some(done_tx));
+    match done_rx.await {
+        Ok(Ok(result)) => (StatusCode::OK, axum::Json(json!({
+            "run_id": result.run_id,
+            "status": result.status,
+            "result": result,
+        }))).into_response(),
+        Ok(Err(error)) => (StatusCode::OK, axum::Json(json!({
+            "run_id": error.run_id,
+            "status": execution::ExecutionState::Failed,
+            "error": error,
+        }))).into_response(),
+        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Run {} terminou sem resultado", run_id)).into_response(),
+    }
+}
+
+/// Dispara um Play sem clique (timers, webhooks, file watchers) e devolve o run_id.
+/// Roda o workspace salvo (workspaces/<nome>.json), convertido do formato do editor.
+/// `trigger_payload` vira o output do Play (body do webhook, caminho do arquivo alterado).
+fn start_saved_play(
+    state: &Arc<AppState>,
+    workspace_id: String,
+    play_node_id: String,
+    trigger_payload: Option<Value>,
+    done: Option<oneshot::Sender<RunOutcome>>,
+) -> Result<String, workspace::WorkspaceError> {
+    let graph = state.resolve_graph(&workspace_id, None).inspect_err(|e| {
+        println!("{} | ⚠️  [Brazil] {}/{} não disparou: {}", Utc::now().to_rfc3339(), workspace_id, play_node_id, e);
+    })?;
+
+    let request = execution::ExecutionRequest {
+        play_node_id: Some(play_node_id),
+        workspace_id,
+        graph,
+        max_parallel_nodes: None,
+        on_error: Default::default(),
+        trigger_payload,
+        selection: None,
+    };
+
+    Ok(start_execution(state, request, done))
+}
+
+/// Resposta HTTP pra um workspace que não deu pra ler/salvar/executar
+fn workspace_error_response(error: &workspace::WorkspaceError) -> axum::response::Response {
+    let status = match error {
+        workspace::WorkspaceError::NotFound(_) => StatusCode::NOT_FOUND,
+        workspace::WorkspaceError::InvalidName { .. } => StatusCode::BAD_REQUEST,
+        workspace::WorkspaceError::AlreadyExists(_) | workspace::WorkspaceError::Conflict { .. } => StatusCode::CONFLICT,
+        workspace::WorkspaceError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        workspace::WorkspaceError::Invalid(_) | workspace::WorkspaceError::Validation(_) => StatusCode::UNPROCESSABLE_ENTITY,
+    };
+    let body = match error {
+        workspace::WorkspaceError::Validation(issues) => json!({ "error": error.to_string(), "issues": issues }),
+        workspace::WorkspaceError::Conflict { current, .. } => json!({ "error": error.to_string(), "revision": current }),
+        _ => json!({ "error": error.to_string() }),
+    };
+    (status, axum::Json(body)).into_response()
+}
+
+/// POST /hooks/:workspace/:play_id - dispara um PlayWebhook com o body como output do Play
+async fn webhook_handler(
+    State(state): State<Arc<AppState>>,
+    Path((workspace_id, play_node_id)): Path<(String, String)>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> axum::response::Response {
+    println!("{} | 🪝 [HTTP Brazil] POST /hooks/{}/{}", Utc::now().to_rfc3339(), workspace_id, play_node_id);
+
+    let Some(settings) = state.webhooks.get(&workspace_id, &play_node_id) else {
+        return (StatusCode::NOT_FOUND, format!("Webhook não registrado: {}/{}", workspace_id, play_node_id)).into_response();
+    };
+    let provided = headers.get(triggers::webhook::SECRET_HEADER).and_then(|v| v.to_str().ok());
+    if !settings.accepts(provided) {
+        println!("{} | 🔴 [Webhook] Segredo inválido em {}/{}", Utc::now().to_rfc3339(), workspace_id, play_node_id);
+        return (StatusCode::UNAUTHORIZED, "Segredo do webhook inválido").into_response();
+    }
+
+    let payload = triggers::webhook::payload_from_body(&body);
+    let wait = settings.response == triggers::webhook::WebhookResponse::Wait;
+    let (done_tx, done_rx) = oneshot::channel();
+
+    let run_id = match start_saved_play(&state, workspace_id.clone(), play_node_id.clone(), Some(payload), wait.then_some(done_tx)) {
+        Ok(run_id) => run_id,
+        Err(e) => return workspace_error_response(&e),
+    };
+
+    if !wait {
+        return (StatusCode::ACCEPTED, axum::Json(json!({"run_id": run_id, "status": "started"}))).into_response();
+    }
+
+    match done_rx.await {
+        Ok(Ok(result)) => (StatusCode::OK, axum::Json(json!({
+            "run_id": result.run_id,
+            "status": result.status,
+            "outputs": triggers::webhook::terminal_outputs(&result, &play_node_id),
+        }))).into_response(),
+        Ok(Err(error)) => (StatusCode::OK, axum::Json(json!({
+            "run_id": error.run_id,
+            "status": execution::ExecutionState::Failed,
+            "error": error,
+        }))).into_response(),
+        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Run {} terminou sem resultado", run_id)).into_response(),
+    }
+}
+
+/// Dispara um run no RunManager e devolve o run_id na hora (avisando os clientes com EXECUTION_STARTED).
+/// O progresso vai pro broadcast como EXECUTION_STATUS e o fim como EXECUTION_COMPLETE/EXECUTION_ERROR;
+/// `done` recebe o resultado final, se alguém estiver esperando.
+fn start_execution(
+    state: &Arc<AppState>,
+    request: execution::ExecutionRequest,
+    done: Option<oneshot::Sender<RunOutcome>>,
+) -> String {
+    start_seeded_execution(state, request, HashMap::new(), done)
+}
+
+/// `start_execution` com outputs de Plays já prontos (replay de run filho)
+fn start_seeded_execution(
+    state: &Arc<AppState>,
+    request: execution::ExecutionRequest,
+    seed: HashMap<String, Value>,
+    done: Option<oneshot::Sender<RunOutcome>>,
+) -> String {
+    let play_node_id = request.play_node_id.clone();
+    let workspace_id = request.workspace_id.clone();
+
+    // Repassa o progresso do run pro frontend como EXECUTION_STATUS (e os outputs como NODE_OUTPUT)
+    let (events_tx, mut events_rx) = tokio::sync::mpsc::unbounded_channel();
+    let tx_status = state.tx.clone();
+    tokio::spawn(async move {
+        while let Some(event) = events_rx.recv().await {
+            let status_msg = match event {
+                execution::events::RunEvent::Status(status) => BrazilToFrontend::ExecutionStatus(status),
+                execution::events::RunEvent::NodeOutput { run_id, node_id, output } => {
+                    BrazilToFrontend::NodeOutput { run_id, node_id, output }
+                }
+            };
+            if let Ok(json_str) = serde_json::to_string(&status_msg) {
+                if tx_status.send(json_str).is_err() { /* ignore */ }
+            }
+        }
+    });
+
+    let tx_result = state.tx.clone();
+    let run_id = state.runs.start_seeded(request, seed, Some(events_tx), move |result| async move {
+        if let Some(done) = done {
+            let _ = done.send(result.clone());
+        }
+
+        let msg = match result {
+            Ok(result) if result.status == execution::ExecutionState::Cancelled => {
+                println!("{} | 🛑 [WS Brazil] Execução cancelada: run_id={}, nodes completos={}",
+                    Utc::now().to_rfc3339(), result.run_id, result.completed_nodes.len());
+
+                BrazilToFrontend::ExecutionCancelled {
+                    run_id: result.run_id,
+                    completed_nodes: result.completed_nodes,
+                    duration_ms: result.duration_ms,
+                }
+            }
+            Ok(result) => {
+                println!("{} | ✅ [WS Brazil] Execução completa: run_id={}, nodes={}/{}",
+                    Utc::now().to_rfc3339(), result.run_id, result.executed_nodes, result.total_nodes);
+
+                BrazilToFrontend::ExecutionComplete {
+                    run_id: result.run_id,
+                    status: result.status,
+                    total_nodes: result.total_nodes,
+                    executed_nodes: result.executed_nodes,
+                    cached_nodes: result.cached_nodes,
+                    duration_ms: result.duration_ms,
+                    nodes: result.nodes,
+                    outputs: result.outputs,
+                    child_runs: result.child_runs,
+                }
+            }
+            Err(error) => {
+                println!("{} | ❌ [WS Brazil] Erro na execução: {}", Utc::now().to_rfc3339(), error);
+
+                BrazilToFrontend::ExecutionError {
+                    run_id: error.run_id,
+                    error: error.message,
+                    failed_node: error.failed_node,
+                    completed_nodes: error.completed_nodes,
+                }
+            }
+        };
+
+        if let Ok(json_str) = serde_json::to_string(&msg) {
+            if tx_result.send(json_str).is_err() { /* ignore */ }
+        }
+    });
+
+    let started_msg = BrazilToFrontend::ExecutionStarted { run_id: run_id.clone(), play_node_id, workspace_id };
+    if let Ok(json_str) = serde_json::to_string(&started_msg) {
+        if state.tx.send(json_str).is_err() { /* ignore */ }
+    }
+
+    run_id
+}
+
 async fn ws_handler( ws: WebSocketUpgrade, State(state): State<Arc<AppState>> ) -> impl IntoResponse {
     println!("{} | 🟡 [WS Brazil] Novo cliente WebSocket tentando conectar...", Utc::now().to_rfc3339());
     ws.on_upgrade(|socket| handle_socket(socket, state))
@@ -316,6 +1117,12 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
         Err(e) => { println!("{} | 🔴 [WS Brazil] Erro ao serializar NODE_CONFIG: {}", Utc::now().to_rfc3339(), e); return; }
     }
     
+    // E a agenda dos timers (próximo disparo de cada PlayTimer)
+    let timers_msg = BrazilToFrontend::TimersUpdated { timers: state.timers.timers() };
+    if let Ok(json_str) = serde_json::to_string(&timers_msg) {
+        if sender.send(Message::Text(json_str)).await.is_err() { return; }
+    }
+
     let mut rx = state.tx.subscribe();
     // FIX Warning: O underscore _ indica que a variável não será usada
     let _state_clone_send = Arc::clone(&state); 
@@ -408,54 +1215,68 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                                 if state_clone_recv.tx.send(json_str).is_err() { }
                             }
                         }
-                        Ok(FrontendToBrazil::ExecutePlay { play_node_id, workspace_id, graph }) => {
+                        Ok(FrontendToBrazil::ExecutePlay { play_node_id, workspace_id, graph, max_parallel_nodes, on_error }) => {
                             println!("{} | 🚀 [WS Brazil] EXECUTE_PLAY recebido - play_node: {}, workspace: {}",
                                 Utc::now().to_rfc3339(), play_node_id, workspace_id);
 
-                            // Cria ExecutionEngine
-                            let engine = execution::ExecutionEngine::new();
+                            let graph = match state_clone_recv.resolve_graph(&workspace_id, graph) {
+                                Ok(graph) => graph,
+                                Err(e) => {
+                                    println!("{} | 🟡 [WS Brazil] EXECUTE_PLAY ignorado - {}", Utc::now().to_rfc3339(), e);
+                                    continue;
+                                }
+                            };
 
                             // Cria request de execução
                             let exec_request = execution::ExecutionRequest {
-                                play_node_id: play_node_id.clone(),
-                                workspace_id: workspace_id.clone(),
+                                play_node_id: Some(play_node_id),
+                                workspace_id,
                                 graph,
+                                max_parallel_nodes,
+                                on_error,
+                                trigger_payload: None,
+                                selection: None,
                             };
 
-                            // Executa! (Fase 2: bloqueante - Fase 3: async + progress updates)
-                            match engine.execute(exec_request).await {
-                                Ok(result) => {
-                                    println!("{} | ✅ [WS Brazil] Execução completa: run_id={}, nodes={}/{}",
-                                        Utc::now().to_rfc3339(), result.run_id, result.executed_nodes, result.total_nodes);
-
-                                    // Envia resultado pro frontend
-                                    let complete_msg = BrazilToFrontend::ExecutionComplete {
-                                        run_id: result.run_id,
-                                        status: "completed".to_string(),
-                                        total_nodes: result.total_nodes,
-                                        executed_nodes: result.executed_nodes,
-                                        cached_nodes: result.cached_nodes,
-                                        duration_ms: result.duration_ms,
-                                    };
+                            // Dispara em background: o loop do WS segue livre pra outras mensagens
+                            start_execution(&state_clone_recv, exec_request, None);
+                        }
+                        Ok(FrontendToBrazil::ExecuteNodes { workspace_id, node_ids, mode, graph, max_parallel_nodes, on_error }) => {
+                            println!("{} | 🎯 [WS Brazil] EXECUTE_NODES recebido - nodes: {:?}, workspace: {}",
+                                Utc::now().to_rfc3339(), node_ids, workspace_id);
 
-                                    if let Ok(json_str) = serde_json::to_string(&complete_msg) {
-                                        if state_clone_recv.tx.send(json_str).is_err() { /* ignore */ }
-                                    }
+                            let graph = match state_clone_recv.resolve_graph(&workspace_id, graph) {
+                                Ok(graph) => graph,
+                                Err(e) => {
+                                    println!("{} | 🟡 [WS Brazil] EXECUTE_NODES ignorado - {}", Utc::now().to_rfc3339(), e);
+                                    continue;
                                 }
-                                Err(error) => {
-                                    println!("{} | ❌ [WS Brazil] Erro na execução: {}", Utc::now().to_rfc3339(), error);
-
-                                    // Envia erro pro frontend
-                                    let error_msg = BrazilToFrontend::ExecutionError {
-                                        run_id: "unknown".to_string(), // TODO: Fase 3 - retornar run_id mesmo em erro
-                                        error: error.clone(),
-                                        failed_node: None, // TODO: Fase 3 - detectar node que falhou
-                                    };
+                            };
 
-                                    if let Ok(json_str) = serde_json::to_string(&error_msg) {
-                                        if state_clone_recv.tx.send(json_str).is_err() { /* ignore */ }
-                                    }
-                                }
+                            // Sem Play: metadata/histórico guardam a seleção no lugar do Play
+                            let exec_request = execution::ExecutionRequest {
+                                play_node_id: None,
+                                workspace_id,
+                                graph,
+                                max_parallel_nodes,
+                                on_error,
+                                trigger_payload: None,
+                                selection: Some(execution::selection::NodeSelection { node_ids, mode }),
+                            };
+                            start_execution(&state_clone_recv, exec_request, None);
+                        }
+                        Ok(FrontendToBrazil::CancelRun { run_id }) => {
+                            // O EXECUTION_CANCELLED sai quando o run terminar de parar
+                            if state_clone_recv.runs.cancel(&run_id) {
+                                println!("{} | 🛑 [WS Brazil] CANCEL_RUN recebido - run: {}", Utc::now().to_rfc3339(), run_id);
+                            } else {
+                                println!("{} | 🟡 [WS Brazil] CANCEL_RUN ignorado - run não está ativo: {}", Utc::now().to_rfc3339(), run_id);
+                            }
+                        }
+                        Ok(FrontendToBrazil::InvalidateCache(request)) => {
+                            let cache_state = Arc::clone(&state_clone_recv);
+                            if let Ok(Err(e)) = tokio::task::spawn_blocking(move || invalidate_cache(&cache_state, request)).await {
+                                println!("{} | 🟡 [WS Brazil] INVALIDATE_CACHE ignorado: {}", Utc::now().to_rfc3339(), e);
                             }
                         }
                         Err(e) => {
@@ -475,4 +1296,439 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
         _ = (&mut recv_task) => send_task.abort(),
     };
     println!("{} | 🟡 [WS Brazil] Conexão WebSocket finalizada.", Utc::now().to_rfc3339());
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execution_status_message_shape() {
+        let status = execution::ExecutionStatus {
+            run_id: "run_x".to_string(),
+            status: execution::ExecutionState::Executing,
+            current_node: Some("sum-1".to_string()),
+            completed_nodes: vec![],
+            running_nodes: vec!["sum-1".to_string()],
+            remaining_nodes: vec!["subtract-1".to_string()],
+            total_nodes: 3,
+            cached_nodes: 0,
+            event: execution::ExecutionEvent::NodeExecuting { node_id: "sum-1".to_string() },
+        };
+
+        let msg = serde_json::to_value(BrazilToFrontend::ExecutionStatus(status)).unwrap();
+        assert_eq!(msg["type"], json!("EXECUTION_STATUS"));
+        assert_eq!(msg["run_id"], json!("run_x"));
+        assert_eq!(msg["status"], json!("executing"));
+        assert_eq!(msg["event"], json!("node_executing"));
+        assert_eq!(msg["node_id"], json!("sum-1"));
+
+        let output = BrazilToFrontend::NodeOutput {
+            run_id: "run_x".to_string(),
+            node_id: "sum-1".to_string(),
+            output: json!({"response": 35}),
+        };
+        let msg = serde_json::to_value(output).unwrap();
+        assert_eq!(msg, json!({"type": "NODE_OUTPUT", "run_id": "run_x", "node_id": "sum-1", "output": {"response": 35}}));
+    }
+
+    fn test_state(name: &str) -> (Arc<AppState>, std::path::PathBuf) {
+        let root = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&root);
+        let engine = execution::ExecutionEngine::with_cache(execution::cache::RunCache::new(root.clone()));
+        let (tx, _rx) = broadcast::channel(100);
+        let state = Arc::new(AppState {
+            tx,
+            known_nodes: Vec::new(),
+            http_client: Client::new(),
+            fs_browser_port: 0,
+            runs: Arc::new(execution::RunManager::new(Arc::new(engine))),
+            workspaces_dir: root.join("workspaces"),
+            node_specs: HashMap::new(),
+            timers: Default::default(),
+            webhooks: Default::default(),
+            file_watchers: Default::default(),
+            retention: Default::default(),
+            max_workspace_versions: workspace::history::DEFAULT_MAX_VERSIONS,
+            workspace_writes: Mutex::new(()),
+        });
+        (state, root)
+    }
+
+    /// Grava um workspace no formato do editor na pasta do teste
+    fn save_editor_workspace(state: &AppState, name: &str, workspace: &Value) {
+        use std::fs;
+        fs::create_dir_all(&state.workspaces_dir).unwrap();
+        fs::write(state.workspaces_dir.join(format!("{}.json", name)), workspace.to_string()).unwrap();
+    }
+
+    fn play_only_graph() -> Value {
+        json!({
+            "nodes": [{"id": "play-1", "node_type": "playButton", "port": 3020, "label": "Play", "data": {}}],
+            "connections": []
+        })
+    }
+
+    async fn post_run(state: &Arc<AppState>, body: Value) -> (StatusCode, Value) {
+        let body: TriggerRunRequest = serde_json::from_value(body).unwrap();
+        let response = trigger_run(State(state.clone()), axum::extract::Json(body)).await;
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    async fn test_trigger_run_waits_for_final_status() {
+        let (state, root) = test_state("ndnm_test_trigger_run");
+
+        let (status, body) = post_run(&state, json!({
+            "workspace": "ws-1",
+            "play_node_id": "play-1",
+            "graph": play_only_graph(),
+        })).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], json!("completed"));
+        assert!(body["run_id"].as_str().unwrap().starts_with("run_"));
+        assert_eq!(body["result"]["total_nodes"], json!(1));
+
+        // Sem graph no body: roda o workspace salvo
+        save_editor_workspace(&state, "ws-1", &json!({"nodes": [{"id": "play-1", "type": "playButton", "data": {}}], "edges": []}));
+        let (status, body) = post_run(&state, json!({"workspace": "ws-1", "play_node_id": "play-1"})).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], json!("completed"));
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_secret_and_wait_response() {
+        let (state, root) = test_state("ndnm_test_webhook");
+        let saved = json!({
+            "nodes": [{"id": "hook-1", "type": "playWebhook", "data": {"secret": "s3cret", "response": "wait"}}],
+            "edges": []
+        });
+        state.webhooks.load_workspace("ws-1", &saved);
+        save_editor_workspace(&state, "ws-1", &saved);
+
+        let call = |secret: Option<&'static str>, play: &'static str| {
+            let state = state.clone();
+            async move {
+                let mut headers = HeaderMap::new();
+                if let Some(secret) = secret {
+                    headers.insert(triggers::webhook::SECRET_HEADER, secret.parse().unwrap());
+                }
+                let path = Path(("ws-1".to_string(), play.to_string()));
+                let response = webhook_handler(State(state), path, headers, Bytes::from_static(br#"{"order": 42}"#)).await;
+                let status = response.status();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                (status, serde_json::from_slice::<Value>(&bytes).unwrap_or(Value::Null))
+            }
+        };
+
+        assert_eq!(call(None, "hook-1").await.0, StatusCode::UNAUTHORIZED);
+        assert_eq!(call(Some("errado"), "hook-1").await.0, StatusCode::UNAUTHORIZED);
+        assert_eq!(call(Some("s3cret"), "hook-2").await.0, StatusCode::NOT_FOUND);
+
+        let (status, body) = call(Some("s3cret"), "hook-1").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], json!("completed"));
+        assert_eq!(body["outputs"], json!({"hook-1": {"outputs": [{"order": 42}]}}));
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn test_trigger_run_unknown_workspace() {
+        let (state, root) = test_state("ndnm_test_trigger_run_unknown");
+
+        let (status, _) = post_run(&state, json!({"workspace": "nope", "play_node_id": "play-1"})).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn test_run_history_and_replay() {
+        let (state, root) = test_state("ndnm_test_run_history");
+
+        // Node fake que devolve o input que recebeu
+        let echo = Router::new().route("/run", post(|axum::extract::Json(input): axum::extract::Json<Value>| async move {
+            axum::Json(json!({ "echo": input }))
+        }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, echo).await.unwrap() });
+
+        let graph = json!({
+            "nodes": [
+                {"id": "echo-1", "node_type": "echo", "port": port, "label": "Echo", "data": {"value": 1}},
+                {"id": "play-1", "node_type": "playButton", "port": 3020, "label": "Play", "data": {}}
+            ],
+            "connections": [{"from_node_id": "echo-1", "from_output_index": 0, "to_node_id": "play-1", "to_input_index": 0}]
+        });
+        let (_, body) = post_run(&state, json!({"workspace": "ws-1", "play_node_id": "play-1", "graph": graph})).await;
+        let run_id = body["run_id"].as_str().unwrap().to_string();
+
+        let runs = execution::history::list_runs(state.runs.cache(), Some("ws-1"), None);
+        assert_eq!(runs.len(), 1);
+        assert!(runs[0].replayable);
+        assert!(execution::history::list_runs(state.runs.cache(), Some("other"), None).is_empty());
+
+        let detail = serde_json::to_value(execution::history::run_detail(state.runs.cache(), &run_id).unwrap()).unwrap();
+        assert_eq!(detail["nodes"][0]["node_id"], json!("echo-1"));
+        assert_eq!(detail["nodes"][0]["output"], json!({"echo": detail["nodes"][0]["input"]}));
+        assert_eq!(detail["snapshot"]["graph"]["nodes"][0]["data"], json!({"value": 1}));
+
+        // Workspace editado depois: o replay roda o grafo gravado no run
+        save_editor_workspace(&state, "ws-1", &json!({"nodes": [{"id": "play-1", "type": "playButton", "data": {}}]}));
+        let response = replay_run_handler(State(state.clone()), Path(run_id.clone()), None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let replay: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_ne!(replay["run_id"], json!(run_id));
+        assert_eq!(replay["result"]["total_nodes"], json!(2));
+        assert_eq!(replay["result"]["cached_nodes"], json!(1));
+
+        let missing = replay_run_handler(State(state.clone()), Path("run_nope".to_string()), None).await;
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn test_run_saved_editor_workspace() {
+        let (mut state, root) = test_state("ndnm_test_saved_workspace");
+
+        // Node fake que soma as entradas ligadas (`variables`)
+        let sum = Router::new().route("/run", post(|axum::extract::Json(input): axum::extract::Json<Value>| async move {
+            let total: i64 = input["variables"].as_array().unwrap().iter().filter_map(Value::as_i64).sum();
+            axum::Json(json!({ "response": total }))
+        }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move { axum::serve(listener, sum).await.unwrap() });
+        Arc::get_mut(&mut state).unwrap().node_specs =
+            HashMap::from([("add".to_string(), workspace::NodeSpec::new(port, "➕ Somar", &[]))]);
+
+        // Formato do editor: type/data nos nodes, edges com handles
+        save_editor_workspace(&state, "ws-1", &json!({
+            "name": "ws-1",
+            "nodes": [
+                {"id": "n1", "type": "add", "position": {"x": 0, "y": 0},
+                 "data": {"label": "➕ Somar", "inputsCount": 2, "variables": [10, 20, 5]}},
+                {"id": "n2", "type": "add", "data": {"variables": [0, 100]}},
+                {"id": "play-1", "type": "playButton", "data": {}}
+            ],
+            "edges": [
+                {"id": "e1", "source": "n1", "sourceHandle": "out_0", "target": "n2", "targetHandle": "in_0"},
+                {"id": "e2", "source": "n2", "sourceHandle": "out_0", "target": "play-1", "targetHandle": "in_0"}
+            ]
+        }));
+
+        let (status, body) = post_run(&state, json!({"workspace": "ws-1", "play_node_id": "play-1"})).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["result"]["outputs"], json!({"n2": {"response": 135}}));
+
+        // Tipo que o discovery não conhece: 422 com o motivo
+        save_editor_workspace(&state, "broken", &json!({"nodes": [{"id": "x", "type": "ksampler", "data": {}}]}));
+        let (status, _) = post_run(&state, json!({"workspace": "broken", "play_node_id": "play-1"})).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn test_save_validates_and_load_migrates() {
+        let (mut state, root) = test_state("ndnm_test_workspace_schema");
+        Arc::get_mut(&mut state).unwrap().node_specs = HashMap::from([
+            ("fixedValue".to_string(), workspace::NodeSpec::new(3010, "🔢 Valor Fixo", &[])),
+            ("add".to_string(), workspace::NodeSpec::new(3000, "➕ Somar", &[]).with_outputs_count(1)),
+        ]);
+        let body = |response: axum::response::Response| async move {
+            let status = response.status();
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (status, serde_json::from_slice::<Value>(&bytes).unwrap_or(Value::Null))
+        };
+
+        // Arquivo antigo (sem schema_version) volta migrado no load
+        let old: Value = serde_json::from_str(include_str!("../../workspaces/workspace-1.json")).unwrap();
+        save_editor_workspace(&state, "workspace-1", &old);
+        let (status, loaded) = body(load_workspace(State(state.clone()), Path("workspace-1".to_string())).await).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(loaded["schema_version"], json!(workspace::CURRENT_SCHEMA_VERSION));
+        assert_eq!(loaded["nodes"][0]["data"]["inputsMode"], json!("0"));
+
+        // Save do formato antigo grava na versão atual
+        let (status, saved) = body(save_workspace(State(state.clone()), axum::extract::Json(old.clone())).await).await;
+        assert_eq!(status, StatusCode::OK, "{}", saved);
+        let on_disk: Value = serde_json::from_str(&std::fs::read_to_string(state.workspaces_dir.join("workspace-1.json")).unwrap()).unwrap();
+        assert_eq!(on_disk["schema_version"], json!(workspace::CURRENT_SCHEMA_VERSION));
+
+        // Edge pra node que não existe + saída além das que o add tem: 422 com os problemas
+        let mut broken = old;
+        broken["edges"] = json!([
+            {"id": "e1", "source": "n1", "target": "gone"},
+            {"id": "e2", "source": "n2", "sourceHandle": "out_3", "target": "n3", "targetHandle": "in_0"}
+        ]);
+        let (status, rejected) = body(save_workspace(State(state.clone()), axum::extract::Json(broken)).await).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(rejected["issues"].as_array().unwrap().len(), 2);
+
+        let (status, _) = body(load_workspace(State(state.clone()), Path("nope".to_string())).await).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn test_workspace_rename_duplicate_delete() {
+        let (state, root) = test_state("ndnm_test_workspace_store_handlers");
+        let mut rx = state.tx.subscribe();
+        let new_name = |name: &str| axum::extract::Json(WorkspaceNameRequest { new_name: name.to_string() });
+        // Só as listas do WORKSPACES_CHANGED (pula TIMERS_UPDATED)
+        let mut changed = move || {
+            let mut lists = Vec::new();
+            while let Ok(msg) = rx.try_recv() {
+                let msg: Value = serde_json::from_str(&msg).unwrap();
+                if msg["type"] == "WORKSPACES_CHANGED" {
+                    let names = msg["workspaces"].as_array().unwrap().iter().map(|w| w["name"].as_str().unwrap().to_string());
+                    lists.push(names.collect::<Vec<_>>());
+                }
+            }
+            lists
+        };
+
+        let hook = json!({"name": "ws-a", "nodes": [{"id": "hook-1", "type": "playWebhook", "data": {}}], "edges": []});
+        assert_eq!(save_workspace(State(state.clone()), axum::extract::Json(hook)).await.status(), StatusCode::OK);
+        assert_eq!(changed(), vec![vec!["ws-a"]]);
+
+        // Rename leva o webhook junto
+        let response = rename_workspace_handler(State(state.clone()), Path("ws-a".to_string()), new_name("ws-b")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(state.webhooks.get("ws-a", "hook-1").is_none());
+        assert!(state.webhooks.get("ws-b", "hook-1").is_some());
+        assert_eq!(changed(), vec![vec!["ws-b"]]);
+
+        let response = duplicate_workspace_handler(State(state.clone()), Path("ws-b".to_string()), new_name("ws-c")).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert!(state.webhooks.get("ws-c", "hook-1").is_some());
+        let response = duplicate_workspace_handler(State(state.clone()), Path("ws-b".to_string()), new_name("ws-c")).await;
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(changed(), vec![vec!["ws-b", "ws-c"]]);
+
+        // Delete vai pra lixeira (arquivo + histórico) e desliga o webhook
+        let response = delete_workspace_handler(State(state.clone()), Path("ws-b".to_string())).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(state.webhooks.get("ws-b", "hook-1").is_none());
+        assert_eq!(std::fs::read_dir(state.workspaces_dir.join(workspace::store::TRASH_DIR)).unwrap().count(), 2);
+        assert_eq!(changed(), vec![vec!["ws-c"]]);
+
+        let listed = list_workspaces_handler(State(state.clone())).await.into_response();
+        let bytes = axum::body::to_bytes(listed.into_body(), usize::MAX).await.unwrap();
+        let listed: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(listed[0]["name"], json!("ws-c"));
+        assert_eq!(listed[0]["node_count"], json!(1));
+
+        let response = delete_workspace_handler(State(state.clone()), Path("ws-b".to_string())).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn test_workspace_versions_diff_restore() {
+        let (state, root) = test_state("ndnm_test_workspace_versions");
+        let body = |response: axum::response::Response| async move {
+            let status = response.status();
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (status, serde_json::from_slice::<Value>(&bytes).unwrap_or(Value::Null))
+        };
+        let hook = |secret: &str, revision: u64| json!({
+            "name": "ws-1",
+            "revision": revision,
+            "nodes": [{"id": "hook-1", "type": "playWebhook", "data": {"secret": secret}}],
+            "edges": []
+        });
+        let name = || Path("ws-1".to_string());
+
+        let (_, saved) = body(save_workspace(State(state.clone()), axum::extract::Json(hook("abc", 0))).await).await;
+        assert_eq!((saved["version"].clone(), saved["revision"].clone()), (json!(1), json!(1)));
+        // Save acidental
+        let (_, saved) = body(save_workspace(State(state.clone()), axum::extract::Json(hook("oops", 1))).await).await;
+        assert_eq!((saved["version"].clone(), saved["revision"].clone()), (json!(2), json!(2)));
+
+        let versions = list_versions_handler(State(state.clone()), name()).await.into_response();
+        let (_, versions) = body(versions).await;
+        assert_eq!(versions.as_array().unwrap().iter().map(|v| v["version"].clone()).collect::<Vec<_>>(), vec![json!(2), json!(1)]);
+
+        let query = |to: Option<u32>| axum::extract::Query(WorkspaceDiffQuery { from: 1, to });
+        let (status, diff) = body(workspace_diff_handler(State(state.clone()), name(), query(Some(2))).await).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(diff["nodes_changed"][0]["changes"][0], json!({"field": "secret", "before": "abc", "after": "oops"}));
+
+        // Restore vira a versão 3 e o webhook volta pro secret antigo
+        let (status, restored) = body(restore_version_handler(State(state.clone()), Path(("ws-1".to_string(), 1))).await).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(restored["version"], json!(3));
+        assert!(state.webhooks.get("ws-1", "hook-1").unwrap().accepts(Some("abc")));
+        let (_, diff) = body(workspace_diff_handler(State(state.clone()), name(), query(None)).await).await;
+        assert_eq!(diff["nodes_changed"], json!([]));
+
+        let (status, _) = body(version_detail_handler(State(state.clone()), Path(("ws-1".to_string(), 9))).await).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // Editor que carregou a revisão 2 não sobrescreve o restore
+        let (status, conflict) = body(save_workspace(State(state.clone()), axum::extract::Json(hook("late", 2))).await).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(conflict["revision"], json!(3));
+        assert!(state.webhooks.get("ws-1", "hook-1").unwrap().accepts(Some("abc")));
+
+        // Nome que escapa da pasta de workspaces
+        let escape = json!({"name": "../escape", "nodes": [], "edges": []});
+        let (status, _) = body(save_workspace(State(state.clone()), axum::extract::Json(escape)).await).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(!root.join("escape.json").exists());
+        let (status, _) = body(load_workspace(State(state.clone()), Path("../workspaces/ws-1".to_string())).await).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_cache_downstream() {
+        let (state, root) = test_state("ndnm_test_invalidate_cache");
+        let mut rx = state.tx.subscribe();
+        for node_id in ["load-1", "sum-1", "other-1"] {
+            state.runs.cache().store("run_2024-01-01_00-00-00_aaaa", node_id, "hash", &json!({"response": 1})).unwrap();
+        }
+        let metadata = execution::cache::RunMetadata::finished("run_2024-01-01_00-00-00_aaaa", "ws-1");
+        state.runs.cache().write_metadata(&metadata).unwrap();
+
+        let graph = json!({
+            "nodes": [],
+            "connections": [
+                {"from_node_id": "load-1", "from_output_index": 0, "to_node_id": "sum-1", "to_input_index": 0},
+                {"from_node_id": "other-1", "from_output_index": 0, "to_node_id": "sum-1", "to_input_index": 1}
+            ]
+        });
+
+        // Sem grafo (nem enviado, nem workspace salvo) não dá pra achar os dependentes
+        let request = |graph: Option<Value>| {
+            serde_json::from_value::<InvalidateCacheRequest>(json!({
+                "workspace_id": "ws-1", "node_id": "load-1", "downstream": true, "graph": graph
+            }))
+            .unwrap()
+        };
+        assert_eq!(invalidate_cache(&state, request(None)).unwrap_err(), workspace::WorkspaceError::NotFound("ws-1".to_string()));
+        let response = invalidate_cache_handler(State(state.clone()), axum::extract::Json(request(None))).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        assert_eq!(invalidate_cache(&state, request(Some(graph))).unwrap(), vec!["load-1", "sum-1"]);
+        assert!(state.runs.cache().lookup("ws-1", "sum-1", "hash").is_none());
+        assert!(state.runs.cache().lookup("ws-1", "other-1", "hash").is_some());
+
+        let msg: Value = serde_json::from_str(&rx.recv().await.unwrap()).unwrap();
+        assert_eq!(msg, json!({"type": "CACHE_INVALIDATED", "workspace_id": "ws-1", "node_ids": ["load-1", "sum-1"]}));
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
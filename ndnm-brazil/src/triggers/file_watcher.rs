@@ -0,0 +1,425 @@
+// ndnm-brazil/src/triggers/file_watcher.rs
+//
+// PlayFileWatcher: dispara a cadeia do Play quando arquivos de uma pasta são criados ou modificados
+//
+// `data` do node no workspace salvo:
+//   { "path": "C:/dados/entrada", "pattern": "*.csv" }            → arquivos .csv da pasta
+//   { "path": "./imagens", "recursive": true, "debounce_ms": 2000 } → inclui subpastas
+//
+// Funciona por polling (mtime + tamanho de cada arquivo), sem depender de API de SO:
+// - Debounce: o arquivo só dispara depois de ficar `debounce_ms` sem mudar (cópia em andamento não dispara no meio)
+// - Cada arquivo alterado vira um run, com o caminho dele como output do Play
+// - Guarda contra loop: a pasta continua sendo lida durante o run, mas nada dispara até ele terminar.
+//   Ao terminar, os arquivos que o run escreveu (caminhos que aparecem nos outputs dele) viram base;
+//   o resto que mudou nesse meio tempo dispara normalmente depois.
+
+use super::saved_nodes_of_type;
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    future::Future,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
+use walkdir::WalkDir;
+
+/// node_type do PlayFileWatcher (config.yaml do node-play-file-watcher)
+pub const PLAY_FILE_WATCHER_TYPE: &str = "playFileWatcher";
+
+/// Debounce padrão (tempo que o arquivo precisa ficar parado)
+pub const DEFAULT_DEBOUNCE_MS: u64 = 500;
+
+/// De quanto em quanto tempo as pastas são relidas
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Configuração de um watcher (data do node)
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchSettings {
+    pub dir: PathBuf,
+    pub pattern: glob::Pattern,
+    pub recursive: bool,
+    pub debounce: Duration,
+}
+
+impl WatchSettings {
+    /// Lê a configuração do `data` do node. `Ok(None)` = sem pasta configurada ou desligado.
+    /// Mesmas regras de caminho do node-list-directory: precisa existir e ser diretório.
+    pub fn from_data(data: &Value) -> Result<Option<Self>, String> {
+        if data["enabled"] == Value::Bool(false) {
+            return Ok(None);
+        }
+        let Some(path_str) = data["path"].as_str().map(str::trim).filter(|s| !s.is_empty()) else {
+            return Ok(None);
+        };
+
+        let dir = PathBuf::from(path_str);
+        if !dir.exists() {
+            return Err(format!("Caminho não existe: {}", path_str));
+        }
+        if !dir.is_dir() {
+            return Err(format!("Caminho não é um diretório: {}", path_str));
+        }
+
+        let pattern_str = data["pattern"].as_str().map(str::trim).filter(|s| !s.is_empty()).unwrap_or("*");
+        let pattern = glob::Pattern::new(pattern_str).map_err(|e| format!("pattern inválido '{}': {}", pattern_str, e))?;
+
+        let recursive = match &data["recursive"] {
+            Value::Bool(b) => *b,
+            Value::String(s) => s.trim() == "true",
+            _ => false,
+        };
+        let debounce_ms = match &data["debounce_ms"] {
+            Value::Number(n) => n.as_u64(),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+        .unwrap_or(DEFAULT_DEBOUNCE_MS);
+
+        Ok(Some(Self { dir, pattern, recursive, debounce: Duration::from_millis(debounce_ms) }))
+    }
+
+    /// Arquivos que batem com o pattern (pelo nome) → assinatura atual (mtime, tamanho)
+    fn scan(&self) -> HashMap<PathBuf, Signature> {
+        let max_depth = if self.recursive { usize::MAX } else { 1 };
+        WalkDir::new(&self.dir)
+            .min_depth(1)
+            .max_depth(max_depth)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter(|e| e.file_name().to_str().is_some_and(|name| self.pattern.matches(name)))
+            .filter_map(|e| {
+                let metadata = e.metadata().ok()?;
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                Some((e.into_path(), (modified, metadata.len())))
+            })
+            .collect()
+    }
+}
+
+/// O que identifica uma versão do arquivo
+type Signature = (SystemTime, u64);
+
+/// Estado de um watcher
+struct Watch {
+    settings: WatchSettings,
+    /// Última versão aceita de cada arquivo
+    known: HashMap<PathBuf, Signature>,
+    /// Arquivos que mudaram e estão esperando o debounce: versão vista + desde quando está assim
+    pending: HashMap<PathBuf, (Signature, Instant)>,
+    /// Run do watcher em andamento (mudanças nesse meio tempo esperam ele terminar)
+    running: bool,
+}
+
+impl Watch {
+    fn new(settings: WatchSettings) -> Self {
+        let known = settings.scan();
+        Self { settings, known, pending: HashMap::new(), running: false }
+    }
+
+    /// Relê a pasta e devolve os arquivos que passaram do debounce (já aceitos como nova base).
+    /// Com run em andamento só acompanha as mudanças: o que ficou pronto dispara depois do `finish`.
+    fn poll(&mut self, now: Instant) -> Vec<PathBuf> {
+        let current = self.settings.scan();
+        for (path, signature) in &current {
+            if self.known.get(path) == Some(signature) {
+                self.pending.remove(path);
+                continue;
+            }
+            match self.pending.get(path) {
+                Some((seen, _)) if seen == signature => {}
+                // Mudou de novo: reinicia o debounce
+                _ => {
+                    self.pending.insert(path.clone(), (*signature, now));
+                }
+            }
+        }
+        // Arquivo apagado antes do debounce: esquece
+        self.pending.retain(|path, _| current.contains_key(path));
+        self.known.retain(|path, _| current.contains_key(path));
+
+        if self.running {
+            return Vec::new();
+        }
+
+        let debounce = self.settings.debounce;
+        let mut ready: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, (_, since))| now.duration_since(*since) >= debounce)
+            .map(|(path, _)| path.clone())
+            .collect();
+        ready.sort();
+
+        for path in &ready {
+            if let Some((signature, _)) = self.pending.remove(path) {
+                self.known.insert(path.clone(), signature);
+            }
+        }
+        ready
+    }
+
+    /// Fim dos runs do watcher: o que o run escreveu vira base e não dispara;
+    /// as outras mudanças continuam pendentes
+    fn finish(&mut self, written: &[PathBuf]) {
+        self.running = false;
+        let written: Vec<PathBuf> = written.iter().map(|path| canonical(path)).collect();
+        for (path, signature) in self.settings.scan() {
+            if written.contains(&canonical(&path)) {
+                self.pending.remove(&path);
+                self.known.insert(path, signature);
+            }
+        }
+    }
+}
+
+/// Caminho absoluto pra comparar o que o run devolveu com o que o scan achou
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Caminhos que aparecem nos outputs de um run (qualquer string, em qualquer nível do JSON)
+pub fn written_paths(outputs: &Value) -> Vec<PathBuf> {
+    match outputs {
+        Value::String(s) => vec![PathBuf::from(s)],
+        Value::Array(items) => items.iter().flat_map(written_paths).collect(),
+        Value::Object(fields) => fields.values().flat_map(written_paths).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Arquivos alterados de um watcher: (workspace, id do Play, caminhos)
+pub type FileChanges = (String, String, Vec<PathBuf>);
+
+/// Watchers de todos os PlayFileWatchers dos workspaces salvos
+#[derive(Default)]
+pub struct FileWatcher {
+    watches: Mutex<HashMap<(String, String), Watch>>,
+}
+
+impl FileWatcher {
+    /// Carrega os watchers de todos os workspaces de uma pasta
+    pub fn load_dir(&self, dir: &Path) {
+        for (name, workspace) in super::read_saved_workspaces(dir) {
+            self.load_workspace(&name, &workspace);
+        }
+    }
+
+    /// (Re)carrega os watchers de um workspace (startup e a cada save).
+    /// Watcher com a mesma configuração continua de onde estava.
+    pub fn load_workspace(&self, workspace_id: &str, workspace: &Value) {
+        let mut watches = self.watches.lock().unwrap_or_else(|e| e.into_inner());
+
+        let mut old: HashMap<(String, String), Watch> = HashMap::new();
+        for key in watches.keys().filter(|(ws, _)| ws == workspace_id).cloned().collect::<Vec<_>>() {
+            if let Some(watch) = watches.remove(&key) {
+                old.insert(key, watch);
+            }
+        }
+
+        for (play_node_id, data) in saved_nodes_of_type(workspace, PLAY_FILE_WATCHER_TYPE) {
+            let settings = match WatchSettings::from_data(data) {
+                Ok(Some(settings)) => settings,
+                Ok(None) => continue,
+                Err(e) => {
+                    println!("⚠️  [FileWatcher] {}/{} ignorado: {}", workspace_id, play_node_id, e);
+                    continue;
+                }
+            };
+
+            let key = (workspace_id.to_string(), play_node_id.to_string());
+            let watch = match old.remove(&key) {
+                Some(previous) if previous.settings == settings => previous,
+                _ => {
+                    println!("👀 [FileWatcher] {}/{}: {} ({})",
+                        workspace_id, play_node_id, settings.dir.display(), settings.pattern);
+                    Watch::new(settings)
+                }
+            };
+            watches.insert(key, watch);
+        }
+    }
+
+    /// Relê as pastas e devolve os arquivos prontos pra disparar, marcando os watchers como rodando
+    pub fn poll(&self, now: Instant) -> Vec<FileChanges> {
+        let mut watches = self.watches.lock().unwrap_or_else(|e| e.into_inner());
+        let mut changes: Vec<FileChanges> = watches
+            .iter_mut()
+            .filter_map(|((workspace_id, play_node_id), watch)| {
+                let ready = watch.poll(now);
+                if ready.is_empty() {
+                    return None;
+                }
+                watch.running = true;
+                Some((workspace_id.clone(), play_node_id.clone(), ready))
+            })
+            .collect();
+        changes.sort();
+        changes
+    }
+
+    /// Fim dos runs de um watcher. `written` = caminhos que os runs escreveram (não disparam).
+    pub fn finish(&self, workspace_id: &str, play_node_id: &str, written: &[PathBuf]) {
+        let mut watches = self.watches.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(watch) = watches.get_mut(&(workspace_id.to_string(), play_node_id.to_string())) {
+            watch.finish(written);
+        }
+    }
+
+    /// Sobe o loop dos watchers. `fire(workspace, play, caminho)` executa o Play com o caminho
+    /// como output e só resolve quando o run termina (um run por arquivo, em sequência),
+    /// devolvendo o resultado do run em JSON (`Null` se falhou) pra saber o que ele escreveu.
+    pub fn spawn<F, Fut>(self: &Arc<Self>, fire: F)
+    where
+        F: Fn(String, String, Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Value> + Send + 'static,
+    {
+        let watcher = Arc::clone(self);
+        let fire = Arc::new(fire);
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(POLL_INTERVAL);
+            loop {
+                ticker.tick().await;
+                // Ler as pastas é IO bloqueante
+                let poller = Arc::clone(&watcher);
+                let Ok(changes) = tokio::task::spawn_blocking(move || poller.poll(Instant::now())).await else {
+                    continue;
+                };
+
+                for (workspace_id, play_node_id, paths) in changes {
+                    let watcher = Arc::clone(&watcher);
+                    let fire = Arc::clone(&fire);
+                    tokio::spawn(async move {
+                        let mut written = Vec::new();
+                        for path in paths {
+                            println!("👀 [FileWatcher] {}/{} disparado por {}", workspace_id, play_node_id, path.display());
+                            let payload = Value::String(path.to_string_lossy().into_owned());
+                            let result = fire(workspace_id.clone(), play_node_id.clone(), payload).await;
+                            written.extend(written_paths(&result));
+                        }
+                        let _ = tokio::task::spawn_blocking(move || watcher.finish(&workspace_id, &play_node_id, &written)).await;
+                    });
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+
+    fn setup_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn watcher_for(dir: &Path) -> FileWatcher {
+        let watcher = FileWatcher::default();
+        watcher.load_workspace("ws", &json!({
+            "nodes": [{"id": "watch-1", "type": "playFileWatcher", "data": {
+                "path": dir.to_string_lossy(),
+                "pattern": "*.csv",
+                "debounce_ms": "100"
+            }}]
+        }));
+        watcher
+    }
+
+    #[test]
+    fn test_settings_validate_path() {
+        let dir = setup_dir("ndnm_test_watch_settings");
+        let file = dir.join("a.txt");
+        fs::write(&file, "x").unwrap();
+
+        let settings = WatchSettings::from_data(&json!({"path": dir.to_string_lossy()})).unwrap().unwrap();
+        assert_eq!(settings.debounce, Duration::from_millis(DEFAULT_DEBOUNCE_MS));
+        assert!(!settings.recursive);
+
+        assert!(WatchSettings::from_data(&json!({"path": ""})).unwrap().is_none());
+        let not_dir = WatchSettings::from_data(&json!({"path": file.to_string_lossy()})).unwrap_err();
+        assert!(not_dir.starts_with("Caminho não é um diretório"));
+        assert!(WatchSettings::from_data(&json!({"path": dir.join("nope").to_string_lossy()})).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_debounce_and_pattern() {
+        let dir = setup_dir("ndnm_test_watch_debounce");
+        fs::write(dir.join("old.csv"), "antigo").unwrap();
+        let watcher = watcher_for(&dir);
+        let t0 = Instant::now();
+
+        // Arquivo que já existia não dispara; .txt não bate com o pattern
+        fs::write(dir.join("new.csv"), "1,2").unwrap();
+        fs::write(dir.join("notes.txt"), "ignorado").unwrap();
+        assert!(watcher.poll(t0).is_empty());
+
+        // Mudou de novo antes do debounce: reinicia a contagem
+        fs::write(dir.join("new.csv"), "1,2,3").unwrap();
+        assert!(watcher.poll(t0 + Duration::from_millis(80)).is_empty());
+        assert!(watcher.poll(t0 + Duration::from_millis(150)).is_empty());
+
+        let changes = watcher.poll(t0 + Duration::from_millis(200));
+        assert_eq!(changes, vec![("ws".to_string(), "watch-1".to_string(), vec![dir.join("new.csv")])]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_run_output_does_not_retrigger() {
+        let dir = setup_dir("ndnm_test_watch_guard");
+        let watcher = watcher_for(&dir);
+        let t0 = Instant::now();
+
+        fs::write(dir.join("in.csv"), "1").unwrap();
+        watcher.poll(t0);
+        assert_eq!(watcher.poll(t0 + Duration::from_millis(100)).len(), 1);
+
+        // O run escreve um csv na mesma pasta enquanto roda e devolve o caminho no output
+        fs::write(dir.join("out.csv"), "resultado").unwrap();
+        assert!(watcher.poll(t0 + Duration::from_millis(200)).is_empty());
+        let result = json!({"outputs": {"save-1": {"outputs": [dir.join("out.csv").to_string_lossy()]}}});
+        watcher.finish("ws", "watch-1", &written_paths(&result));
+
+        // Depois do run, o arquivo dele já é a base: não dispara
+        assert!(watcher.poll(t0 + Duration::from_millis(300)).is_empty());
+        assert!(watcher.poll(t0 + Duration::from_millis(500)).is_empty());
+
+        // Arquivo novo de fora volta a disparar
+        fs::write(dir.join("in2.csv"), "2").unwrap();
+        watcher.poll(t0 + Duration::from_millis(600));
+        assert_eq!(watcher.poll(t0 + Duration::from_millis(700)).len(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_outside_change_during_run_fires_after_finish() {
+        let dir = setup_dir("ndnm_test_watch_mid_run");
+        let watcher = watcher_for(&dir);
+        let t0 = Instant::now();
+
+        fs::write(dir.join("in.csv"), "1").unwrap();
+        watcher.poll(t0);
+        assert_eq!(watcher.poll(t0 + Duration::from_millis(100)).len(), 1);
+
+        // Chega um arquivo de fora com o run rodando: espera, mas não se perde
+        fs::write(dir.join("outside.csv"), "2").unwrap();
+        watcher.poll(t0 + Duration::from_millis(200));
+        assert!(watcher.poll(t0 + Duration::from_millis(400)).is_empty());
+
+        watcher.finish("ws", "watch-1", &[]);
+        let changes = watcher.poll(t0 + Duration::from_millis(500));
+        assert_eq!(changes, vec![("ws".to_string(), "watch-1".to_string(), vec![dir.join("outside.csv")])]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,72 @@
+// ndnm-brazil/src/triggers/mod.rs
+//
+// Plays disparados sem clique (Fase 5 do PLAY_SYSTEM.md)
+// A configuração de cada gatilho mora no `data` do Play dentro do workspace salvo
+// (workspaces/<nome>.json); o Brazil lê os workspaces e agenda/escuta por conta própria.
+
+pub mod timer;
+pub mod webhook;
+pub mod file_watcher;
+
+use crate::workspace;
+use serde_json::Value;
+use std::path::Path;
+
+/// Lê todos os workspaces salvos de uma pasta (nome → workspace já migrado, em JSON).
+/// Mesmo caminho do save (`reload_triggers`): nome inválido ou arquivo oculto fica de fora,
+/// então todo gatilho registrado aqui consegue ser disparado depois.
+pub fn read_saved_workspaces(dir: &Path) -> Vec<(String, Value)> {
+    workspace::store::list(dir)
+        .into_iter()
+        .filter_map(|summary| match workspace::read(dir, &summary.name) {
+            Ok(saved) => Some((summary.name, serde_json::to_value(saved).ok()?)),
+            Err(e) => {
+                println!("⚠️  [Triggers] {} ignorado: {}", summary.name, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Nodes de um tipo num workspace salvo (formato do editor: `nodes[].type` / `nodes[].data`)
+pub fn saved_nodes_of_type<'a>(workspace: &'a Value, node_type: &str) -> Vec<(&'a str, &'a Value)> {
+    workspace["nodes"]
+        .as_array()
+        .map(|nodes| {
+            nodes
+                .iter()
+                .filter(|n| n["type"].as_str() == Some(node_type))
+                .filter_map(|n| Some((n["id"].as_str()?, &n["data"])))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+
+    #[test]
+    fn test_reads_only_loadable_workspaces() {
+        let dir = std::env::temp_dir().join("ndnm_test_triggers_saved");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let saved = json!({"nodes": [{"id": "timer-1", "type": "playTimer", "data": {"interval_seconds": 60}}], "edges": []});
+        for name in ["ws-1", ".hidden", "CON"] {
+            fs::write(dir.join(format!("{}.json", name)), saved.to_string()).unwrap();
+        }
+
+        let workspaces = read_saved_workspaces(&dir);
+        let names: Vec<&str> = workspaces.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["ws-1"]);
+        // Passou pelo migrate: já tem o nome e a versão atual
+        assert_eq!(workspaces[0].1["name"], json!("ws-1"));
+        assert_eq!(workspaces[0].1["schema_version"], json!(workspace::CURRENT_SCHEMA_VERSION));
+        assert_eq!(saved_nodes_of_type(&workspaces[0].1, "playTimer").len(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
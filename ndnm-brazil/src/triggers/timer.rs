@@ -0,0 +1,449 @@
+// ndnm-brazil/src/triggers/timer.rs
+//
+// PlayTimer: dispara a cadeia do Play num intervalo fixo ou numa expressão cron
+//
+// `data` do node no workspace salvo:
+//   { "interval_seconds": 3600 }                  → a cada hora
+//   { "cron": "0 * * * *", "overlap": "queue" }   → no minuto 0 de toda hora
+//   { "enabled": false, ... }                     → timer desligado
+//
+// `overlap` = o que fazer quando o disparo chega e o run anterior ainda está rodando:
+//   skip  (padrão) → pula esse disparo
+//   queue          → roda assim que o anterior terminar (no máximo 1 na fila)
+//   allow          → roda em paralelo
+
+use super::saved_nodes_of_type;
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    future::Future,
+    path::Path,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// node_type do PlayTimer (config.yaml do node-play-timer)
+pub const PLAY_TIMER_TYPE: &str = "playTimer";
+
+/// Menor intervalo aceito (evita um Play disparando sem parar por engano)
+pub const MIN_INTERVAL_SECONDS: u64 = 1;
+
+/// De quanto em quanto tempo o loop confere os timers
+const TICK: Duration = Duration::from_millis(500);
+
+/// O que fazer com um disparo quando o run anterior ainda não terminou
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverlapPolicy {
+    #[default]
+    Skip,
+    Queue,
+    Allow,
+}
+
+/// Quando o timer dispara
+#[derive(Debug, Clone)]
+pub enum TimerSchedule {
+    Interval(Duration),
+    /// Expressão como o usuário escreveu + a agenda já parseada
+    Cron(String, Box<Schedule>),
+}
+
+impl TimerSchedule {
+    /// Lê a agenda do `data` do node. `Ok(None)` = timer desligado ou sem agenda configurada.
+    /// Campos de texto do editor chegam como string, então "3600" vale igual a 3600.
+    pub fn from_data(data: &Value) -> Result<Option<Self>, String> {
+        if data["enabled"] == Value::Bool(false) {
+            return Ok(None);
+        }
+
+        if let Some(expr) = data["cron"].as_str().map(str::trim).filter(|s| !s.is_empty()) {
+            return Self::cron(expr).map(Some);
+        }
+
+        let seconds = match &data["interval_seconds"] {
+            Value::Number(n) => n.as_u64(),
+            Value::String(s) if s.trim().is_empty() => return Ok(None),
+            Value::String(s) => s.trim().parse().ok(),
+            Value::Null => return Ok(None),
+            _ => None,
+        };
+
+        match seconds {
+            Some(s) if s >= MIN_INTERVAL_SECONDS => Ok(Some(Self::Interval(Duration::from_secs(s)))),
+            _ => Err(format!("interval_seconds inválido: {}", data["interval_seconds"])),
+        }
+    }
+
+    /// Cron de 5 campos (min hora dia mês dia-da-semana, padrão Unix: 0/7 = domingo, 1 = segunda)
+    /// ou de 6/7 com segundos/ano, no formato do crate `cron` (dia da semana 1 = domingo)
+    pub fn cron(expr: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let full = match fields.as_slice() {
+            [minute, hour, day, month, weekday] => {
+                let weekday = unix_weekdays(weekday).ok_or_else(|| format!("cron inválido '{}': dia da semana", expr))?;
+                format!("0 {} {} {} {} {}", minute, hour, day, month, weekday)
+            }
+            _ => expr.to_string(),
+        };
+        let schedule = Schedule::from_str(&full).map_err(|e| format!("cron inválido '{}': {}", expr, e))?;
+        Ok(Self::Cron(expr.to_string(), Box::new(schedule)))
+    }
+
+    /// Próximo disparo depois de `after`
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Interval(interval) => chrono::Duration::from_std(*interval).ok().map(|d| after + d),
+            Self::Cron(_, schedule) => schedule.after(&after).next(),
+        }
+    }
+
+    /// Texto pro frontend ("every 3600s" / "cron 0 * * * *")
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Interval(interval) => format!("every {}s", interval.as_secs()),
+            Self::Cron(expr, _) => format!("cron {}", expr),
+        }
+    }
+}
+
+/// Nomes dos dias no crate `cron` (o número dele começa em 1 = domingo, o do Unix em 0)
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+/// Traduz o dia da semana do cron Unix pros nomes que o crate `cron` entende:
+/// "1-5" → "Mon,Tue,Wed,Thu,Fri", "0" e "7" → "Sun", "*/2" → "Sun,Tue,Thu,Sat".
+/// Item que já é nome ("MON-FRI") passa direto. `None` = número fora de 0-7.
+fn unix_weekdays(field: &str) -> Option<String> {
+    if field == "*" || field == "?" {
+        return Some(field.to_string());
+    }
+
+    let mut items = Vec::new();
+    for item in field.split(',') {
+        let (base, step) = match item.split_once('/') {
+            Some((base, step)) => (base, Some(step.parse::<usize>().ok().filter(|s| *s > 0)?)),
+            None => (item, None),
+        };
+        let (start, end) = match base.split_once('-') {
+            _ if base == "*" => (0, 6),
+            Some((start, end)) => match (start.parse::<usize>(), end.parse::<usize>()) {
+                (Ok(start), Ok(end)) => (start, end),
+                _ => {
+                    items.push(item.to_string());
+                    continue;
+                }
+            },
+            None => match base.parse::<usize>() {
+                // "1/2" no cron Unix = de 1 até o fim, de 2 em 2
+                Ok(day) => (day, if step.is_some() { 6 } else { day }),
+                Err(_) => {
+                    items.push(item.to_string());
+                    continue;
+                }
+            },
+        };
+        if start > 7 || end > 7 || start > end {
+            return None;
+        }
+        items.extend((start..=end).step_by(step.unwrap_or(1)).map(|day| WEEKDAYS[day % 7].to_string()));
+    }
+    Some(items.join(","))
+}
+
+/// Estado de um timer, como vai pro frontend (GET /timers e TIMERS_UPDATED)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimerInfo {
+    pub workspace_id: String,
+    pub play_node_id: String,
+    pub schedule: String,
+    pub overlap: OverlapPolicy,
+    pub next_fire_at: Option<DateTime<Utc>>,
+    pub running: bool,
+    pub queued: bool,
+}
+
+struct TimerEntry {
+    schedule: TimerSchedule,
+    overlap: OverlapPolicy,
+    next_fire: Option<DateTime<Utc>>,
+    /// Runs desse timer rodando agora (só passa de 1 com overlap = allow)
+    running: usize,
+    queued: bool,
+}
+
+/// Chave de um timer: (workspace, id do Play)
+type TimerKey = (String, String);
+
+/// Agenda de todos os PlayTimers dos workspaces salvos
+#[derive(Default)]
+pub struct TimerScheduler {
+    entries: Mutex<HashMap<TimerKey, TimerEntry>>,
+}
+
+impl TimerScheduler {
+    /// Carrega os timers de todos os workspaces de uma pasta
+    pub fn load_dir(&self, dir: &Path, now: DateTime<Utc>) {
+        for (name, workspace) in super::read_saved_workspaces(dir) {
+            self.load_workspace(&name, &workspace, now);
+        }
+    }
+
+    /// (Re)carrega os timers de um workspace (chamado no startup e a cada save).
+    /// Timer que continua com a mesma agenda mantém o próximo disparo e os runs em andamento.
+    pub fn load_workspace(&self, workspace_id: &str, workspace: &Value, now: DateTime<Utc>) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+
+        let old: HashMap<TimerKey, TimerEntry> = entries
+            .keys()
+            .filter(|(ws, _)| ws == workspace_id)
+            .cloned()
+            .collect::<Vec<_>>()
+            .into_iter()
+            .filter_map(|key| entries.remove(&key).map(|entry| (key, entry)))
+            .collect();
+
+        for (play_node_id, data) in saved_nodes_of_type(workspace, PLAY_TIMER_TYPE) {
+            let schedule = match TimerSchedule::from_data(data) {
+                Ok(Some(schedule)) => schedule,
+                Ok(None) => continue,
+                Err(e) => {
+                    println!("⚠️  [Timer] {}/{} ignorado: {}", workspace_id, play_node_id, e);
+                    continue;
+                }
+            };
+            let overlap = serde_json::from_value(data["overlap"].clone()).unwrap_or_default();
+
+            let key = (workspace_id.to_string(), play_node_id.to_string());
+            let mut entry = TimerEntry {
+                next_fire: schedule.next_after(now),
+                schedule,
+                overlap,
+                running: 0,
+                queued: false,
+            };
+            if let Some(previous) = old.get(&key) {
+                entry.running = previous.running;
+                entry.queued = previous.queued;
+                if previous.schedule.describe() == entry.schedule.describe() {
+                    entry.next_fire = previous.next_fire;
+                }
+            }
+
+            println!("⏰ [Timer] {}/{}: {} (próximo: {:?})",
+                workspace_id, play_node_id, entry.schedule.describe(), entry.next_fire);
+            entries.insert(key, entry);
+        }
+    }
+
+    /// Todos os timers, ordenados por workspace e Play
+    pub fn timers(&self) -> Vec<TimerInfo> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let mut timers: Vec<TimerInfo> = entries
+            .iter()
+            .map(|((workspace_id, play_node_id), entry)| TimerInfo {
+                workspace_id: workspace_id.clone(),
+                play_node_id: play_node_id.clone(),
+                schedule: entry.schedule.describe(),
+                overlap: entry.overlap,
+                next_fire_at: entry.next_fire,
+                running: entry.running > 0,
+                queued: entry.queued,
+            })
+            .collect();
+        timers.sort_by(|a, b| (&a.workspace_id, &a.play_node_id).cmp(&(&b.workspace_id, &b.play_node_id)));
+        timers
+    }
+
+    /// Timers que devem disparar agora. Já agenda o próximo disparo, aplica a política de
+    /// overlap e conta os devolvidos como rodando (quem dispara chama `finish` no fim).
+    /// Disparos perdidos (Brazil parado, máquina dormindo) não são recuperados: o próximo é depois de `now`.
+    pub fn due(&self, now: DateTime<Utc>) -> Vec<TimerKey> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let mut fire = Vec::new();
+
+        for (key, entry) in entries.iter_mut() {
+            if entry.next_fire.is_none_or(|at| at > now) {
+                continue;
+            }
+            entry.next_fire = entry.schedule.next_after(now);
+
+            if entry.running > 0 {
+                match entry.overlap {
+                    OverlapPolicy::Skip => {
+                        println!("⏭️  [Timer] {}/{}: run anterior ainda rodando, disparo pulado", key.0, key.1);
+                        continue;
+                    }
+                    OverlapPolicy::Queue => {
+                        entry.queued = true;
+                        continue;
+                    }
+                    OverlapPolicy::Allow => {}
+                }
+            }
+
+            entry.running += 1;
+            fire.push(key.clone());
+        }
+
+        fire.sort();
+        fire
+    }
+
+    /// Marca o fim de um run do timer. `true` = tinha disparo na fila e ele deve rodar agora.
+    pub fn finish(&self, workspace_id: &str, play_node_id: &str) -> bool {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(entry) = entries.get_mut(&(workspace_id.to_string(), play_node_id.to_string())) else {
+            return false; // Timer removido do workspace enquanto rodava
+        };
+
+        entry.running = entry.running.saturating_sub(1);
+        if entry.queued {
+            entry.queued = false;
+            entry.running += 1;
+            return true;
+        }
+        false
+    }
+
+    /// Sobe o loop dos timers. `fire(workspace, play)` executa o Play e só resolve quando o run termina;
+    /// `notify` recebe a lista de timers sempre que algo muda (disparo, fim de run).
+    pub fn spawn<F, Fut, N>(self: &Arc<Self>, fire: F, notify: N)
+    where
+        F: Fn(String, String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+        N: Fn(Vec<TimerInfo>) + Send + Sync + 'static,
+    {
+        let scheduler = Arc::clone(self);
+        let fire = Arc::new(fire);
+        let notify = Arc::new(notify);
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(TICK);
+            loop {
+                ticker.tick().await;
+                let due = scheduler.due(Utc::now());
+                if due.is_empty() {
+                    continue;
+                }
+                notify(scheduler.timers());
+
+                for (workspace_id, play_node_id) in due {
+                    let scheduler = Arc::clone(&scheduler);
+                    let fire = Arc::clone(&fire);
+                    let notify = Arc::clone(&notify);
+                    tokio::spawn(async move {
+                        loop {
+                            println!("⏰ [Timer] Disparando {}/{}", workspace_id, play_node_id);
+                            fire(workspace_id.clone(), play_node_id.clone()).await;
+                            let again = scheduler.finish(&workspace_id, &play_node_id);
+                            notify(scheduler.timers());
+                            if !again {
+                                break;
+                            }
+                        }
+                    });
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn workspace(data: Value) -> Value {
+        json!({
+            "name": "ws",
+            "nodes": [
+                {"id": "timer-1", "type": "playTimer", "data": data},
+                {"id": "n1", "type": "add", "data": {}}
+            ],
+            "edges": []
+        })
+    }
+
+    #[test]
+    fn test_schedule_from_node_data() {
+        let t0 = Utc.with_ymd_and_hms(2024, 10, 20, 22, 30, 15).unwrap();
+
+        let interval = TimerSchedule::from_data(&json!({"interval_seconds": "90"})).unwrap().unwrap();
+        assert_eq!(interval.next_after(t0), Some(t0 + chrono::Duration::seconds(90)));
+
+        // Cron de 5 campos: minuto 0 de toda hora
+        let hourly = TimerSchedule::from_data(&json!({"cron": "0 * * * *"})).unwrap().unwrap();
+        assert_eq!(hourly.next_after(t0), Some(Utc.with_ymd_and_hms(2024, 10, 20, 23, 0, 0).unwrap()));
+        assert_eq!(hourly.describe(), "cron 0 * * * *");
+
+        assert!(TimerSchedule::from_data(&json!({"cron": "toda hora"})).is_err());
+        assert!(TimerSchedule::from_data(&json!({"cron": "0 9 * * 8"})).is_err());
+        assert!(TimerSchedule::from_data(&json!({"interval_seconds": 0})).is_err());
+        assert!(TimerSchedule::from_data(&json!({"interval_seconds": 60, "enabled": false})).unwrap().is_none());
+        assert!(TimerSchedule::from_data(&json!({"value": ""})).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_cron_weekdays_are_unix() {
+        use chrono::Datelike;
+        // Sábado, 19/10/2024
+        let saturday = Utc.with_ymd_and_hms(2024, 10, 19, 12, 0, 0).unwrap();
+
+        let weekdays = TimerSchedule::cron("0 9 * * 1-5").unwrap();
+        let fires: Vec<_> = std::iter::successors(weekdays.next_after(saturday), |t| weekdays.next_after(*t))
+            .take(7)
+            .map(|t| t.weekday().to_string())
+            .collect();
+        assert_eq!(fires, ["Mon", "Tue", "Wed", "Thu", "Fri", "Mon", "Tue"]);
+
+        // 0 e 7 = domingo
+        for expr in ["0 9 * * 0", "0 9 * * 7"] {
+            let sunday = TimerSchedule::cron(expr).unwrap();
+            assert_eq!(sunday.next_after(saturday), Some(Utc.with_ymd_and_hms(2024, 10, 20, 9, 0, 0).unwrap()));
+        }
+        assert_eq!(unix_weekdays("*/2").as_deref(), Some("Sun,Tue,Thu,Sat"));
+        assert_eq!(unix_weekdays("5-7,MON").as_deref(), Some("Fri,Sat,Sun,MON"));
+    }
+
+    #[test]
+    fn test_overlap_policies() {
+        let t0 = Utc.with_ymd_and_hms(2024, 10, 20, 22, 0, 0).unwrap();
+        let tick = |n: i64| t0 + chrono::Duration::seconds(10 * n);
+
+        for (overlap, fired_while_running, fires_after_finish) in [("skip", 0, false), ("queue", 0, true), ("allow", 1, false)] {
+            let scheduler = TimerScheduler::default();
+            scheduler.load_workspace("ws", &workspace(json!({"interval_seconds": 10, "overlap": overlap})), t0);
+
+            assert!(scheduler.due(t0).is_empty());
+            assert_eq!(scheduler.due(tick(1)).len(), 1, "{}", overlap);
+            // Segundo disparo com o primeiro run ainda rodando
+            assert_eq!(scheduler.due(tick(2)).len(), fired_while_running, "{}", overlap);
+            assert_eq!(scheduler.finish("ws", "timer-1"), fires_after_finish, "{}", overlap);
+
+            let info = &scheduler.timers()[0];
+            assert_eq!(info.next_fire_at, Some(tick(3)));
+        }
+    }
+
+    #[test]
+    fn test_reload_keeps_running_state() {
+        let t0 = Utc.with_ymd_and_hms(2024, 10, 20, 22, 0, 0).unwrap();
+        let scheduler = TimerScheduler::default();
+        scheduler.load_workspace("ws", &workspace(json!({"interval_seconds": 10})), t0);
+        assert_eq!(scheduler.due(t0 + chrono::Duration::seconds(10)).len(), 1);
+
+        // Save do workspace com o run rodando: mesmo timer, continua marcado como rodando
+        scheduler.load_workspace("ws", &workspace(json!({"interval_seconds": 10})), t0);
+        assert!(scheduler.timers()[0].running);
+
+        // Timer removido do workspace some da agenda
+        scheduler.load_workspace("ws", &json!({"nodes": []}), t0);
+        assert!(scheduler.timers().is_empty());
+        assert!(!scheduler.finish("ws", "timer-1"));
+    }
+}
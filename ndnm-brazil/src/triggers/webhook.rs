@@ -0,0 +1,161 @@
+// ndnm-brazil/src/triggers/webhook.rs
+//
+// PlayWebhook: dispara a cadeia do Play com POST /hooks/{workspace}/{play_id}
+// O body do request vira o output do Play, ou seja, o valor que desce pros Plays em cascata.
+//
+// `data` do node no workspace salvo:
+//   { "secret": "abc123" }     → exige o header X-NDNM-Webhook-Secret: abc123
+//   { "response": "wait" }     → responde só no fim do run, com os outputs finais
+//   { "response": "run_id" }   → (padrão) responde 202 com o run_id na hora
+
+use super::saved_nodes_of_type;
+use crate::execution::ExecutionResult;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{collections::HashMap, path::Path, sync::Mutex};
+
+/// node_type do PlayWebhook (config.yaml do node-play-webhook)
+pub const PLAY_WEBHOOK_TYPE: &str = "playWebhook";
+
+/// Header com o segredo compartilhado
+pub const SECRET_HEADER: &str = "x-ndnm-webhook-secret";
+
+/// Quando o POST /hooks responde
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookResponse {
+    /// 202 com o run_id assim que o run é disparado
+    #[default]
+    RunId,
+    /// Espera o run (e a cascata) terminar e devolve os outputs finais
+    Wait,
+}
+
+/// Configuração de um webhook (data do node)
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WebhookSettings {
+    pub secret: Option<String>,
+    pub response: WebhookResponse,
+}
+
+impl WebhookSettings {
+    pub fn from_data(data: &Value) -> Self {
+        Self {
+            secret: data["secret"].as_str().map(str::trim).filter(|s| !s.is_empty()).map(String::from),
+            response: serde_json::from_value(data["response"].clone()).unwrap_or_default(),
+        }
+    }
+
+    /// Confere o segredo do header (sem segredo configurado, aceita qualquer request).
+    /// Comparação em tempo constante pra não vazar o segredo pelo tempo de resposta.
+    pub fn accepts(&self, provided: Option<&str>) -> bool {
+        let Some(secret) = &self.secret else { return true };
+        let Some(provided) = provided else { return false };
+
+        secret.len() == provided.len()
+            && secret.bytes().zip(provided.bytes()).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+    }
+}
+
+/// Webhooks registrados pelos workspaces salvos: (workspace, id do Play) → configuração
+#[derive(Default)]
+pub struct WebhookRegistry {
+    hooks: Mutex<HashMap<(String, String), WebhookSettings>>,
+}
+
+impl WebhookRegistry {
+    /// Registra os webhooks de todos os workspaces de uma pasta
+    pub fn load_dir(&self, dir: &Path) {
+        for (name, workspace) in super::read_saved_workspaces(dir) {
+            self.load_workspace(&name, &workspace);
+        }
+    }
+
+    /// (Re)registra os webhooks de um workspace (startup e a cada save)
+    pub fn load_workspace(&self, workspace_id: &str, workspace: &Value) {
+        let mut hooks = self.hooks.lock().unwrap_or_else(|e| e.into_inner());
+        hooks.retain(|(ws, _), _| ws != workspace_id);
+
+        for (play_node_id, data) in saved_nodes_of_type(workspace, PLAY_WEBHOOK_TYPE) {
+            println!("🪝 [Webhook] POST /hooks/{}/{}", workspace_id, play_node_id);
+            hooks.insert(
+                (workspace_id.to_string(), play_node_id.to_string()),
+                WebhookSettings::from_data(data),
+            );
+        }
+    }
+
+    pub fn get(&self, workspace_id: &str, play_node_id: &str) -> Option<WebhookSettings> {
+        let hooks = self.hooks.lock().unwrap_or_else(|e| e.into_inner());
+        hooks.get(&(workspace_id.to_string(), play_node_id.to_string())).cloned()
+    }
+}
+
+/// Body do request → output do Play: JSON se for JSON, senão o texto cru (vazio = null)
+pub fn payload_from_body(body: &[u8]) -> Value {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Value::Null;
+    }
+    serde_json::from_slice(body).unwrap_or_else(|_| Value::String(String::from_utf8_lossy(body).into_owned()))
+}
+
+/// Outputs do fim da cascata: os nodes no fim da cadeia do webhook (e os marcados como output)
+/// e os Plays que terminaram sem disparar nenhum outro. Sem nada disso, o próprio Play do webhook.
+/// Node/Play id → output.
+pub fn terminal_outputs(result: &ExecutionResult, root_play_id: &str) -> Map<String, Value> {
+    let mut outputs: Map<String, Value> = result.outputs.iter().map(|(id, output)| (id.clone(), output.clone())).collect();
+
+    for child in &result.child_runs {
+        let triggered_others = result.child_runs.iter().any(|c| c.triggered_by == child.play_node_id);
+        if !triggered_others {
+            outputs.insert(child.play_node_id.clone(), child.output.clone().unwrap_or(Value::Null));
+        }
+    }
+
+    if outputs.is_empty() {
+        outputs.insert(root_play_id.to_string(), result.output.clone().unwrap_or(Value::Null));
+    }
+    outputs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_registry_and_secret() {
+        let workspace = json!({
+            "nodes": [
+                {"id": "hook-1", "type": "playWebhook", "data": {"secret": "abc123", "response": "wait"}},
+                {"id": "hook-2", "type": "playWebhook", "data": {}},
+                {"id": "n1", "type": "add", "data": {}}
+            ]
+        });
+        let registry = WebhookRegistry::default();
+        registry.load_workspace("ws", &workspace);
+
+        let secured = registry.get("ws", "hook-1").unwrap();
+        assert_eq!(secured.response, WebhookResponse::Wait);
+        assert!(secured.accepts(Some("abc123")));
+        assert!(!secured.accepts(Some("abc124")));
+        assert!(!secured.accepts(None));
+
+        let open = registry.get("ws", "hook-2").unwrap();
+        assert_eq!(open.response, WebhookResponse::RunId);
+        assert!(open.accepts(None));
+
+        assert!(registry.get("ws", "n1").is_none());
+
+        // Save sem o webhook desregistra a rota
+        registry.load_workspace("ws", &json!({"nodes": []}));
+        assert!(registry.get("ws", "hook-1").is_none());
+    }
+
+    #[test]
+    fn test_payload_from_body() {
+        assert_eq!(payload_from_body(br#"{"order": 42}"#), json!({"order": 42}));
+        assert_eq!(payload_from_body(b"ping"), json!("ping"));
+        assert_eq!(payload_from_body(b""), Value::Null);
+    }
+}
@@ -0,0 +1,149 @@
+// ndnm-brazil/src/workspace/diff.rs
+//
+// Diff estrutural entre duas versões de um workspace
+// - nodes e edges são comparados pelo id
+// - node mudou = tipo ou algum campo do `data` diferente (mover no canvas não conta)
+// - edge com o mesmo id mas ligando outras pontas sai como removida + adicionada
+
+use super::schema::{Workspace, WorkspaceEdge, WorkspaceNode};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct WorkspaceDiff {
+    pub nodes_added: Vec<String>,
+    pub nodes_removed: Vec<String>,
+    pub nodes_changed: Vec<NodeChange>,
+    pub edges_added: Vec<WorkspaceEdge>,
+    pub edges_removed: Vec<WorkspaceEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeChange {
+    pub id: String,
+    pub changes: Vec<FieldChange>,
+}
+
+/// `field` = "type" ou a chave do `data`; None = campo não existia daquele lado
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+fn node_changes(before: &WorkspaceNode, after: &WorkspaceNode) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    if before.node_type != after.node_type {
+        changes.push(FieldChange {
+            field: "type".to_string(),
+            before: Some(Value::String(before.node_type.clone())),
+            after: Some(Value::String(after.node_type.clone())),
+        });
+    }
+
+    let keys: BTreeSet<&String> = before.data.keys().chain(after.data.keys()).collect();
+    for key in keys {
+        let (old, new) = (before.data.get(key), after.data.get(key));
+        if old != new {
+            changes.push(FieldChange { field: key.clone(), before: old.cloned(), after: new.cloned() });
+        }
+    }
+    changes
+}
+
+fn same_ends(a: &WorkspaceEdge, b: &WorkspaceEdge) -> bool {
+    (&a.source, &a.source_handle, &a.target, &a.target_handle) == (&b.source, &b.source_handle, &b.target, &b.target_handle)
+}
+
+/// O que mudou de `before` pra `after`
+pub fn diff(before: &Workspace, after: &Workspace) -> WorkspaceDiff {
+    let mut result = WorkspaceDiff::default();
+
+    let old_nodes: HashMap<&str, &WorkspaceNode> = before.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+    let new_nodes: HashMap<&str, &WorkspaceNode> = after.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+    for node in &after.nodes {
+        match old_nodes.get(node.id.as_str()) {
+            None => result.nodes_added.push(node.id.clone()),
+            Some(old) => {
+                let changes = node_changes(old, node);
+                if !changes.is_empty() {
+                    result.nodes_changed.push(NodeChange { id: node.id.clone(), changes });
+                }
+            }
+        }
+    }
+    result.nodes_removed = before.nodes.iter().filter(|n| !new_nodes.contains_key(n.id.as_str())).map(|n| n.id.clone()).collect();
+
+    let old_edges: HashMap<&str, &WorkspaceEdge> = before.edges.iter().map(|e| (e.id.as_str(), e)).collect();
+    let new_edges: HashMap<&str, &WorkspaceEdge> = after.edges.iter().map(|e| (e.id.as_str(), e)).collect();
+    result.edges_added = after
+        .edges
+        .iter()
+        .filter(|e| !old_edges.get(e.id.as_str()).is_some_and(|old| same_ends(old, e)))
+        .cloned()
+        .collect();
+    result.edges_removed = before
+        .edges
+        .iter()
+        .filter(|e| !new_edges.get(e.id.as_str()).is_some_and(|new| same_ends(new, e)))
+        .cloned()
+        .collect();
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff() {
+        let before: Workspace = serde_json::from_value(json!({
+            "name": "ws-1",
+            "nodes": [
+                {"id": "n1", "type": "fixedValue", "position": {"x": 0, "y": 0}, "data": {"value": 1, "label": "A"}},
+                {"id": "n2", "type": "add", "data": {}},
+                {"id": "n3", "type": "add", "data": {}}
+            ],
+            "edges": [
+                {"id": "e1", "source": "n1", "sourceHandle": "out_0", "target": "n2", "targetHandle": "in_0"},
+                {"id": "e2", "source": "n1", "sourceHandle": "out_0", "target": "n3", "targetHandle": "in_0"}
+            ]
+        }))
+        .unwrap();
+        let after: Workspace = serde_json::from_value(json!({
+            "name": "ws-1",
+            "nodes": [
+                {"id": "n1", "type": "fixedValue", "position": {"x": 50, "y": 10}, "data": {"value": 2, "note": "x"}},
+                {"id": "n2", "type": "add", "data": {}},
+                {"id": "n4", "type": "subtract", "data": {}}
+            ],
+            "edges": [
+                {"id": "e1", "source": "n1", "sourceHandle": "out_0", "target": "n2", "targetHandle": "in_1"},
+                {"id": "e3", "source": "n2", "target": "n4"}
+            ]
+        }))
+        .unwrap();
+
+        let result = diff(&before, &after);
+        assert_eq!(result.nodes_added, vec!["n4"]);
+        assert_eq!(result.nodes_removed, vec!["n3"]);
+        assert_eq!(result.nodes_changed.len(), 1);
+        let fields: Vec<_> = result.nodes_changed[0].changes.iter().map(|c| (c.field.as_str(), c.before.clone(), c.after.clone())).collect();
+        assert_eq!(fields, vec![
+            ("label", Some(json!("A")), None),
+            ("note", None, Some(json!("x"))),
+            ("value", Some(json!(1)), Some(json!(2))),
+        ]);
+
+        // e1 mudou de entrada: sai e entra de novo
+        let ids = |edges: &[WorkspaceEdge]| edges.iter().map(|e| e.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&result.edges_added), vec!["e1", "e3"]);
+        assert_eq!(ids(&result.edges_removed), vec!["e1", "e2"]);
+
+        assert_eq!(diff(&after, &after), WorkspaceDiff::default());
+    }
+}
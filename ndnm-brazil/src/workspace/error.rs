@@ -0,0 +1,43 @@
+// ndnm-brazil/src/workspace/error.rs
+//
+// Erros ao ler/converter um workspace salvo (os handlers HTTP escolhem o status a partir da variante)
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorkspaceError {
+    /// Não existe workspaces/<nome>.json
+    NotFound(String),
+    /// Já existe um workspace com esse nome (rename/duplicate não sobrescrevem)
+    AlreadyExists(String),
+    /// Nome que não dá pra usar como nome de arquivo (ex: `../x`, `.trash`)
+    InvalidName { name: String, reason: String },
+    /// Save feito em cima de uma revisão velha: outro editor salvou antes
+    Conflict { name: String, expected: u64, current: u64 },
+    /// Falha lendo ou gravando o arquivo
+    Io(String),
+    /// JSON quebrado, versão desconhecida ou grafo que não dá pra executar
+    Invalid(String),
+    /// Save recusado: problemas encontrados pelo Workspace::validate
+    Validation(Vec<String>),
+}
+
+impl fmt::Display for WorkspaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(name) => write!(f, "Workspace não encontrado: {}", name),
+            Self::AlreadyExists(name) => write!(f, "Já existe um workspace chamado {}", name),
+            Self::InvalidName { name, reason } => write!(f, "Nome de workspace inválido '{}': {}", name, reason),
+            Self::Conflict { name, expected, current } => write!(
+                f,
+                "Workspace {} foi salvo por outro editor (revisão {}, esperada {}): recarregue antes de salvar",
+                name, current, expected
+            ),
+            Self::Io(message) => write!(f, "Erro de disco no workspace: {}", message),
+            Self::Invalid(message) => write!(f, "Workspace inválido: {}", message),
+            Self::Validation(issues) => write!(f, "Workspace inválido: {}", issues.join("; ")),
+        }
+    }
+}
+
+impl std::error::Error for WorkspaceError {}
@@ -0,0 +1,393 @@
+// ndnm-brazil/src/workspace/graph.rs
+//
+// Workspace salvo (formato React Flow do editor) → WorkflowGraph executável
+// - nodes[].type vira node_type; a porta vem do discovery (config.yaml de cada node)
+// - edges[].sourceHandle/targetHandle viram índices: "out_0"/"in_1", e nos nodes com sections
+//   os handles dos templates ("copy_input_0") ou o nome de um slot fixo, numerados por node
+//   (cada seção depois da anterior) e enviados pro node em data.handles
+// - campos que só servem pro editor (label, inputsCount, input_fields, ...) não vão pro /run do node
+
+use super::schema::{Workspace, WorkspaceEdge, WorkspaceNode};
+use crate::execution::{Connection, GraphNode, WorkflowGraph, PLAY_NODE_TYPES};
+use ndnm_core::{ExecutionPolicyConfig, Section, SectionBehavior, SlotDefinition};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+
+/// Campos do `data` que são só do editor
+const EDITOR_ONLY_FIELDS: &[&str] = &["label", "inputsCount", "inputsMode", "outputsCount", "outputsMode", "input_fields"];
+
+/// Campo do `data` que leva pro node com sections o índice de cada handle ligado
+/// (`{"handles": {"inputs": {"copy_input_0": 0, "internal_input_0": 1}, "outputs": {...}}}`):
+/// é a posição do valor em `variables` (entradas) e em `outputs` (saídas).
+pub const SECTION_HANDLES_FIELD: &str = "handles";
+
+/// Handles de um lado (entradas ou saídas) declarados nas sections do config.yaml
+#[derive(Debug, Clone, Default)]
+pub struct HandleSlots {
+    /// Uma entrada por seção que tem slots desse lado, na ordem do config.yaml
+    sections: Vec<SectionSlots>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct SectionSlots {
+    /// Nome base do template (handle = "<base>_<N>")
+    template: Option<String>,
+    /// Slots fixos (handle = nome do slot)
+    fixed: Vec<String>,
+    /// `dynamic_per_file`: o template também aparece como "<base>_<arquivo>"
+    per_file: bool,
+}
+
+impl HandleSlots {
+    fn from_sections(sections: &[Section], side: fn(&ndnm_core::SlotTemplate) -> Option<&SlotDefinition>) -> Self {
+        let sections = sections
+            .iter()
+            .map(|section| SectionSlots {
+                template: section.slot_template.as_ref().and_then(side).map(|slot| slot.name.clone()),
+                fixed: section.slots.iter().filter_map(side).map(|slot| slot.name.clone()).collect(),
+                per_file: section.behavior == SectionBehavior::DynamicPerFile,
+            })
+            .filter(|section| section.template.is_some() || !section.fixed.is_empty())
+            .collect();
+        Self { sections }
+    }
+
+    /// Onde um handle cai: (seção, 0 = slot fixo / 1 = template, posição no fixo ou N do template)
+    fn key(&self, handle: &str) -> Option<(usize, usize, usize)> {
+        self.sections.iter().enumerate().find_map(|(i, section)| {
+            if let Some(position) = section.fixed.iter().position(|name| name == handle) {
+                return Some((i, 0, position));
+            }
+            let (base, n) = handle.rsplit_once('_')?;
+            if section.template.as_deref() != Some(base) {
+                return None;
+            }
+            Some((i, 1, n.parse().ok()?))
+        })
+    }
+
+    /// Índices dos handles ligados num node. Cada seção começa onde a anterior terminou
+    /// (slots fixos primeiro, depois os templates em ordem de N), então handles de seções
+    /// diferentes nunca dividem um índice. Handle que não é de nenhuma seção fica de fora.
+    pub fn layout<'h>(&self, handles: impl IntoIterator<Item = &'h str>) -> HashMap<String, usize> {
+        let keys: BTreeMap<(usize, usize, usize), &str> =
+            handles.into_iter().filter_map(|handle| Some((self.key(handle)?, handle))).collect();
+        keys.into_values().enumerate().map(|(index, handle)| (handle.to_string(), index)).collect()
+    }
+
+    /// Índice de um handle "<plain>_N" (in_0/out_0): só no lado sem sections
+    pub fn plain_index(&self, handle: &str, plain: &str) -> Option<usize> {
+        if !self.sections.is_empty() {
+            return None;
+        }
+        let (base, index) = handle.rsplit_once('_')?;
+        if base != plain {
+            return None;
+        }
+        index.parse().ok()
+    }
+
+    /// Handle por arquivo de uma seção `dynamic_per_file` ("internal_input_readme.txt"):
+    /// o editor cria e o save aceita, mas a execução ainda não sabe numerar
+    pub fn per_file(&self, handle: &str) -> bool {
+        self.key(handle).is_none()
+            && self.sections.iter().filter(|section| section.per_file).any(|section| {
+                section
+                    .template
+                    .as_deref()
+                    .and_then(|base| handle.strip_prefix(base)?.strip_prefix('_'))
+                    .is_some_and(|file| !file.is_empty())
+            })
+    }
+
+    /// O handle existe nesse lado do node
+    pub fn accepts(&self, handle: &str, plain: &str) -> bool {
+        self.key(handle).is_some() || self.plain_index(handle, plain).is_some() || self.per_file(handle)
+    }
+
+    /// Índice de um handle: "<plain>_N" no lado sem sections; nos outros, o do `layout` do node
+    pub fn index(&self, handle: &str, plain: &str, layout: &HashMap<String, usize>) -> Option<usize> {
+        match self.sections.is_empty() {
+            true => self.plain_index(handle, plain),
+            false => layout.get(handle).copied(),
+        }
+    }
+}
+
+/// O que a conversão precisa saber de cada tipo de node (vem do discovery)
+#[derive(Debug, Clone, Default)]
+pub struct NodeSpec {
+    pub port: u16,
+    pub label: String,
+    pub inputs: HandleSlots,
+    pub outputs: HandleSlots,
+    /// Saídas out_N do node quando o editor não diz (`initial_outputs_count` do config.yaml)
+    pub outputs_count: Option<usize>,
+}
+
+impl NodeSpec {
+    pub fn new(port: u16, label: &str, sections: &[Section]) -> Self {
+        Self {
+            port,
+            label: label.to_string(),
+            inputs: HandleSlots::from_sections(sections, |t| t.input.as_ref()),
+            outputs: HandleSlots::from_sections(sections, |t| t.output.as_ref()),
+            outputs_count: None,
+        }
+    }
+
+    pub fn with_outputs_count(mut self, count: usize) -> Self {
+        self.outputs_count = Some(count);
+        self
+    }
+}
+
+/// Converte o workspace do editor. `specs` = tipo de node → spec do discovery.
+/// Play sem node rodando (sem spec) entra com porta 0: o engine nunca chama o /run de um Play.
+pub fn to_workflow_graph(workspace: &Workspace, specs: &HashMap<String, NodeSpec>) -> Result<WorkflowGraph, String> {
+    // Handles ligados em cada node (nos nodes com sections o índice depende de quais estão ligados)
+    let mut input_handles: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut output_handles: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in &workspace.edges {
+        if let Some(handle) = &edge.source_handle {
+            output_handles.entry(&edge.source).or_default().push(handle);
+        }
+        if let Some(handle) = &edge.target_handle {
+            input_handles.entry(&edge.target).or_default().push(handle);
+        }
+    }
+
+    let play_spec = NodeSpec::default();
+    let mut layouts: HashMap<&str, (&NodeSpec, NodeHandles)> = HashMap::new();
+    let mut nodes = Vec::with_capacity(workspace.nodes.len());
+
+    for node in &workspace.nodes {
+        let spec = match specs.get(&node.node_type) {
+            Some(spec) => spec,
+            None if PLAY_NODE_TYPES.contains(&node.node_type.as_str()) => &play_spec,
+            None => {
+                return Err(format!("Node {}: tipo '{}' não encontrado no discovery", node.id, node.node_type))
+            }
+        };
+        let handles = NodeHandles {
+            inputs: spec.inputs.layout(input_handles.get(node.id.as_str()).into_iter().flatten().copied()),
+            outputs: spec.outputs.layout(output_handles.get(node.id.as_str()).into_iter().flatten().copied()),
+        };
+        nodes.push(graph_node(node, spec, &handles)?);
+        layouts.insert(&node.id, (spec, handles));
+    }
+
+    let layout_of = |edge: &WorkspaceEdge, node_id: &str| {
+        layouts
+            .get(node_id)
+            .ok_or_else(|| format!("Conexão {}: node {} não existe", edge.id, node_id))
+    };
+    let index_of = |edge: &WorkspaceEdge, handle: &Option<String>, slots: &HandleSlots, layout: &HashMap<String, usize>, plain: &str| {
+        match handle {
+            None => Ok(0),
+            Some(handle) => slots.index(handle, plain, layout).ok_or_else(|| match slots.per_file(handle) {
+                true => format!("Conexão {}: handle por arquivo '{}' ainda não é suportado na execução", edge.id, handle),
+                false => format!("Conexão {}: handle '{}' não reconhecido", edge.id, handle),
+            }),
+        }
+    };
+
+    let connections = workspace
+        .edges
+        .iter()
+        .map(|edge| {
+            let (source, source_handles) = layout_of(edge, &edge.source)?;
+            let (target, target_handles) = layout_of(edge, &edge.target)?;
+            Ok(Connection {
+                from_node_id: edge.source.clone(),
+                from_output_index: index_of(edge, &edge.source_handle, &source.outputs, &source_handles.outputs, "out")?,
+                to_node_id: edge.target.clone(),
+                to_input_index: index_of(edge, &edge.target_handle, &target.inputs, &target_handles.inputs, "in")?,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(WorkflowGraph { nodes, connections })
+}
+
+/// Índices dos handles de section ligados num node (vão pro /run em `SECTION_HANDLES_FIELD`)
+#[derive(Debug, Default, Serialize)]
+struct NodeHandles {
+    inputs: HashMap<String, usize>,
+    outputs: HashMap<String, usize>,
+}
+
+fn graph_node(node: &WorkspaceNode, spec: &NodeSpec, handles: &NodeHandles) -> Result<GraphNode, String> {
+    let mut data = node.data.clone();
+    let label = match data.get("label").and_then(Value::as_str) {
+        Some(label) => label.to_string(),
+        None if !spec.label.is_empty() => spec.label.clone(),
+        None => node.node_type.clone(),
+    };
+    for field in EDITOR_ONLY_FIELDS {
+        data.remove(*field);
+    }
+
+    // Timeout/retries do node no editor (sobrescreve o `execution:` do config.yaml)
+    let execution: ExecutionPolicyConfig = match data.remove("execution") {
+        Some(execution) => serde_json::from_value(execution)
+            .map_err(|e| format!("Node {}: 'execution' inválido: {}", node.id, e))?,
+        None => Default::default(),
+    };
+
+    if !handles.inputs.is_empty() || !handles.outputs.is_empty() {
+        let handles = serde_json::to_value(handles).map_err(|e| format!("Node {}: {}", node.id, e))?;
+        data.insert(SECTION_HANDLES_FIELD.to_string(), handles);
+    }
+
+    Ok(GraphNode {
+        id: node.id.clone(),
+        node_type: node.node_type.clone(),
+        port: spec.port,
+        label,
+        data: data.into_iter().collect(),
+        execution,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn specs() -> HashMap<String, NodeSpec> {
+        let sections: Vec<Section> = serde_json::from_value(json!([
+            {
+                "section_name": "copy_here",
+                "behavior": "auto_increment",
+                "slot_template": {
+                    "input": {"name": "copy_input", "type": "FILE_CONTENT", "connections": 1},
+                    "output": {"name": "copied_output", "type": "FILE_CONTENT", "connections": "n"}
+                }
+            },
+            {
+                "section_name": "internal_files",
+                "behavior": "dynamic_per_file",
+                "slot_template": {"input": {"name": "internal_input", "type": "FILE_CONTENT", "connections": 1}}
+            },
+            {
+                "section_name": "controls",
+                "behavior": "fixed",
+                "slots": [
+                    {"input": {"name": "target_directory", "type": "STRING", "connections": 1}},
+                    {"input": {"name": "pattern", "type": "STRING", "connections": 1}}
+                ]
+            }
+        ]))
+        .unwrap();
+
+        HashMap::from([
+            ("fixedValue".to_string(), NodeSpec::new(3010, "🔢 Valor Fixo", &[])),
+            ("add".to_string(), NodeSpec::new(3000, "➕ Somar", &[])),
+            ("filesystem".to_string(), NodeSpec::new(3011, "📂 Arquivos", &sections)),
+        ])
+    }
+
+    fn workspace(value: Value) -> Workspace {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_converts_editor_workspace() {
+        // Mesmo formato do workspaces/workspace-1.json, com um Play no fim
+        let editor = workspace(json!({
+            "name": "workspace-1",
+            "nodes": [
+                {"id": "n1", "type": "fixedValue", "position": {"x": 0, "y": 0},
+                 "data": {"label": "🔢 Valor Fixo", "inputsCount": 1, "outputsMode": "n", "input_fields": [], "value": "123"}},
+                {"id": "n2", "type": "add", "data": {"inputsCount": 2, "value": null, "execution": {"timeout_ms": 500}}},
+                {"id": "play-1", "type": "playButton", "data": {"label": "▶️ Play"}}
+            ],
+            "edges": [
+                {"id": "e1", "source": "n1", "sourceHandle": "out_0", "target": "n2", "targetHandle": "in_1"},
+                {"id": "e2", "source": "n2", "target": "play-1"}
+            ]
+        }));
+
+        let graph = to_workflow_graph(&editor, &specs()).unwrap();
+        let n1 = &graph.nodes[0];
+        assert_eq!((n1.node_type.as_str(), n1.port, n1.label.as_str()), ("fixedValue", 3010, "🔢 Valor Fixo"));
+        assert_eq!(n1.data, HashMap::from([("value".to_string(), json!("123"))]));
+
+        let n2 = &graph.nodes[1];
+        assert_eq!(n2.label, "➕ Somar");
+        assert_eq!(n2.execution.timeout_ms, Some(500));
+        assert!(!n2.data.contains_key("execution"));
+        assert!(graph.nodes[2].is_play());
+
+        let conn = &graph.connections[0];
+        assert_eq!((conn.from_output_index, conn.to_input_index), (0, 1));
+        assert_eq!((graph.connections[1].from_output_index, graph.connections[1].to_input_index), (0, 0));
+    }
+
+    #[test]
+    fn test_section_handles_and_errors() {
+        let edge = |source_handle: &str, target_handle: &str| {
+            workspace(json!({
+                "nodes": [
+                    {"id": "fs-1", "type": "filesystem", "data": {}},
+                    {"id": "fs-2", "type": "filesystem", "data": {}}
+                ],
+                "edges": [{"id": "e1", "source": "fs-1", "sourceHandle": source_handle,
+                           "target": "fs-2", "targetHandle": target_handle}]
+            }))
+        };
+
+        // Só um handle ligado de cada lado: os dois viram o índice 0 do node
+        let graph = to_workflow_graph(&edge("copied_output_1", "copy_input_2"), &specs()).unwrap();
+        assert_eq!((graph.connections[0].from_output_index, graph.connections[0].to_input_index), (0, 0));
+        assert_eq!(graph.nodes[1].data[SECTION_HANDLES_FIELD], json!({"inputs": {"copy_input_2": 0}, "outputs": {}}));
+
+        // Handle por arquivo não tem índice; handle de entrada usado como saída também não vale
+        let per_file = to_workflow_graph(&edge("copied_output_0", "internal_input_readme.txt"), &specs()).unwrap_err();
+        assert!(per_file.contains("ainda não é suportado"), "{}", per_file);
+        assert!(to_workflow_graph(&edge("copy_input_0", "in_0"), &specs()).is_err());
+
+        let unknown = workspace(json!({"nodes": [{"id": "x", "type": "ksampler", "data": {}}]}));
+        assert!(to_workflow_graph(&unknown, &specs()).unwrap_err().contains("ksampler"));
+
+        let dangling = workspace(json!({
+            "nodes": [{"id": "n1", "type": "add", "data": {}}],
+            "edges": [{"id": "e1", "source": "n1", "target": "gone"}]
+        }));
+        assert!(to_workflow_graph(&dangling, &specs()).unwrap_err().contains("gone"));
+    }
+
+    #[test]
+    fn test_template_sections_wired_at_once() {
+        let editor = workspace(json!({
+            "nodes": [
+                {"id": "n1", "type": "fixedValue", "data": {}},
+                {"id": "fs-1", "type": "filesystem", "data": {"target_directory": "/tmp"}},
+                {"id": "fs-2", "type": "filesystem", "data": {}}
+            ],
+            "edges": [
+                {"id": "e1", "source": "n1", "sourceHandle": "out_0", "target": "fs-2", "targetHandle": "internal_input_0"},
+                {"id": "e2", "source": "fs-1", "sourceHandle": "copied_output_0", "target": "fs-2", "targetHandle": "copy_input_0"},
+                {"id": "e3", "source": "n1", "sourceHandle": "out_0", "target": "fs-2", "targetHandle": "pattern"},
+                {"id": "e4", "source": "fs-1", "sourceHandle": "copied_output_3", "target": "fs-2", "targetHandle": "copy_input_1"}
+            ]
+        }));
+
+        let graph = to_workflow_graph(&editor, &specs()).unwrap();
+        // Seção a seção, na ordem do config.yaml: copy_here (e2, e4), internal_files (e1), controls (e3)
+        let inputs: Vec<usize> = graph.connections.iter().map(|c| c.to_input_index).collect();
+        assert_eq!(inputs, vec![2, 0, 3, 1]);
+        let outputs: Vec<usize> = graph.connections.iter().map(|c| c.from_output_index).collect();
+        assert_eq!(outputs, vec![0, 0, 0, 1]);
+
+        // O node recebe o mapa handle → índice de cada lado
+        assert_eq!(graph.nodes[2].data[SECTION_HANDLES_FIELD], json!({
+            "inputs": {"copy_input_0": 0, "copy_input_1": 1, "internal_input_0": 2, "pattern": 3},
+            "outputs": {}
+        }));
+        assert_eq!(graph.nodes[1].data[SECTION_HANDLES_FIELD]["outputs"], json!({"copied_output_0": 0, "copied_output_3": 1}));
+        assert!(!graph.nodes[0].data.contains_key(SECTION_HANDLES_FIELD));
+    }
+}
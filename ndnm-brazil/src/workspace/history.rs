@@ -0,0 +1,203 @@
+// ndnm-brazil/src/workspace/history.rs
+//
+// Histórico de versões dos workspaces salvos
+// - cada save grava uma cópia em workspaces/.history/<nome>/<versão>.json (1, 2, 3, ...)
+// - só as `keep` versões mais recentes ficam (retention.max_workspace_versions no config.yaml)
+// - arquivo salvo antes do histórico existir vira a versão 1 no primeiro save (nada se perde)
+// - restore = salvar a versão escolhida como atual (vira uma versão nova, então dá pra desfazer)
+// - save confere a revisão: quem salva em cima de uma revisão velha recebe Conflict
+
+use super::{check_name, current_revision, file_path, store, write, write_atomic, Workspace, WorkspaceError};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Pasta do histórico, dentro da pasta dos workspaces
+pub const HISTORY_DIR: &str = ".history";
+
+/// Versões guardadas por workspace quando o config.yaml não diz
+pub const DEFAULT_MAX_VERSIONS: usize = 20;
+
+/// Uma versão na listagem (GET /workspaces/:name/versions)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VersionInfo {
+    pub version: u32,
+    pub saved_at: String,
+    pub node_count: usize,
+}
+
+fn history_dir(dir: &Path, name: &str) -> Result<PathBuf, WorkspaceError> {
+    check_name(name)?;
+    Ok(dir.join(HISTORY_DIR).join(name))
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> WorkspaceError {
+    WorkspaceError::Io(format!("{}: {}", path.display(), e))
+}
+
+/// Números das versões guardadas, da mais antiga pra mais nova
+fn versions(dir: &Path, name: &str) -> Vec<u32> {
+    let Some(entries) = history_dir(dir, name).ok().and_then(|history| fs::read_dir(history).ok()) else {
+        return Vec::new();
+    };
+    let mut versions: Vec<u32> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.path().file_stem()?.to_str()?.parse().ok())
+        .collect();
+    versions.sort_unstable();
+    versions
+}
+
+/// Versões do workspace, da mais nova pra mais antiga
+pub fn list(dir: &Path, name: &str) -> Vec<VersionInfo> {
+    let Ok(history) = history_dir(dir, name) else { return Vec::new() };
+    versions(dir, name)
+        .into_iter()
+        .rev()
+        .filter_map(|version| {
+            let path = history.join(format!("{}.json", version));
+            let value: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+            let saved_at = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .map(|at| DateTime::<Utc>::from(at).to_rfc3339())
+                .unwrap_or_default();
+            Some(VersionInfo { version, saved_at, node_count: value["nodes"].as_array().map_or(0, Vec::len) })
+        })
+        .collect()
+}
+
+/// Lê uma versão guardada (migrando se for antiga)
+pub fn read(dir: &Path, name: &str, version: u32) -> Result<Workspace, WorkspaceError> {
+    let mut workspace = super::read(&history_dir(dir, name)?, &version.to_string()).map_err(|e| match e {
+        WorkspaceError::NotFound(_) => WorkspaceError::NotFound(format!("{} (versão {})", name, version)),
+        e => e,
+    })?;
+    workspace.name = name.to_string();
+    Ok(workspace)
+}
+
+/// Guarda `workspace` como versão nova e devolve o número dela
+pub fn record(dir: &Path, workspace: &Workspace) -> Result<u32, WorkspaceError> {
+    let history = history_dir(dir, &workspace.name)?;
+    fs::create_dir_all(&history).map_err(|e| io_error(&history, e))?;
+    let version = versions(dir, &workspace.name).last().map_or(1, |last| last + 1);
+    let content = serde_json::to_string_pretty(workspace).map_err(|e| WorkspaceError::Io(e.to_string()))?;
+    write_atomic(&history.join(format!("{}.json", version)), &content)?;
+    Ok(version)
+}
+
+/// Apaga as versões mais antigas até sobrarem `keep` (pelo menos 1)
+pub fn prune(dir: &Path, name: &str, keep: usize) {
+    let Ok(history) = history_dir(dir, name) else { return };
+    let versions = versions(dir, name);
+    let excess = versions.len().saturating_sub(keep.max(1));
+    for version in &versions[..excess] {
+        let _ = fs::remove_file(history.join(format!("{}.json", version)));
+    }
+}
+
+/// Grava o workspace como atual e guarda a versão. Devolve o número da versão.
+/// `workspace.revision` tem que ser a do arquivo salvo agora; sai daqui já com a revisão nova.
+pub fn save(dir: &Path, workspace: &mut Workspace, keep: usize) -> Result<u32, WorkspaceError> {
+    match current_revision(dir, &workspace.name)? {
+        Some(current) if current != workspace.revision => {
+            return Err(WorkspaceError::Conflict { name: workspace.name.clone(), expected: workspace.revision, current });
+        }
+        Some(current) => workspace.revision = current + 1,
+        None => workspace.revision += 1,
+    }
+
+    // Primeiro save com histórico: o arquivo que vai ser sobrescrito vira a versão 1
+    let current = file_path(dir, &workspace.name)?;
+    if versions(dir, &workspace.name).is_empty() && current.is_file() {
+        let history = history_dir(dir, &workspace.name)?;
+        fs::create_dir_all(&history).map_err(|e| io_error(&history, e))?;
+        fs::copy(&current, history.join("1.json")).map_err(|e| io_error(&current, e))?;
+    }
+
+    write(dir, workspace)?;
+    let version = record(dir, workspace)?;
+    prune(dir, &workspace.name, keep);
+    Ok(version)
+}
+
+/// Histórico acompanha o rename (um histórico velho com o nome novo vai pra lixeira antes)
+pub fn rename(dir: &Path, from: &str, to: &str) -> Result<(), WorkspaceError> {
+    let source = history_dir(dir, from)?;
+    if !source.is_dir() {
+        return Ok(());
+    }
+    trash(dir, to)?;
+    let target = history_dir(dir, to)?;
+    fs::rename(&source, &target).map_err(|e| io_error(&source, e))
+}
+
+/// Move o histórico pra lixeira (junto com o workspace apagado)
+pub fn trash(dir: &Path, name: &str) -> Result<Option<PathBuf>, WorkspaceError> {
+    let source = history_dir(dir, name)?;
+    if !source.is_dir() {
+        return Ok(None);
+    }
+    let trash = dir.join(store::TRASH_DIR);
+    fs::create_dir_all(&trash).map_err(|e| io_error(&trash, e))?;
+    let target = trash.join(format!("{}_{}_history", name, store::trash_stamp()));
+    fs::rename(&source, &target).map_err(|e| io_error(&source, e))?;
+    Ok(Some(target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn workspace(value: i64) -> Workspace {
+        serde_json::from_value(json!({
+            "schema_version": 1,
+            "name": "ws-1",
+            "nodes": [{"id": "n1", "type": "fixedValue", "data": {"value": value}}]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_save_keeps_bounded_versions() {
+        let dir = std::env::temp_dir().join("ndnm_test_workspace_history");
+        let _ = fs::remove_dir_all(&dir);
+
+        // Arquivo de antes do histórico vira a versão 1
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("ws-1.json"), json!({"name": "ws-1", "nodes": []}).to_string()).unwrap();
+        let mut saved = workspace(1);
+        assert_eq!(save(&dir, &mut saved, 3).unwrap(), 2);
+        assert_eq!(saved.revision, 1);
+        assert_eq!(read(&dir, "ws-1", 1).unwrap().nodes.len(), 0);
+
+        for value in 2..=4 {
+            let mut next = workspace(value);
+            next.revision = saved.revision;
+            save(&dir, &mut next, 3).unwrap();
+            saved = next;
+        }
+        let listed: Vec<u32> = list(&dir, "ws-1").iter().map(|v| v.version).collect();
+        assert_eq!(listed, vec![5, 4, 3]);
+        assert_eq!(read(&dir, "ws-1", 5).unwrap(), saved);
+
+        // Save em cima de revisão velha: nada muda
+        let mut stale = workspace(9);
+        stale.revision = 2;
+        let conflict = WorkspaceError::Conflict { name: "ws-1".to_string(), expected: 2, current: 4 };
+        assert_eq!(save(&dir, &mut stale, 3).unwrap_err(), conflict);
+        assert_eq!(list(&dir, "ws-1").len(), 3);
+        assert_eq!(read(&dir, "ws-1", 1).unwrap_err(), WorkspaceError::NotFound("ws-1 (versão 1)".to_string()));
+
+        rename(&dir, "ws-1", "ws-2").unwrap();
+        assert!(list(&dir, "ws-1").is_empty());
+        assert_eq!(read(&dir, "ws-2", 5).unwrap().name, "ws-2");
+
+        assert!(trash(&dir, "ws-2").unwrap().is_some_and(|p| p.is_dir()));
+        assert!(list(&dir, "ws-2").is_empty());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
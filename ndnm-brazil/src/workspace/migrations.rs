@@ -0,0 +1,100 @@
+// ndnm-brazil/src/workspace/migrations.rs
+//
+// Cadeia de migrações do workspace salvo
+// Cada migração leva o JSON da versão N pra N+1 (N = posição na lista). Arquivo sem
+// `schema_version` é versão 0 (tudo que foi salvo antes do schema tipado, ex: workspace-1.json).
+// Formato novo = migração nova no fim da lista + CURRENT_SCHEMA_VERSION + 1.
+
+use super::schema::CURRENT_SCHEMA_VERSION;
+use serde_json::{json, Value};
+
+type Migration = fn(&mut Value);
+
+const MIGRATIONS: &[Migration] = &[v0_to_v1];
+
+/// Versão gravada no JSON (0 = sem `schema_version`)
+pub fn version_of(value: &Value) -> u32 {
+    value.get("schema_version").and_then(Value::as_u64).unwrap_or(0) as u32
+}
+
+/// Leva o JSON até a versão atual. Devolve o JSON migrado e a versão de onde ele veio.
+pub fn migrate(mut value: Value) -> Result<(Value, u32), String> {
+    if !value.is_object() {
+        return Err("workspace precisa ser um objeto JSON".to_string());
+    }
+    let from = version_of(&value);
+    if from > CURRENT_SCHEMA_VERSION {
+        return Err(format!(
+            "schema_version {} é mais novo que o deste Brazil ({})",
+            from, CURRENT_SCHEMA_VERSION
+        ));
+    }
+
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(from as usize) {
+        migration(&mut value);
+        value["schema_version"] = json!(version + 1);
+    }
+    Ok((value, from))
+}
+
+/// v0 → v1
+/// - inputsMode/outputsMode viram string, igual ao default_data do discovery ("1", "n");
+///   o editor antigo gravava número às vezes (workspace-1.json: `"inputsMode": 0`)
+/// - edge sem id ganha um (o diff de versões e o editor identificam edges pelo id)
+fn v0_to_v1(value: &mut Value) {
+    if let Some(nodes) = value.get_mut("nodes").and_then(Value::as_array_mut) {
+        for data in nodes.iter_mut().filter_map(|n| n.get_mut("data")).filter_map(Value::as_object_mut) {
+            for field in ["inputsMode", "outputsMode"] {
+                if let Some(Value::Number(n)) = data.get(field) {
+                    let mode = n.to_string();
+                    data.insert(field.to_string(), Value::String(mode));
+                }
+            }
+        }
+    }
+
+    if let Some(edges) = value.get_mut("edges").and_then(Value::as_array_mut) {
+        for (index, edge) in edges.iter_mut().enumerate() {
+            let Some(edge) = edge.as_object_mut() else { continue };
+            if edge.get("id").and_then(Value::as_str).is_some_and(|id| !id.is_empty()) {
+                continue;
+            }
+            let end = |key: &str| edge.get(key).and_then(Value::as_str).unwrap_or_default().to_string();
+            let id = format!("e-{}-{}-{}", end("source"), end("target"), index);
+            edge.insert("id".to_string(), Value::String(id));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace::schema::Workspace;
+
+    #[test]
+    fn test_migrates_workspace_1() {
+        let original: Value = serde_json::from_str(include_str!("../../../workspaces/workspace-1.json")).unwrap();
+        assert_eq!(version_of(&original), 0);
+
+        let (migrated, from) = migrate(original).unwrap();
+        assert_eq!((from, version_of(&migrated)), (0, CURRENT_SCHEMA_VERSION));
+
+        let workspace: Workspace = serde_json::from_value(migrated.clone()).unwrap();
+        assert_eq!(workspace.name, "workspace-1");
+        assert_eq!(workspace.nodes[0].data["inputsMode"], json!("0"));
+        assert_eq!(workspace.nodes[1].data["outputsMode"], json!("1"));
+        assert_eq!(workspace.edges[0].id, "e-n1-n2-1761121908973");
+
+        // Já na versão atual: nada muda
+        assert_eq!(migrate(migrated.clone()).unwrap(), (migrated, CURRENT_SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn test_edge_ids_and_newer_schema() {
+        let (migrated, _) = migrate(json!({"edges": [{"source": "a", "target": "b"}]})).unwrap();
+        assert_eq!(migrated["edges"][0]["id"], json!("e-a-b-0"));
+
+        assert!(migrate(json!({"schema_version": CURRENT_SCHEMA_VERSION + 1})).is_err());
+        assert!(migrate(json!([])).is_err());
+    }
+}
@@ -0,0 +1,176 @@
+// ndnm-brazil/src/workspace/mod.rs
+//
+// Workspaces salvos pelo editor (workspaces/<nome>.json)
+// - schema.rs: modelo tipado (Workspace) + validação do save
+// - migrations.rs: arquivos antigos sobem pra versão atual no load
+// - graph.rs: conversão pro WorkflowGraph (o Brazil executa direto do workspace salvo)
+// - store.rs: listar, renomear, duplicar e apagar (com lixeira)
+// - history.rs + diff.rs: versões guardadas a cada save, diff entre versões e restore
+//
+// O nome vem do usuário e vira nome de arquivo: todo caminho passa por file_path (check_name),
+// e toda gravação por write_atomic (arquivo temporário + rename: nunca fica JSON pela metade).
+
+pub mod diff;
+pub mod error;
+pub mod graph;
+pub mod history;
+pub mod migrations;
+pub mod schema;
+pub mod store;
+
+pub use error::WorkspaceError;
+pub use graph::NodeSpec;
+pub use schema::{Workspace, CURRENT_SCHEMA_VERSION};
+
+use crate::execution::WorkflowGraph;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Tamanho máximo do nome (em caracteres)
+const MAX_NAME_LEN: usize = 100;
+
+/// Nomes que o Windows não deixa criar como arquivo
+const RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Nome de workspace aceito: letras, números, espaço, `-`, `_` e `.`; sem começar/terminar com
+/// ponto ou espaço (isso barra `..`, `.trash`, `.history` e os arquivos temporários)
+pub fn check_name(name: &str) -> Result<(), WorkspaceError> {
+    let invalid = |reason: &str| Err(WorkspaceError::InvalidName { name: name.to_string(), reason: reason.to_string() });
+
+    if name.trim().is_empty() {
+        return invalid("nome vazio");
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return invalid("mais de 100 caracteres");
+    }
+    if let Some(c) = name.chars().find(|c| !(c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.'))) {
+        return invalid(&format!("caractere '{}' não permitido (use letras, números, espaço, - _ .)", c));
+    }
+    if name.starts_with(['.', ' ']) || name.ends_with(['.', ' ']) {
+        return invalid("não pode começar nem terminar com ponto ou espaço");
+    }
+    let base = name.split('.').next().unwrap_or_default();
+    if RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(base)) {
+        return invalid("nome reservado do sistema");
+    }
+    Ok(())
+}
+
+/// `<dir>/<nome>.json`, se o nome for válido
+pub fn file_path(dir: &Path, name: &str) -> Result<PathBuf, WorkspaceError> {
+    check_name(name)?;
+    Ok(dir.join(format!("{}.json", name)))
+}
+
+/// Grava num temporário do lado (`.<arquivo>.tmp`) e troca pelo definitivo com rename
+pub(crate) fn write_atomic(path: &Path, content: &str) -> Result<(), WorkspaceError> {
+    let io_error = |e: std::io::Error| WorkspaceError::Io(format!("{}: {}", path.display(), e));
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("workspace.json");
+    let tmp = path.with_file_name(format!(".{}.tmp", file_name));
+
+    let written = fs::File::create(&tmp)
+        .and_then(|mut file| {
+            file.write_all(content.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&tmp, path));
+    if let Err(e) = written {
+        let _ = fs::remove_file(&tmp);
+        return Err(io_error(e));
+    }
+    Ok(())
+}
+
+/// Revisão do workspace salvo agora (None = ainda não existe)
+pub fn current_revision(dir: &Path, name: &str) -> Result<Option<u64>, WorkspaceError> {
+    let path = file_path(dir, name)?;
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(WorkspaceError::Io(format!("{}: {}", path.display(), e))),
+    };
+    let value: Value = serde_json::from_str(&content)
+        .map_err(|e| WorkspaceError::Invalid(format!("JSON inválido em {}: {}", path.display(), e)))?;
+    Ok(Some(value.get("revision").and_then(Value::as_u64).unwrap_or(0)))
+}
+
+/// JSON do editor (qualquer versão) → Workspace na versão atual
+pub fn parse(value: Value) -> Result<Workspace, WorkspaceError> {
+    let (value, from) = migrations::migrate(value).map_err(WorkspaceError::Invalid)?;
+    let workspace: Workspace = serde_json::from_value(value).map_err(|e| WorkspaceError::Invalid(e.to_string()))?;
+    if from < CURRENT_SCHEMA_VERSION {
+        println!("⬆️  Workspace '{}' migrado da versão {} pra {}", workspace.name, from, CURRENT_SCHEMA_VERSION);
+    }
+    Ok(workspace)
+}
+
+/// Lê `<dir>/<nome>.json` (migrando se for antigo)
+pub fn read(dir: &Path, name: &str) -> Result<Workspace, WorkspaceError> {
+    let path = file_path(dir, name)?;
+    let content = fs::read_to_string(&path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => WorkspaceError::NotFound(name.to_string()),
+        _ => WorkspaceError::Io(format!("{}: {}", path.display(), e)),
+    })?;
+    let value = serde_json::from_str(&content)
+        .map_err(|e| WorkspaceError::Invalid(format!("JSON inválido em {}: {}", path.display(), e)))?;
+
+    let mut workspace = parse(value)?;
+    if workspace.name.is_empty() {
+        workspace.name = name.to_string();
+    }
+    Ok(workspace)
+}
+
+/// Grava `<dir>/<nome>.json` na versão atual (sem checar revisão: o save passa por history::save)
+pub fn write(dir: &Path, workspace: &Workspace) -> Result<(), WorkspaceError> {
+    let path = file_path(dir, &workspace.name)?;
+    fs::create_dir_all(dir).map_err(|e| WorkspaceError::Io(format!("{}: {}", dir.display(), e)))?;
+    let content = serde_json::to_string_pretty(workspace).map_err(|e| WorkspaceError::Io(e.to_string()))?;
+    write_atomic(&path, &content)
+}
+
+/// Lê o workspace salvo e converte pro grafo executável
+pub fn load_graph(dir: &Path, name: &str, specs: &HashMap<String, NodeSpec>) -> Result<WorkflowGraph, WorkspaceError> {
+    let workspace = read(dir, name)?;
+    graph::to_workflow_graph(&workspace, specs).map_err(WorkspaceError::Invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_name() {
+        for name in ["workspace-1", "Meu fluxo v2.1", "geração_rápida"] {
+            assert_eq!(check_name(name), Ok(()), "{}", name);
+        }
+        for name in ["", "  ", "../etc/passwd", "a/b", "a\\b", "..", ".trash", "x.", " x", "con", "NUL.backup", "a:b"] {
+            assert!(matches!(check_name(name), Err(WorkspaceError::InvalidName { .. })), "{}", name);
+        }
+        assert!(check_name(&"a".repeat(101)).is_err());
+        assert!(file_path(Path::new("workspaces"), "../x").is_err());
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_file() {
+        let dir = std::env::temp_dir().join("ndnm_test_workspace_atomic");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("ws-1.json");
+
+        write_atomic(&path, "{\"revision\": 1}").unwrap();
+        write_atomic(&path, "{\"revision\": 2}").unwrap();
+        assert_eq!(current_revision(&dir, "ws-1").unwrap(), Some(2));
+        assert_eq!(current_revision(&dir, "ws-2").unwrap(), None);
+        // Temporário não fica pra trás
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
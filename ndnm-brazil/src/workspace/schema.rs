@@ -0,0 +1,245 @@
+// ndnm-brazil/src/workspace/schema.rs
+//
+// Modelo tipado do workspace salvo pelo editor (workspaces/<nome>.json)
+// - schema_version: arquivos antigos passam pela cadeia de migrações (migrations.rs) antes de virar Workspace
+// - campos que o Brazil não usa (viewport, width/height, selected, ...) passam direto pelo `extra`
+// - validate() roda no save: o que for salvo precisa dar pra executar
+// - revision: sobe a cada save; o editor manda de volta a que carregou (save em cima de revisão velha = conflito)
+
+use super::graph::NodeSpec;
+use crate::execution::PLAY_NODE_TYPES;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+
+/// Versão do formato gravada nos workspaces salvos por este Brazil
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Workspace {
+    #[serde(default)]
+    pub schema_version: u32,
+    /// 0 = nunca salvo com revisão (arquivo antigo ou workspace novo)
+    #[serde(default)]
+    pub revision: u64,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub nodes: Vec<WorkspaceNode>,
+    #[serde(default)]
+    pub edges: Vec<WorkspaceEdge>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceNode {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub node_type: String,
+    #[serde(default)]
+    pub position: Position,
+    #[serde(default)]
+    pub data: Map<String, Value>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceEdge {
+    #[serde(default)]
+    pub id: String,
+    pub source: String,
+    /// Sem handle = saída/entrada 0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_handle: Option<String>,
+    pub target: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_handle: Option<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl WorkspaceNode {
+    /// Quantas saídas o node tem no editor (`outputsCount`), se o data disser
+    fn outputs_count(&self) -> Option<usize> {
+        self.data.get("outputsCount").and_then(Value::as_u64).map(|n| n as usize)
+    }
+}
+
+impl Workspace {
+    /// Problemas que impedem salvar (vazio = ok). `specs` = tipos de node do discovery.
+    pub fn validate(&self, specs: &HashMap<String, NodeSpec>) -> Vec<String> {
+        let mut issues = Vec::new();
+        if self.name.trim().is_empty() {
+            issues.push("Workspace sem nome".to_string());
+        }
+
+        let play_spec = NodeSpec::default();
+        let mut nodes: HashMap<&str, (&WorkspaceNode, &NodeSpec)> = HashMap::new();
+        let mut seen = HashSet::new();
+        for node in &self.nodes {
+            if !seen.insert(node.id.as_str()) {
+                issues.push(format!("Node {} aparece duas vezes", node.id));
+                continue;
+            }
+            match specs.get(&node.node_type) {
+                Some(spec) => {
+                    nodes.insert(&node.id, (node, spec));
+                }
+                None if PLAY_NODE_TYPES.contains(&node.node_type.as_str()) => {
+                    nodes.insert(&node.id, (node, &play_spec));
+                }
+                None => issues.push(format!("Node {}: tipo '{}' não encontrado no discovery", node.id, node.node_type)),
+            }
+        }
+
+        for edge in &self.edges {
+            let source = nodes.get(edge.source.as_str());
+            let target = nodes.get(edge.target.as_str());
+            for (end, node_id) in [(source, &edge.source), (target, &edge.target)] {
+                if end.is_none() && !self.nodes.iter().any(|n| &n.id == node_id) {
+                    issues.push(format!("Conexão {}: node {} não existe", edge.id, node_id));
+                }
+            }
+
+            if let (Some((node, spec)), Some(handle)) = (source, &edge.source_handle) {
+                if !spec.outputs.accepts(handle, "out") {
+                    issues.push(format!("Conexão {}: saída '{}' não existe em {}", edge.id, handle, node.id));
+                }
+                // Só as saídas out_N têm quantidade fixa (templates de section crescem sozinhos)
+                if let (Some(index), Some(count)) = (spec.outputs.plain_index(handle, "out"), node.outputs_count().or(spec.outputs_count)) {
+                    if index >= count {
+                        issues.push(format!(
+                            "Conexão {}: saída {} de {} não existe (node tem {} saídas)",
+                            edge.id, index, node.id, count
+                        ));
+                    }
+                }
+            }
+            if let (Some((node, spec)), Some(handle)) = (target, &edge.target_handle) {
+                if !spec.inputs.accepts(handle, "in") {
+                    issues.push(format!("Conexão {}: entrada '{}' não existe em {}", edge.id, handle, node.id));
+                }
+            }
+        }
+
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn specs() -> HashMap<String, NodeSpec> {
+        HashMap::from([
+            ("fixedValue".to_string(), NodeSpec::new(3010, "🔢 Valor Fixo", &[]).with_outputs_count(1)),
+            ("add".to_string(), NodeSpec::new(3000, "➕ Somar", &[]).with_outputs_count(1)),
+        ])
+    }
+
+    fn workspace(value: Value) -> Workspace {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_roundtrip_keeps_editor_fields() {
+        let value = json!({
+            "schema_version": 1,
+            "revision": 3,
+            "name": "ws-1",
+            "viewport": {"x": 0, "y": 0, "zoom": 1.5},
+            "nodes": [{"id": "n1", "type": "add", "position": {"x": 1.5, "y": 2.0}, "data": {"label": "➕"}, "selected": true}],
+            "edges": [{"id": "e1", "source": "n1", "sourceHandle": "out_0", "target": "n1", "animated": true}]
+        });
+        let parsed = workspace(value.clone());
+        assert_eq!(parsed.nodes[0].position, Position { x: 1.5, y: 2.0 });
+        assert_eq!(parsed.edges[0].target_handle, None);
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), value);
+    }
+
+    #[test]
+    fn test_validate() {
+        let valid = workspace(json!({
+            "name": "ws-1",
+            "nodes": [
+                {"id": "n1", "type": "fixedValue", "data": {"outputsCount": 2}},
+                {"id": "n2", "type": "add", "data": {}},
+                {"id": "play-1", "type": "playButton", "data": {}}
+            ],
+            "edges": [
+                {"id": "e1", "source": "n1", "sourceHandle": "out_1", "target": "n2", "targetHandle": "in_3"},
+                {"id": "e2", "source": "n2", "sourceHandle": "out_0", "target": "play-1", "targetHandle": "in_0"}
+            ]
+        }));
+        assert_eq!(valid.validate(&specs()), Vec::<String>::new());
+
+        let invalid = workspace(json!({
+            "name": "ws-1",
+            "nodes": [
+                {"id": "n1", "type": "fixedValue", "data": {}},
+                {"id": "n2", "type": "ksampler", "data": {}},
+                {"id": "n1", "type": "add", "data": {}}
+            ],
+            "edges": [
+                {"id": "e1", "source": "n1", "sourceHandle": "out_1", "target": "n2"},
+                {"id": "e2", "source": "n1", "target": "gone"},
+                {"id": "e3", "source": "n1", "sourceHandle": "out_0", "target": "n1", "targetHandle": "copy_input_0"}
+            ]
+        }));
+        let issues = invalid.validate(&specs());
+        assert_eq!(issues.len(), 5, "{:?}", issues);
+        assert!(issues[0].contains("ksampler"));
+        assert!(issues[1].contains("duas vezes"));
+        assert!(issues[2].contains("node tem 1 saídas"));
+        assert!(issues[3].contains("gone"));
+        assert!(issues[4].contains("copy_input_0"));
+    }
+
+    #[test]
+    fn test_fs_browser_per_file_slots_save_but_do_not_run() {
+        // Sections de verdade do node-fs-browser (internal_files = dynamic_per_file)
+        let (config, _) = ndnm_core::load_config(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/../node-fs-browser/config.yaml"),
+            env!("CARGO_MANIFEST_DIR"),
+        )
+        .unwrap();
+        let mut specs = specs();
+        specs.insert("filesystem".to_string(), NodeSpec::new(3011, "📂 Arquivos", &config.sections));
+
+        let editor = workspace(json!({
+            "name": "ws-1",
+            "nodes": [
+                {"id": "n1", "type": "fixedValue", "data": {}},
+                {"id": "fs-1", "type": "filesystem", "data": {"target_directory": "/tmp"}}
+            ],
+            "edges": [
+                {"id": "e1", "source": "n1", "sourceHandle": "out_0", "target": "fs-1", "targetHandle": "internal_input_readme.txt"},
+                {"id": "e2", "source": "fs-1", "sourceHandle": "internal_output_notas.md", "target": "fs-1", "targetHandle": "copy_input_0"}
+            ]
+        }));
+        assert_eq!(editor.validate(&specs), Vec::<String>::new());
+
+        // A execução continua recusando (ainda não sabe numerar handle por arquivo)
+        let error = super::super::graph::to_workflow_graph(&editor, &specs).unwrap_err();
+        assert!(error.contains("internal_input_readme.txt") && error.contains("não é suportado"), "{}", error);
+
+        // Handle que não é de nenhuma seção continua barrado no save
+        let unknown = workspace(json!({
+            "name": "ws-1",
+            "nodes": [{"id": "fs-1", "type": "filesystem", "data": {}}],
+            "edges": [{"id": "e1", "source": "fs-1", "sourceHandle": "internal_input_readme.txt", "target": "fs-1", "targetHandle": "other_readme.txt"}]
+        }));
+        let issues = unknown.validate(&specs);
+        assert_eq!(issues.len(), 2, "{:?}", issues);
+    }
+}
@@ -0,0 +1,155 @@
+// ndnm-brazil/src/workspace/store.rs
+//
+// Gerenciamento dos arquivos em workspaces/: listar, renomear, duplicar, apagar
+// - só os *.json da raiz são workspaces (workspaces/runs é o cache dos runs)
+// - apagar não é definitivo: o arquivo vai pra workspaces/.trash/<nome>_<timestamp>.json
+//   (e o histórico de versões junto, em <nome>_<timestamp>_history/)
+
+use super::{check_name, file_path, history, migrations, read, write, Workspace, WorkspaceError};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Pasta da lixeira, dentro da pasta dos workspaces
+pub const TRASH_DIR: &str = ".trash";
+
+/// Um workspace na listagem (GET /workspaces e WORKSPACES_CHANGED)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorkspaceSummary {
+    pub name: String,
+    pub modified_at: String,
+    pub node_count: usize,
+    /// Versão gravada no arquivo (menor que a atual = migra no próximo load)
+    pub schema_version: u32,
+}
+
+/// Sufixo dos nomes na lixeira (dois deletes do mesmo nome não colidem)
+pub(super) fn trash_stamp() -> String {
+    Utc::now().format("%Y-%m-%d_%H-%M-%S-%3f").to_string()
+}
+
+/// Lê `from` já com o nome `to`, se `to` ainda não existir
+fn copy_as(dir: &Path, from: &str, to: &str) -> Result<Workspace, WorkspaceError> {
+    let mut workspace = read(dir, from)?;
+    if file_path(dir, to)?.exists() {
+        return Err(WorkspaceError::AlreadyExists(to.to_string()));
+    }
+    workspace.name = to.to_string();
+    Ok(workspace)
+}
+
+/// Workspaces salvos, em ordem de nome. Arquivo que não é JSON (ou com nome inválido) fica de fora.
+pub fn list(dir: &Path) -> Vec<WorkspaceSummary> {
+    let Ok(entries) = fs::read_dir(dir) else { return Vec::new() };
+
+    let mut workspaces: Vec<WorkspaceSummary> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().and_then(|e| e.to_str()) == Some("json"))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_str()?.to_string();
+            check_name(&name).ok()?;
+            let value: Value = match fs::read_to_string(&path).map(|c| serde_json::from_str(&c)) {
+                Ok(Ok(value)) => value,
+                _ => {
+                    println!("⚠️  [Workspace] {} ignorado: não é um JSON válido", path.display());
+                    return None;
+                }
+            };
+            let modified_at = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .map(|at| DateTime::<Utc>::from(at).to_rfc3339())
+                .unwrap_or_default();
+
+            Some(WorkspaceSummary {
+                name,
+                modified_at,
+                node_count: value["nodes"].as_array().map_or(0, Vec::len),
+                schema_version: migrations::version_of(&value),
+            })
+        })
+        .collect();
+
+    workspaces.sort_by(|a, b| a.name.cmp(&b.name));
+    workspaces
+}
+
+/// Copia `from` pra um workspace novo `to` (não sobrescreve). A cópia começa um histórico próprio.
+pub fn duplicate(dir: &Path, from: &str, to: &str) -> Result<Workspace, WorkspaceError> {
+    let workspace = copy_as(dir, from, to)?;
+    write(dir, &workspace)?;
+    history::record(dir, &workspace)?;
+    Ok(workspace)
+}
+
+/// Renomeia `from` pra `to` (o `name` dentro do arquivo e o histórico acompanham)
+pub fn rename(dir: &Path, from: &str, to: &str) -> Result<Workspace, WorkspaceError> {
+    let workspace = copy_as(dir, from, to)?;
+    write(dir, &workspace)?;
+    fs::remove_file(file_path(dir, from)?).map_err(|e| WorkspaceError::Io(format!("{}: {}", from, e)))?;
+    history::rename(dir, from, to)?;
+    Ok(workspace)
+}
+
+/// Move o workspace pra lixeira e devolve onde ele foi parar
+pub fn delete(dir: &Path, name: &str) -> Result<PathBuf, WorkspaceError> {
+    let path = file_path(dir, name)?;
+    if !path.is_file() {
+        return Err(WorkspaceError::NotFound(name.to_string()));
+    }
+
+    let trash = dir.join(TRASH_DIR);
+    fs::create_dir_all(&trash).map_err(|e| WorkspaceError::Io(format!("{}: {}", trash.display(), e)))?;
+    let target = trash.join(format!("{}_{}.json", name, trash_stamp()));
+    fs::rename(&path, &target).map_err(|e| WorkspaceError::Io(format!("{}: {}", path.display(), e)))?;
+    history::trash(dir, name)?;
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_list_rename_duplicate_delete() {
+        let dir = std::env::temp_dir().join("ndnm_test_workspace_store");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("runs")).unwrap();
+        fs::write(dir.join("a.json"), json!({"name": "a", "nodes": [{"id": "n1", "type": "add"}]}).to_string()).unwrap();
+        fs::write(dir.join("broken.json"), "{").unwrap();
+        fs::write(dir.join(".hidden.json"), "{}").unwrap();
+
+        // Pasta de runs e JSON quebrado ficam de fora
+        let names = |dir: &Path| list(dir).into_iter().map(|w| w.name).collect::<Vec<_>>();
+        let listed = list(&dir);
+        assert_eq!(listed.len(), 1);
+        assert_eq!((listed[0].node_count, listed[0].schema_version), (1, 0));
+
+        let copy = duplicate(&dir, "a", "b").unwrap();
+        assert_eq!(copy.name, "b");
+        assert_eq!(history::list(&dir, "b").len(), 1);
+        assert_eq!(names(&dir), vec!["a", "b"]);
+        assert_eq!(duplicate(&dir, "a", "b").unwrap_err(), WorkspaceError::AlreadyExists("b".to_string()));
+
+        rename(&dir, "b", "c").unwrap();
+        assert_eq!(names(&dir), vec!["a", "c"]);
+        assert_eq!(read(&dir, "c").unwrap().name, "c");
+        assert_eq!(history::list(&dir, "c").len(), 1);
+        assert_eq!(rename(&dir, "missing", "d").unwrap_err(), WorkspaceError::NotFound("missing".to_string()));
+
+        let trashed = delete(&dir, "a").unwrap();
+        assert!(trashed.starts_with(dir.join(TRASH_DIR)) && trashed.is_file());
+        assert_eq!(names(&dir), vec!["c"]);
+        delete(&dir, "c").unwrap();
+        assert!(history::list(&dir, "c").is_empty());
+        assert_eq!(fs::read_dir(dir.join(TRASH_DIR)).unwrap().count(), 3);
+        assert!(delete(&dir, "a").is_err());
+        assert!(list(&dir).is_empty());
+        assert!(matches!(rename(&dir, "c", "../c"), Err(WorkspaceError::InvalidName { .. } | WorkspaceError::NotFound(_))));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
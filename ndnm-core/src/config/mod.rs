@@ -23,6 +23,21 @@ pub struct NodeConfig {
     #[serde(default)]
     pub input_fields: Vec<InputFieldConfig>,
 
+    // --- EXECUÇÃO (lido pelo Brazil) ---
+    /// Quantos /run simultâneos o node aguenta (vazio = sem limite no Brazil; 1 pra nodes de GPU)
+    pub max_concurrency: Option<usize>,
+    /// Timeout e retries do /run (cada campo vazio usa o default do Brazil)
+    #[serde(default)]
+    pub execution: ExecutionPolicyConfig,
+
+    /// Quando o Brazil pode reaproveitar o output do node (vazio = pure)
+    #[serde(default)]
+    pub cache: CachePolicyConfig,
+
+    // --- RETENÇÃO DE RUNS (só o config.yaml do Brazil usa) ---
+    #[serde(default)]
+    pub retention: RetentionConfig,
+
     // --- NOVO FORMATO (para nodes avançados) ---
     // Se presente, este node usa o sistema de sections
     #[serde(default)]
@@ -30,6 +45,91 @@ pub struct NodeConfig {
     pub node_id_hash: Option<String>,
 }
 
+// --- POLÍTICA DE EXECUÇÃO ---
+
+/// Timeout/retries de um node. Vai no config.yaml (seção `execution:`) e pode ser
+/// sobrescrito por node no grafo; campo vazio = herda do nível de cima.
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq)]
+pub struct ExecutionPolicyConfig {
+    /// Tempo máximo de um /run (ms)
+    pub timeout_ms: Option<u64>,
+    /// Tentativas extras depois da primeira falha
+    pub max_retries: Option<u32>,
+    /// Espera antes do primeiro retry (ms); dobra a cada tentativa
+    pub backoff_ms: Option<u64>,
+    /// Quais falhas disparam retry
+    pub retry_on: Option<RetryOn>,
+}
+
+/// Tipo de falha que vale retry
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RetryOn {
+    /// Só erros de conexão e timeout (o node nem respondeu)
+    Connection,
+    /// Conexão/timeout e também respostas 5xx
+    ServerError,
+}
+
+// --- POLÍTICA DE CACHE ---
+
+/// Seção `cache:` do config.yaml: quando o output de um node pode vir do cache do Brazil.
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq)]
+pub struct CachePolicyConfig {
+    /// pure (padrão), volatile, ttl ou content_addressed
+    pub policy: Option<CacheMode>,
+    /// Validade do cache com `policy: ttl` (segundos)
+    pub ttl_seconds: Option<u64>,
+    /// Campos do input com caminho de arquivo (`content_addressed`).
+    /// Vazio = todo campo de texto do input que aponta pra um arquivo existente.
+    #[serde(default)]
+    pub file_fields: Vec<String>,
+    /// Como identificar o arquivo (`content_addressed`, padrão mtime_size)
+    pub file_key: Option<FileKey>,
+}
+
+/// Determinismo declarado pelo node
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheMode {
+    /// Mesmo input = mesmo output: cache sempre vale (ex: node-sum)
+    Pure,
+    /// Nunca vem do cache (lê relógio, disco, rede...)
+    Volatile,
+    /// Cache vale por `ttl_seconds` depois que o node rodou
+    Ttl,
+    /// Como pure, mas o conteúdo dos arquivos citados no input entra no hash
+    ContentAddressed,
+}
+
+/// Identidade de um arquivo no hash de input
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileKey {
+    /// Data de modificação + tamanho (barato, serve pra quase tudo)
+    MtimeSize,
+    /// blake3 do conteúdo (lê o arquivo inteiro a cada run)
+    ContentHash,
+}
+
+// --- RETENÇÃO DE RUNS ---
+
+/// Limites do Cache Cleaner do Brazil (pastas em workspaces/runs).
+/// Campo vazio = sem limite; runs fixados (pin) nunca são apagados.
+#[derive(Debug, Clone, Deserialize, Serialize, Default, PartialEq)]
+pub struct RetentionConfig {
+    /// Máximo de runs guardados por workspace (os mais antigos saem primeiro)
+    pub max_runs_per_workspace: Option<usize>,
+    /// Tamanho máximo da pasta de runs (MB)
+    pub max_total_mb: Option<u64>,
+    /// Idade máxima de um run (dias)
+    pub max_age_days: Option<u64>,
+    /// De quanto em quanto tempo a limpeza roda (minutos)
+    pub interval_minutes: Option<u64>,
+    /// Versões guardadas por workspace salvo (workspaces/.history); vazio = padrão do Brazil
+    pub max_workspace_versions: Option<usize>,
+}
+
 // --- NOVO SISTEMA DE SECTIONS ---
 
 /// Comportamento de uma seção de I/O
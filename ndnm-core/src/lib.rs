@@ -4,6 +4,7 @@ pub mod error;
 pub mod config;
 pub mod server;
 pub mod runner;
+pub mod trigger;
 
 // Exports públicos
 pub use node::{Node, async_trait};
@@ -11,6 +12,12 @@ pub use error::AppError;
 pub use config::{
     NodeConfig,
     InputFieldConfig,
+    ExecutionPolicyConfig,
+    RetryOn,
+    CachePolicyConfig,
+    CacheMode,
+    FileKey,
+    RetentionConfig,
     Section,
     SectionBehavior,
     SlotDefinition,
@@ -18,4 +25,5 @@ pub use config::{
     ConnectionMode
 };
 pub use server::{router, serve, ServerOpts};
-pub use runner::{run_node, load_config};
\ No newline at end of file
+pub use runner::{run_node, load_config};
+pub use trigger::{BrazilClient, RunResponse, TriggerInput, TriggerOutput};
\ No newline at end of file
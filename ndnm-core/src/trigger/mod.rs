@@ -0,0 +1,181 @@
+// ndnm-core/src/trigger/mod.rs
+//
+// Cliente dos Play nodes pro ndnm-brazil
+// Os Play nodes não executam nada: o /run deles só pede pro Brazil disparar o run
+// (POST /runs, ou POST /hooks no PlayWebhook) e devolve o que ele respondeu.
+
+use crate::error::AppError;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// URL padrão do ndnm-brazil (sobrescreve com NDNM_BRAZIL_URL)
+pub const DEFAULT_BRAZIL_URL: &str = "http://localhost:3100";
+
+/// Variável de ambiente com a URL do ndnm-brazil
+pub const BRAZIL_URL_ENV: &str = "NDNM_BRAZIL_URL";
+
+/// Header com o segredo compartilhado do webhook
+pub const WEBHOOK_SECRET_HEADER: &str = "X-NDNM-Webhook-Secret";
+
+/// Input comum dos Play nodes: `{"action": "execute", "workspace": "...", "play_node_id": "..."}`.
+/// Play com campos a mais (payload, path, ...) usa este com `#[serde(flatten)]`.
+#[derive(Debug, Deserialize)]
+pub struct TriggerInput {
+    /// Ação a ser executada ("execute")
+    pub action: String,
+    /// Workspace onde o Play está
+    #[serde(default)]
+    pub workspace: Option<String>,
+    /// ID do Play no grafo (o Brazil executa a cadeia dele)
+    #[serde(default)]
+    pub play_node_id: Option<String>,
+}
+
+impl TriggerInput {
+    /// Confere a ação e devolve (workspace, play_node_id)
+    pub fn target(&self) -> Result<(&str, &str), AppError> {
+        if self.action != "execute" {
+            return Err(AppError::BadRequest(format!(
+                "Invalid action: '{}'. Expected 'execute'",
+                self.action
+            )));
+        }
+        match (&self.workspace, &self.play_node_id) {
+            (Some(workspace), Some(play_node_id)) => Ok((workspace, play_node_id)),
+            _ => Err(AppError::bad("Missing 'workspace' or 'play_node_id' to trigger the run")),
+        }
+    }
+}
+
+/// Resposta do Brazil (só o que os Plays usam): `result` vem do POST /runs, `outputs` do POST /hooks
+#[derive(Debug, Deserialize)]
+pub struct RunResponse {
+    pub run_id: String,
+    pub status: String,
+    #[serde(default)]
+    pub result: Option<Value>,
+    #[serde(default)]
+    pub outputs: Option<Value>,
+    #[serde(default)]
+    pub error: Option<Value>,
+}
+
+impl RunResponse {
+    /// Mensagem do erro, se o run falhou
+    pub fn error_message(&self) -> Option<String> {
+        self.error
+            .as_ref()
+            .map(|error| error["message"].as_str().unwrap_or("Run falhou").to_string())
+    }
+
+    /// Nodes executados no run (fora os que vieram do cache)
+    pub fn executed_nodes(&self) -> Option<usize> {
+        self.result.as_ref()?["executed_nodes"].as_u64().map(|n| n as usize)
+    }
+}
+
+/// Output comum dos Play nodes (só volta quando o run termina no Brazil)
+#[derive(Debug, Serialize)]
+pub struct TriggerOutput {
+    /// Status final do run ("completed", "failed", "cancelled") ou "started"
+    pub status: String,
+    /// Mensagem descritiva
+    pub message: String,
+    /// Run ID gerado pelo Brazil
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+}
+
+impl From<&RunResponse> for TriggerOutput {
+    /// Mensagem padrão: o erro do run ou "Run completed: 2 nodes executados, 1 do cache"
+    fn from(run: &RunResponse) -> Self {
+        let message = match (run.error_message(), &run.result) {
+            (Some(error), _) => error,
+            (None, Some(result)) => format!(
+                "Run {}: {} nodes executados, {} do cache",
+                run.status, result["executed_nodes"], result["cached_nodes"]
+            ),
+            (None, None) => format!("Run {}", run.status),
+        };
+        Self {
+            status: run.status.clone(),
+            message,
+            run_id: Some(run.run_id.clone()),
+        }
+    }
+}
+
+/// Cliente HTTP do ndnm-brazil
+#[derive(Debug, Clone)]
+pub struct BrazilClient {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl BrazilClient {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// URL do NDNM_BRAZIL_URL (ou a padrão)
+    pub fn from_env() -> Self {
+        Self::new(std::env::var(BRAZIL_URL_ENV).unwrap_or_else(|_| DEFAULT_BRAZIL_URL.to_string()))
+    }
+
+    /// POST /runs esperando o run terminar. `payload` vira a saída 0 do Play.
+    pub async fn trigger_run(
+        &self,
+        workspace: &str,
+        play_node_id: &str,
+        payload: Option<Value>,
+    ) -> Result<RunResponse, AppError> {
+        let mut body = json!({
+            "workspace": workspace,
+            "play_node_id": play_node_id,
+            "wait": true,
+        });
+        if let Some(payload) = payload {
+            body["payload"] = payload;
+        }
+        self.send(self.client.post(format!("{}/runs", self.url)).json(&body), "run").await
+    }
+
+    /// POST /hooks/{workspace}/{play_node_id} com o body do webhook (e o segredo, se tiver)
+    pub async fn trigger_hook(
+        &self,
+        workspace: &str,
+        play_node_id: &str,
+        payload: &Value,
+        secret: Option<&str>,
+    ) -> Result<RunResponse, AppError> {
+        let mut request = self
+            .client
+            .post(format!("{}/hooks/{}/{}", self.url, workspace, play_node_id))
+            .json(payload);
+        if let Some(secret) = secret {
+            request = request.header(WEBHOOK_SECRET_HEADER, secret);
+        }
+        self.send(request, "webhook").await
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder, what: &str) -> Result<RunResponse, AppError> {
+        let response = request.send().await.map_err(|e| {
+            eprintln!("❌ Falha ao chamar o ndnm-brazil em {}: {}", self.url, e);
+            AppError::Internal
+        })?;
+
+        let http_status = response.status();
+        if !http_status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(AppError::BadRequest(format!("Brazil recusou o {} ({}): {}", what, http_status, body)));
+        }
+
+        response.json().await.map_err(|e| {
+            eprintln!("❌ Resposta inválida do ndnm-brazil: {}", e);
+            AppError::Internal
+        })
+    }
+}
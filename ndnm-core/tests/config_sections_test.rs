@@ -1,5 +1,5 @@
 // ndnm-core/tests/config_sections_test.rs
-use ndnm_core::{NodeConfig, SectionBehavior, ConnectionMode};
+use ndnm_core::{NodeConfig, SectionBehavior, ConnectionMode, RetryOn, CacheMode, FileKey};
 
 #[test]
 fn test_deserialize_advanced_config() {
@@ -103,3 +103,83 @@ initial_outputs_count: 1
     assert_eq!(config.inputs_mode, Some("n".to_string()));
     assert_eq!(config.sections.len(), 0); // Sem sections no formato legado
 }
+
+
+#[test]
+fn test_execution_limits_config() {
+    let yaml = r#"
+port: 3050
+label: "🎨 KSampler"
+node_type: "ksampler"
+max_concurrency: 1
+"#;
+
+    let config: NodeConfig = serde_yaml::from_str(yaml).expect("Failed to deserialize config");
+    assert_eq!(config.max_concurrency, Some(1));
+
+    // Sem o campo = Brazil usa o default
+    let legacy: NodeConfig = serde_yaml::from_str("port: 3000").unwrap();
+    assert_eq!(legacy.max_concurrency, None);
+}
+
+#[test]
+fn test_execution_policy_config() {
+    let yaml = r#"
+port: 3050
+node_type: "ksampler"
+execution:
+  timeout_ms: 600000
+  max_retries: 2
+  backoff_ms: 1000
+  retry_on: "server_error"
+"#;
+
+    let config: NodeConfig = serde_yaml::from_str(yaml).expect("Failed to deserialize config");
+    assert_eq!(config.execution.timeout_ms, Some(600_000));
+    assert_eq!(config.execution.max_retries, Some(2));
+    assert_eq!(config.execution.backoff_ms, Some(1000));
+    assert_eq!(config.execution.retry_on, Some(RetryOn::ServerError));
+
+    // Sem a seção = tudo vazio (Brazil usa os defaults)
+    let legacy: NodeConfig = serde_yaml::from_str("port: 3000").unwrap();
+    assert_eq!(legacy.execution, Default::default());
+}
+
+#[test]
+fn test_retention_config() {
+    let yaml = r#"
+port: 3100
+retention:
+  max_runs_per_workspace: 50
+  max_age_days: 30
+  max_workspace_versions: 10
+"#;
+
+    let config: NodeConfig = serde_yaml::from_str(yaml).expect("Failed to deserialize config");
+    assert_eq!(config.retention.max_runs_per_workspace, Some(50));
+    assert_eq!(config.retention.max_age_days, Some(30));
+    assert_eq!(config.retention.max_total_mb, None);
+    assert_eq!(config.retention.interval_minutes, None);
+    assert_eq!(config.retention.max_workspace_versions, Some(10));
+}
+
+#[test]
+fn test_cache_policy_config() {
+    let yaml = r#"
+port: 3003
+cache:
+  policy: content_addressed
+  file_fields: ["file_path"]
+  file_key: content_hash
+"#;
+
+    let config: NodeConfig = serde_yaml::from_str(yaml).expect("Failed to deserialize config");
+    assert_eq!(config.cache.policy, Some(CacheMode::ContentAddressed));
+    assert_eq!(config.cache.file_fields, vec!["file_path".to_string()]);
+    assert_eq!(config.cache.file_key, Some(FileKey::ContentHash));
+    assert_eq!(config.cache.ttl_seconds, None);
+
+    // Sem a seção: pure
+    let config: NodeConfig = serde_yaml::from_str("port: 3000").unwrap();
+    assert_eq!(config.cache.policy, None);
+}
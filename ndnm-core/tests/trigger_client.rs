@@ -0,0 +1,76 @@
+// ndnm-core/tests/trigger_client.rs
+use axum::{extract::Path, http::{HeaderMap, StatusCode}, routing::post, Json, Router};
+use ndnm_core::{AppError, BrazilClient, TriggerInput, TriggerOutput};
+use serde_json::{json, Value};
+
+/// Sobe um Brazil fake: POST /runs devolve sempre o mesmo run (com o payload no result)
+/// e POST /hooks exige o segredo "s3cret" e devolve o body como output do Play
+async fn fake_brazil() -> String {
+    let app = Router::new()
+        .route(
+            "/runs",
+            post(|Json(body): Json<Value>| async move {
+                assert_eq!(body["wait"], json!(true));
+                Json(json!({
+                    "run_id": "run_fake",
+                    "status": "completed",
+                    "result": {"executed_nodes": 2, "cached_nodes": 1, "payload": body["payload"]}
+                }))
+            }),
+        )
+        .route(
+            "/hooks/:workspace/:play_id",
+            post(|Path((_, play_id)): Path<(String, String)>, headers: HeaderMap, Json(payload): Json<Value>| async move {
+                if headers.get("x-ndnm-webhook-secret").and_then(|v| v.to_str().ok()) != Some("s3cret") {
+                    return (StatusCode::UNAUTHORIZED, Json(json!("Segredo do webhook inválido")));
+                }
+                (StatusCode::OK, Json(json!({
+                    "run_id": "run_fake",
+                    "status": "completed",
+                    "outputs": {play_id: {"outputs": [payload]}}
+                })))
+            }),
+        );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+#[tokio::test]
+async fn trigger_run_and_hook() {
+    let brazil = BrazilClient::new(fake_brazil().await);
+
+    let run = brazil.trigger_run("workspace-1", "play-1", None).await.unwrap();
+    assert_eq!(run.executed_nodes(), Some(2));
+    let output = serde_json::to_value(TriggerOutput::from(&run)).unwrap();
+    assert_eq!(output, json!({
+        "status": "completed",
+        "message": "Run completed: 2 nodes executados, 1 do cache",
+        "run_id": "run_fake"
+    }));
+
+    // Payload vira a saída 0 do Play
+    let run = brazil.trigger_run("workspace-1", "watch-1", Some(json!("C:/dados/a.csv"))).await.unwrap();
+    assert_eq!(run.result.unwrap()["payload"], json!("C:/dados/a.csv"));
+
+    let hook = brazil.trigger_hook("workspace-1", "hook-1", &json!({"order": 42}), Some("s3cret")).await.unwrap();
+    assert_eq!(hook.outputs, Some(json!({"hook-1": {"outputs": [{"order": 42}]}})));
+    let refused = brazil.trigger_hook("workspace-1", "hook-1", &json!({}), Some("errado")).await.unwrap_err();
+    assert!(matches!(refused, AppError::BadRequest(msg) if msg.contains("401")));
+
+    // Brazil fora do ar
+    let offline = BrazilClient::new("http://127.0.0.1:9");
+    assert!(matches!(offline.trigger_run("workspace-1", "play-1", None).await, Err(AppError::Internal)));
+}
+
+#[test]
+fn trigger_input_needs_execute_and_play() {
+    let input = |value: Value| serde_json::from_value::<TriggerInput>(value).unwrap();
+
+    let ok = input(json!({"action": "execute", "workspace": "workspace-1", "play_node_id": "play-1"}));
+    assert_eq!(ok.target().unwrap(), ("workspace-1", "play-1"));
+    assert!(input(json!({"action": "execute", "workspace": "workspace-1"})).target().is_err());
+    assert!(input(json!({"action": "stop", "workspace": "workspace-1", "play_node_id": "play-1"})).target().is_err());
+}
@@ -1,27 +1,21 @@
 // node-comfy-play/src/main.rs
-use ndnm_core::{async_trait, AppError, Node};
-use serde::{Deserialize, Serialize};
-
-/// Input para o Comfy Play
-/// Fase 1: Só recebe comando "execute"
-#[derive(Debug, Deserialize)]
-pub struct Input {
-    /// Ação a ser executada ("execute")
-    action: String,
-}
+use ndnm_core::{async_trait, AppError, BrazilClient, Node, TriggerInput};
+use serde::Serialize;
 
 /// Output do Comfy Play
-/// Fase 1: Só confirma execução
 /// Nota: Este node NÃO tem saída no grafo (é terminal)
 #[derive(Debug, Serialize)]
 pub struct Output {
-    /// Status da execução
+    /// Status final do run ("completed", "failed", "cancelled")
     status: String,
     /// Mensagem descritiva
     message: String,
-    /// Total de nodes executados (fase 2+)
+    /// Total de nodes executados no run (fora os que vieram do cache)
     #[serde(skip_serializing_if = "Option::is_none")]
     nodes_executed: Option<usize>,
+    /// Run ID gerado pelo Brazil
+    #[serde(skip_serializing_if = "Option::is_none")]
+    run_id: Option<String>,
 }
 
 /// Node Comfy Play - O "Easy Mode" 🎮
@@ -32,37 +26,48 @@ pub struct Output {
 /// - Executa TUDO conectado a ele (sem granularidade)
 /// - Perfeito pra usuários que querem simplicidade
 ///
+/// A execução é do ndnm-brazil: o node dispara POST /runs e espera o fim.
+///
 /// Nome "Comfy" é uma homenagem sarcástica ao ComfyUI 😏
-#[derive(Default)]
-pub struct ComfyPlayNode;
+pub struct ComfyPlayNode {
+    brazil: BrazilClient,
+}
+
+impl ComfyPlayNode {
+    pub fn new(brazil_url: impl Into<String>) -> Self {
+        Self {
+            brazil: BrazilClient::new(brazil_url),
+        }
+    }
+}
+
+impl Default for ComfyPlayNode {
+    fn default() -> Self {
+        Self {
+            brazil: BrazilClient::from_env(),
+        }
+    }
+}
 
 #[async_trait]
 impl Node for ComfyPlayNode {
-    type Input = Input;
+    type Input = TriggerInput;
     type Output = Output;
 
     async fn process(&self, input: Self::Input) -> Result<Self::Output, AppError> {
-        // Fase 1: Implementação simples
-        // Só confirma que recebeu o comando
-        //
-        // Fases futuras (2+):
-        // - Resolve dependências (TODOS os nodes conectados)
-        // - Executa sequencialmente
-        // - Salva cache
-        // - Como é terminal (sem output), não dispara outros Plays
+        let (workspace, play_node_id) = input.target()?;
+        let run = self.brazil.trigger_run(workspace, play_node_id, None).await?;
 
-        if input.action == "execute" {
-            Ok(Output {
-                status: "started".to_string(),
-                message: "ComfyUI-style execution started (Fase 1 - confirmação apenas)".to_string(),
-                nodes_executed: None, // Fase 2: contar nodes executados
-            })
-        } else {
-            Err(AppError::BadRequest(format!(
-                "Invalid action: '{}'. Expected 'execute'",
-                input.action
-            )))
-        }
+        let message = run
+            .error_message()
+            .unwrap_or_else(|| format!("ComfyUI-style run {}", run.status));
+
+        Ok(Output {
+            nodes_executed: run.executed_nodes(),
+            message,
+            status: run.status,
+            run_id: Some(run.run_id),
+        })
     }
 }
 
@@ -1,70 +1,44 @@
 // node-play-button/src/main.rs
-use ndnm_core::{async_trait, AppError, Node};
-use serde::{Deserialize, Serialize};
-
-/// Input para o Play Button
-/// Fase 1: Só recebe comando "execute"
-/// Fases futuras: Receberá grafo completo para executar
-#[derive(Debug, Deserialize)]
-pub struct Input {
-    /// Ação a ser executada ("execute" por enquanto)
-    action: String,
-}
-
-/// Output do Play Button
-/// Fase 1: Só confirma que recebeu o comando
-/// Fases futuras: Retornará resultado da execução completa
-#[derive(Debug, Serialize)]
-pub struct Output {
-    /// Status da execução ("started", "completed", "error")
-    status: String,
-    /// Mensagem descritiva
-    message: String,
-    /// Run ID (será adicionado em fases futuras)
-    #[serde(skip_serializing_if = "Option::is_none")]
-    run_id: Option<String>,
-}
+use ndnm_core::{async_trait, AppError, BrazilClient, Node, TriggerInput, TriggerOutput};
 
 /// Node Play Button - O CHAD Play 💪
 ///
 /// Este é o Play avançado que tem:
 /// - Input (conecta em nodes anteriores)
 /// - Output (pode disparar outro Play - cascata!)
-/// - Cache inteligente (fase 2+)
-/// - Resolução de dependências (fase 3+)
-#[derive(Default)]
-pub struct PlayButtonNode;
+/// - Cache inteligente (feito pelo Brazil)
+/// - Resolução de dependências (feita pelo Brazil)
+///
+/// O node em si só dispara o run: POST /runs no ndnm-brazil e espera o resultado.
+pub struct PlayButtonNode {
+    brazil: BrazilClient,
+}
+
+impl PlayButtonNode {
+    pub fn new(brazil_url: impl Into<String>) -> Self {
+        Self {
+            brazil: BrazilClient::new(brazil_url),
+        }
+    }
+}
+
+impl Default for PlayButtonNode {
+    fn default() -> Self {
+        Self {
+            brazil: BrazilClient::from_env(),
+        }
+    }
+}
 
 #[async_trait]
 impl Node for PlayButtonNode {
-    type Input = Input;
-    type Output = Output;
+    type Input = TriggerInput;
+    type Output = TriggerOutput;
 
     async fn process(&self, input: Self::Input) -> Result<Self::Output, AppError> {
-        // Fase 1: Implementação SUPER simples
-        // Só confirma que recebeu o comando "execute"
-        //
-        // Fases futuras (2+): Aqui vai:
-        // 1. Resolver dependências (quais nodes executar)
-        // 2. Calcular hashes
-        // 3. Executar nodes sequencialmente
-        // 4. Salvar cache
-        // 5. Retornar resultado
-
-        if input.action == "execute" {
-            // Por enquanto: só confirma que recebeu
-            // TODO (Fase 2): Disparar execução via ndnm-brazil
-            Ok(Output {
-                status: "started".to_string(),
-                message: "Play execution started (Fase 1 - confirmação apenas)".to_string(),
-                run_id: None, // Fase 2: gerar run_id aqui
-            })
-        } else {
-            Err(AppError::BadRequest(format!(
-                "Invalid action: '{}'. Expected 'execute'",
-                input.action
-            )))
-        }
+        let (workspace, play_node_id) = input.target()?;
+        let run = self.brazil.trigger_run(workspace, play_node_id, None).await?;
+        Ok(TriggerOutput::from(&run))
     }
 }
 
@@ -0,0 +1,69 @@
+// node-play-file-watcher/src/main.rs
+use ndnm_core::{async_trait, AppError, BrazilClient, Node, TriggerInput, TriggerOutput};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Input para o Play File Watcher
+/// Quem observa a pasta é o Brazil (config no data do node); o /run aqui dispara na mão,
+/// opcionalmente dizendo qual arquivo "mudou".
+#[derive(Debug, Deserialize)]
+pub struct Input {
+    #[serde(flatten)]
+    trigger: TriggerInput,
+    /// Caminho que vira o output do Play (igual a um disparo do watcher)
+    #[serde(default)]
+    path: Option<String>,
+}
+
+/// Node Play File Watcher - O Play que fica de olho 👀
+///
+/// - Input (executa a cadeia ligada nele, igual o Play Button)
+/// - Output = caminho do arquivo criado/modificado (desce pros Plays em cascata)
+/// - Pasta, pattern e debounce no data do node; quem observa é o ndnm-brazil
+///
+/// O node em si só dispara o run: POST /runs no ndnm-brazil e espera o resultado.
+pub struct PlayFileWatcherNode {
+    brazil: BrazilClient,
+}
+
+impl PlayFileWatcherNode {
+    pub fn new(brazil_url: impl Into<String>) -> Self {
+        Self {
+            brazil: BrazilClient::new(brazil_url),
+        }
+    }
+}
+
+impl Default for PlayFileWatcherNode {
+    fn default() -> Self {
+        Self {
+            brazil: BrazilClient::from_env(),
+        }
+    }
+}
+
+#[async_trait]
+impl Node for PlayFileWatcherNode {
+    type Input = Input;
+    type Output = TriggerOutput;
+
+    async fn process(&self, input: Self::Input) -> Result<Self::Output, AppError> {
+        let (workspace, play_node_id) = input.trigger.target()?;
+        let run = self
+            .brazil
+            .trigger_run(workspace, play_node_id, input.path.map(Value::String))
+            .await?;
+        Ok(TriggerOutput::from(&run))
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), AppError> {
+    ndnm_core::run_node(
+        PlayFileWatcherNode::default(),
+        "node-play-file-watcher",
+        "File watcher Play node (folder watched by ndnm-brazil)",
+        env!("CARGO_MANIFEST_DIR"),
+    )
+    .await
+}
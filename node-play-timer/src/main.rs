@@ -0,0 +1,53 @@
+// node-play-timer/src/main.rs
+use ndnm_core::{async_trait, AppError, BrazilClient, Node, TriggerInput, TriggerOutput};
+
+/// Node Play Timer - O Play que aperta a si mesmo ⏰
+///
+/// - Input (executa a cadeia ligada nele, igual o Play Button)
+/// - Output (pode disparar outro Play em cascata)
+/// - Agenda por intervalo fixo ou cron, lida pelo ndnm-brazil do workspace salvo
+///
+/// A agenda em si (interval_seconds/cron/overlap) fica no data do node e quem dispara é o Brazil;
+/// o /run aqui é o "disparar agora", fora da agenda (POST /runs e espera o resultado).
+pub struct PlayTimerNode {
+    brazil: BrazilClient,
+}
+
+impl PlayTimerNode {
+    pub fn new(brazil_url: impl Into<String>) -> Self {
+        Self {
+            brazil: BrazilClient::new(brazil_url),
+        }
+    }
+}
+
+impl Default for PlayTimerNode {
+    fn default() -> Self {
+        Self {
+            brazil: BrazilClient::from_env(),
+        }
+    }
+}
+
+#[async_trait]
+impl Node for PlayTimerNode {
+    type Input = TriggerInput;
+    type Output = TriggerOutput;
+
+    async fn process(&self, input: Self::Input) -> Result<Self::Output, AppError> {
+        let (workspace, play_node_id) = input.target()?;
+        let run = self.brazil.trigger_run(workspace, play_node_id, None).await?;
+        Ok(TriggerOutput::from(&run))
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), AppError> {
+    ndnm_core::run_node(
+        PlayTimerNode::default(),
+        "node-play-timer",
+        "Timer Play node (interval or cron schedule managed by ndnm-brazil)",
+        env!("CARGO_MANIFEST_DIR"),
+    )
+    .await
+}
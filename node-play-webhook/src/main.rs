@@ -0,0 +1,93 @@
+// node-play-webhook/src/main.rs
+use ndnm_core::{async_trait, AppError, BrazilClient, Node, TriggerInput, TriggerOutput};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Input para o Play Webhook
+/// A rota de verdade é o POST /hooks/{workspace}/{play_id} do Brazil; o /run aqui
+/// só chama essa rota (útil pra disparar de outro node ou de script com o mesmo contrato).
+#[derive(Debug, Deserialize)]
+pub struct Input {
+    #[serde(flatten)]
+    trigger: TriggerInput,
+    /// Body enviado pro webhook (vira o output do Play)
+    #[serde(default)]
+    payload: Value,
+    /// Segredo do webhook, se o Play tiver um configurado
+    #[serde(default)]
+    secret: Option<String>,
+}
+
+/// Output do Play Webhook
+/// `status` é "started" (webhook responde na hora) ou o status final do run
+#[derive(Debug, Serialize)]
+pub struct Output {
+    #[serde(flatten)]
+    run: TriggerOutput,
+    /// Outputs do fim da cascata (só quando o webhook espera o run terminar)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    outputs: Option<Value>,
+}
+
+/// Node Play Webhook - O Play que atende telefone 🪝
+///
+/// - Input (executa a cadeia ligada nele, igual o Play Button)
+/// - Output = body do request (desce pros Plays em cascata)
+/// - Segredo opcional no header e resposta imediata ou no fim do run (configurados no data do node)
+pub struct PlayWebhookNode {
+    brazil: BrazilClient,
+}
+
+impl PlayWebhookNode {
+    pub fn new(brazil_url: impl Into<String>) -> Self {
+        Self {
+            brazil: BrazilClient::new(brazil_url),
+        }
+    }
+}
+
+impl Default for PlayWebhookNode {
+    fn default() -> Self {
+        Self {
+            brazil: BrazilClient::from_env(),
+        }
+    }
+}
+
+#[async_trait]
+impl Node for PlayWebhookNode {
+    type Input = Input;
+    type Output = Output;
+
+    async fn process(&self, input: Self::Input) -> Result<Self::Output, AppError> {
+        let (workspace, play_node_id) = input.trigger.target()?;
+        let hook = self
+            .brazil
+            .trigger_hook(workspace, play_node_id, &input.payload, input.secret.as_deref())
+            .await?;
+
+        let message = hook
+            .error_message()
+            .unwrap_or_else(|| format!("Webhook run {}", hook.status));
+
+        Ok(Output {
+            run: TriggerOutput {
+                status: hook.status,
+                message,
+                run_id: Some(hook.run_id),
+            },
+            outputs: hook.outputs,
+        })
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), AppError> {
+    ndnm_core::run_node(
+        PlayWebhookNode::default(),
+        "node-play-webhook",
+        "Webhook Play node (POST /hooks/{workspace}/{play_id} on ndnm-brazil)",
+        env!("CARGO_MANIFEST_DIR"),
+    )
+    .await
+}